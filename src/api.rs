@@ -0,0 +1,683 @@
+//! Local admin HTTP API for the `edgli` binary.
+//!
+//! [`router`] exposes the [`EdgeNodeApi`] surface plus the capacity and balance views of
+//! [`AdminNode`] as JSON under `/v1`. Every route requires `Authorization: Bearer <token>`;
+//! tokens are compared in constant time. [`bind`] binds the router to a loopback TCP address
+//! or a Unix socket ([`ApiListen`]) — the API is meant for tooling on the same host, never for
+//! exposure to a network.
+//!
+//! Balances and addresses are rendered with their `Display` form (`"1.5 wxHOPR"`,
+//! `"0x…"`), which is also what the request bodies accept.
+//!
+//! The unauthenticated health probes live in [`crate::probes`], which is built without this
+//! feature; their items are re-exported here.
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use axum::{
+    Json, Router,
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use hopr_lib::api::types::{
+    internal::channels::ChannelEntry,
+    primitive::prelude::{Address, HoprBalance},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{EdgliError, Result};
+use crate::strategy::{
+    BalanceRecommendation, Capacity, CapacityAllocations, IncentiveConfiguration,
+};
+use crate::traits::{EdgeNodeApi, NodeBalances};
+
+pub use crate::probes::{ApiListen, HealthDto, LivenessDto, bind, health_router};
+
+/// The node views the admin API serves on top of [`EdgeNodeApi`].
+#[async_trait::async_trait]
+pub trait AdminNode: EdgeNodeApi {
+    /// See [`crate::Edgli::describe_current_capacity_allocations`].
+    async fn capacity_allocations(&self) -> anyhow::Result<CapacityAllocations>;
+
+    /// See [`crate::Edgli::ideal_balance_recommendation`].
+    async fn ideal_balance_recommendation(
+        &self,
+        cfg: &IncentiveConfiguration,
+    ) -> anyhow::Result<BalanceRecommendation>;
+}
+
+#[async_trait::async_trait]
+impl AdminNode for crate::Edgli {
+    async fn capacity_allocations(&self) -> anyhow::Result<CapacityAllocations> {
+        self.describe_current_capacity_allocations().await
+    }
+
+    async fn ideal_balance_recommendation(
+        &self,
+        cfg: &IncentiveConfiguration,
+    ) -> anyhow::Result<BalanceRecommendation> {
+        crate::Edgli::ideal_balance_recommendation(self, cfg).await
+    }
+}
+
+/// Shared state of the admin API router.
+#[derive(Clone)]
+pub struct AdminState {
+    node: Arc<dyn AdminNode>,
+    token: Arc<str>,
+    incentives: Arc<RwLock<IncentiveConfiguration>>,
+}
+
+impl AdminState {
+    /// Serves `node`, admitting requests that present `token`.
+    ///
+    /// `incentives` sizes `/v1/balance-recommendation`; keep it in step with the running
+    /// reactor through [`Self::set_incentives`].
+    pub fn new(
+        node: Arc<dyn AdminNode>,
+        token: impl Into<String>,
+        incentives: IncentiveConfiguration,
+    ) -> Result<Self> {
+        let token = token.into();
+        if token.is_empty() {
+            return Err(EdgliError::ValidationError(
+                "the admin API token must not be empty".into(),
+            ));
+        }
+        Ok(Self {
+            node,
+            token: token.into(),
+            incentives: Arc::new(RwLock::new(incentives)),
+        })
+    }
+
+    /// Replaces the configuration `/v1/balance-recommendation` is sized for.
+    pub fn set_incentives(&self, incentives: IncentiveConfiguration) {
+        *self.incentives.write().unwrap_or_else(|e| e.into_inner()) = incentives;
+    }
+}
+
+/// Compares two byte strings in time independent of where they first differ.
+fn tokens_match(presented: &[u8], expected: &[u8]) -> bool {
+    if presented.len() != expected.len() {
+        return false;
+    }
+    presented
+        .iter()
+        .zip(expected)
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+async fn require_token(State(state): State<AdminState>, request: Request, next: Next) -> Response {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match presented {
+        Some(token) if tokens_match(token.as_bytes(), state.token.as_bytes()) => {
+            next.run(request).await
+        }
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(ErrorDto {
+                error: "missing or invalid bearer token".into(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Body of every error response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ErrorDto {
+    pub error: String,
+}
+
+/// An error rendered as an [`ErrorDto`] with its status code.
+#[derive(Debug)]
+struct ApiError(StatusCode, String);
+
+impl ApiError {
+    fn internal(e: impl fmt::Display) -> Self {
+        Self(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    }
+
+    fn bad_request(e: impl fmt::Display) -> Self {
+        Self(StatusCode::BAD_REQUEST, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(ErrorDto { error: self.1 })).into_response()
+    }
+}
+
+type ApiResult<T> = std::result::Result<Json<T>, ApiError>;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StatusDto {
+    pub state: String,
+    pub node_address: String,
+    pub safe_address: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BalancesDto {
+    pub node_wxhopr: String,
+    pub safe_wxhopr: String,
+    pub node_xdai: String,
+}
+
+impl From<NodeBalances> for BalancesDto {
+    fn from(balances: NodeBalances) -> Self {
+        Self {
+            node_wxhopr: balances.node_wxhopr.to_string(),
+            safe_wxhopr: balances.safe_wxhopr.to_string(),
+            node_xdai: balances.node_xdai.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChannelDto {
+    pub destination: String,
+    pub balance: String,
+    pub status: String,
+}
+
+impl From<&ChannelEntry> for ChannelDto {
+    fn from(channel: &ChannelEntry) -> Self {
+        Self {
+            destination: channel.destination.to_string(),
+            balance: channel.balance.to_string(),
+            status: channel.status.to_string(),
+        }
+    }
+}
+
+/// Body of `POST /v1/channels/outgoing`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OpenChannelDto {
+    /// Counterparty address, `0x…`.
+    pub destination: String,
+    /// Stake, e.g. `"10 wxHOPR"`.
+    pub amount: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CapacityDto {
+    pub stake: String,
+    pub expected_messages: u64,
+    pub min_guaranteed_messages: u64,
+    pub byte_capacity: u64,
+}
+
+impl From<&Capacity> for CapacityDto {
+    fn from(capacity: &Capacity) -> Self {
+        Self {
+            stake: capacity.stake.to_string(),
+            expected_messages: capacity.expected_messages,
+            min_guaranteed_messages: capacity.min_guaranteed_messages,
+            byte_capacity: capacity.byte_capacity,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CapacityAllocationsDto {
+    /// Keyed by destination address.
+    pub peer_allocations: std::collections::BTreeMap<String, CapacityDto>,
+    pub node: CapacityDto,
+    pub safe: CapacityDto,
+}
+
+impl From<&CapacityAllocations> for CapacityAllocationsDto {
+    fn from(allocations: &CapacityAllocations) -> Self {
+        Self {
+            peer_allocations: allocations
+                .peer_allocations
+                .iter()
+                .map(|(peer, capacity)| (peer.to_string(), capacity.into()))
+                .collect(),
+            node: (&allocations.node).into(),
+            safe: (&allocations.safe).into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BalanceRecommendationDto {
+    pub channel_stakes: String,
+    pub fee_to_start: String,
+    pub total_wxhopr: String,
+    pub txs_to_start: u64,
+    pub xdai_fee_per_tx: String,
+    pub xdai_minimum: String,
+    pub xdai_fund_amount: String,
+}
+
+impl From<&BalanceRecommendation> for BalanceRecommendationDto {
+    fn from(recommendation: &BalanceRecommendation) -> Self {
+        Self {
+            channel_stakes: recommendation.channel_stakes.to_string(),
+            fee_to_start: recommendation.fee_to_start.to_string(),
+            total_wxhopr: recommendation.total_wxhopr().to_string(),
+            txs_to_start: recommendation.txs_to_start,
+            xdai_fee_per_tx: recommendation.xdai_fee_per_tx.to_string(),
+            xdai_minimum: recommendation.xdai_minimum.to_string(),
+            xdai_fund_amount: recommendation.xdai_fund_amount.to_string(),
+        }
+    }
+}
+
+async fn status(State(state): State<AdminState>) -> Json<StatusDto> {
+    Json(StatusDto {
+        state: state.node.status().to_string(),
+        node_address: state.node.me_onchain().to_string(),
+        safe_address: state.node.safe_address().to_string(),
+    })
+}
+
+async fn balances(State(state): State<AdminState>) -> ApiResult<BalancesDto> {
+    let balances = state.node.balances().await.map_err(ApiError::internal)?;
+    Ok(Json(balances.into()))
+}
+
+async fn outgoing_channels(State(state): State<AdminState>) -> ApiResult<Vec<ChannelDto>> {
+    let channels = state
+        .node
+        .my_outgoing_channels()
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json(channels.iter().map(ChannelDto::from).collect()))
+}
+
+async fn open_channel(
+    State(state): State<AdminState>,
+    Json(body): Json<OpenChannelDto>,
+) -> std::result::Result<StatusCode, ApiError> {
+    let destination = Address::from_str(&body.destination)
+        .map_err(|e| ApiError::bad_request(format!("invalid destination: {e}")))?;
+    let amount = HoprBalance::from_str(&body.amount)
+        .map_err(|e| ApiError::bad_request(format!("invalid amount: {e}")))?;
+
+    state
+        .node
+        .open_channel(destination, amount)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(StatusCode::CREATED)
+}
+
+async fn connected_peers(State(state): State<AdminState>) -> ApiResult<Vec<String>> {
+    let peers = state
+        .node
+        .connected_peer_addresses()
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json(peers.iter().map(Address::to_string).collect()))
+}
+
+async fn capacity(State(state): State<AdminState>) -> ApiResult<CapacityAllocationsDto> {
+    let allocations = state
+        .node
+        .capacity_allocations()
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json((&allocations).into()))
+}
+
+async fn balance_recommendation(
+    State(state): State<AdminState>,
+) -> ApiResult<BalanceRecommendationDto> {
+    let incentives = state
+        .incentives
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone();
+    let recommendation = state
+        .node
+        .ideal_balance_recommendation(&incentives)
+        .await
+        .map_err(ApiError::internal)?;
+    Ok(Json((&recommendation).into()))
+}
+
+/// Builds the admin API router; every route requires the state's bearer token.
+pub fn router(state: AdminState) -> Router {
+    Router::new()
+        .route("/v1/status", get(status))
+        .route("/v1/balances", get(balances))
+        .route(
+            "/v1/channels/outgoing",
+            get(outgoing_channels).post(open_channel),
+        )
+        .route("/v1/peers", get(connected_peers))
+        .route("/v1/capacity", get(capacity))
+        .route("/v1/balance-recommendation", get(balance_recommendation))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use hopr_lib::{
+        api::{chain::ChainReceipt, node::HoprState, types::primitive::prelude::XDaiBalance},
+        errors::HoprLibError,
+    };
+    use tower::ServiceExt as _;
+
+    use super::*;
+    use crate::health::HealthMonitor;
+    use crate::traits::TransactionHandle;
+
+    const TOKEN: &str = "correct horse battery staple";
+
+    #[derive(Default)]
+    struct StubAdminNode {
+        opened: std::sync::Mutex<Vec<(Address, HoprBalance)>>,
+    }
+
+    fn capacity() -> Capacity {
+        Capacity {
+            stake: HoprBalance::zero(),
+            expected_messages: 0,
+            min_guaranteed_messages: 0,
+            byte_capacity: 0,
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl EdgeNodeApi for StubAdminNode {
+        fn me_onchain(&self) -> Address {
+            [0x11u8; 20].into()
+        }
+
+        fn safe_address(&self) -> Address {
+            [0x22u8; 20].into()
+        }
+
+        fn status(&self) -> HoprState {
+            HoprState::Running
+        }
+
+        async fn balances(&self) -> std::result::Result<NodeBalances, HoprLibError> {
+            Ok(NodeBalances {
+                node_wxhopr: HoprBalance::zero(),
+                safe_wxhopr: HoprBalance::zero(),
+                node_xdai: XDaiBalance::zero(),
+            })
+        }
+
+        async fn my_outgoing_channels(
+            &self,
+        ) -> std::result::Result<Vec<ChannelEntry>, HoprLibError> {
+            Ok(vec![])
+        }
+
+        async fn open_channel(
+            &self,
+            target: Address,
+            amount: HoprBalance,
+        ) -> std::result::Result<(), HoprLibError> {
+            self.opened.lock().unwrap().push((target, amount));
+            Ok(())
+        }
+
+        async fn submit_open_channel(
+            &self,
+            target: Address,
+            amount: HoprBalance,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            self.open_channel(target, amount).await?;
+            Ok(TransactionHandle::confirmed(ChainReceipt::default()))
+        }
+
+        async fn fund_channel(
+            &self,
+            _destination: Address,
+            _amount: HoprBalance,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            Err(HoprLibError::GeneralError("not used".into()))
+        }
+
+        async fn close_channel(
+            &self,
+            _destination: Address,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            Err(HoprLibError::GeneralError("not used".into()))
+        }
+
+        async fn finalize_closure(
+            &self,
+            _destination: Address,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            Err(HoprLibError::GeneralError("not used".into()))
+        }
+
+        async fn withdraw(
+            &self,
+            _recipient: Address,
+            _amount: HoprBalance,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            Err(HoprLibError::GeneralError("not used".into()))
+        }
+
+        fn events(&self) -> futures::stream::BoxStream<'static, crate::events::EdgeEvent> {
+            Box::pin(futures::stream::empty())
+        }
+
+        async fn open_session(
+            &self,
+            _destination: Address,
+            _target: hopr_lib::exports::transport::SessionTarget,
+            _cfg: hopr_lib::exports::transport::HoprSessionClientConfig,
+        ) -> std::result::Result<Box<dyn crate::session::EdgeSession>, HoprLibError> {
+            Err(HoprLibError::GeneralError("not used".into()))
+        }
+
+        fn sessions(&self) -> Vec<crate::session::SessionInfo> {
+            vec![]
+        }
+
+        fn close_session(
+            &self,
+            _id: crate::session::EdgeSessionId,
+        ) -> std::result::Result<(), HoprLibError> {
+            Err(HoprLibError::GeneralError("not used".into()))
+        }
+
+        async fn connected_peer_addresses(
+            &self,
+        ) -> std::result::Result<Vec<Address>, HoprLibError> {
+            Err(HoprLibError::GeneralError("transport is down".into()))
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl AdminNode for StubAdminNode {
+        async fn capacity_allocations(&self) -> anyhow::Result<CapacityAllocations> {
+            Ok(CapacityAllocations {
+                peer_allocations: Default::default(),
+                node: capacity(),
+                safe: capacity(),
+            })
+        }
+
+        async fn ideal_balance_recommendation(
+            &self,
+            _cfg: &IncentiveConfiguration,
+        ) -> anyhow::Result<BalanceRecommendation> {
+            anyhow::bail!("not used")
+        }
+    }
+
+    fn app(node: Arc<StubAdminNode>) -> Router {
+        router(AdminState::new(node, TOKEN, IncentiveConfiguration::default()).unwrap())
+    }
+
+    fn get_request(uri: &str, token: Option<&str>) -> Request {
+        let mut builder = axum::http::Request::builder().uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    async fn json<T: serde::de::DeserializeOwned>(response: Response) -> T {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn tokens_match_only_identical_tokens() {
+        assert!(tokens_match(b"secret", b"secret"));
+        assert!(!tokens_match(b"secreT", b"secret"));
+        assert!(!tokens_match(b"secret-but-longer", b"secret"));
+        assert!(!tokens_match(b"", b"secret"));
+    }
+
+    #[test]
+    fn empty_token_is_rejected() {
+        let state = AdminState::new(
+            Arc::new(StubAdminNode::default()),
+            "",
+            IncentiveConfiguration::default(),
+        );
+        assert!(matches!(state, Err(EdgliError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn requests_without_the_token_are_unauthorized() {
+        let app = app(Arc::new(StubAdminNode::default()));
+        for token in [None, Some("wrong")] {
+            let response = app
+                .clone()
+                .oneshot(get_request("/v1/status", token))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert!(response.headers().contains_key(header::WWW_AUTHENTICATE));
+        }
+    }
+
+    #[tokio::test]
+    async fn status_reports_the_node_identity() {
+        let response = app(Arc::new(StubAdminNode::default()))
+            .oneshot(get_request("/v1/status", Some(TOKEN)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let status: StatusDto = json(response).await;
+        assert_eq!(status.state, HoprState::Running.to_string());
+        assert_eq!(status.node_address, Address::from([0x11u8; 20]).to_string());
+    }
+
+    #[tokio::test]
+    async fn open_channel_parses_the_body() {
+        let node = Arc::new(StubAdminNode::default());
+        let destination = Address::from([0x33u8; 20]);
+        let body = serde_json::to_vec(&OpenChannelDto {
+            destination: destination.to_string(),
+            amount: "10 wxHOPR".into(),
+        })
+        .unwrap();
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/v1/channels/outgoing")
+            .header(header::AUTHORIZATION, format!("Bearer {TOKEN}"))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap();
+
+        let response = app(node.clone()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(
+            *node.opened.lock().unwrap(),
+            vec![(destination, "10 wxHOPR".parse().unwrap())]
+        );
+    }
+
+    #[tokio::test]
+    async fn open_channel_rejects_an_invalid_destination() {
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/v1/channels/outgoing")
+            .header(header::AUTHORIZATION, format!("Bearer {TOKEN}"))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                r#"{"destination":"not-an-address","amount":"1 wxHOPR"}"#,
+            ))
+            .unwrap();
+
+        let response = app(Arc::new(StubAdminNode::default()))
+            .oneshot(request)
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn node_errors_surface_as_internal_errors() {
+        let response = app(Arc::new(StubAdminNode::default()))
+            .oneshot(get_request("/v1/peers", Some(TOKEN)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let error: ErrorDto = json(response).await;
+        assert!(error.error.contains("transport is down"), "{error:?}");
+    }
+
+    #[tokio::test]
+    async fn capacity_is_rendered() {
+        let response = app(Arc::new(StubAdminNode::default()))
+            .oneshot(get_request("/v1/capacity", Some(TOKEN)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let allocations: CapacityAllocationsDto = json(response).await;
+        assert!(allocations.peer_allocations.is_empty());
+        assert_eq!(allocations.node.byte_capacity, 0);
+    }
+
+    #[async_trait::async_trait]
+    impl crate::health::HealthProbe for StubAdminNode {
+        async fn blokli_reachable(&self) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn readyz_reports_the_attached_node() {
+        let monitor = HealthMonitor::default();
+        monitor.record(crate::EdgliInitState::Ready);
+        monitor.attach(Arc::new(StubAdminNode::default()));
+
+        let response = health_router(monitor)
+            .oneshot(get_request("/readyz", None))
+            .await
+            .unwrap();
+        // The stub's peer query fails, so the peer count is unknown.
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let health: HealthDto = json(response).await;
+        assert_eq!(health.blokli_reachable, Some(true));
+        assert_eq!(health.connected_peers, None);
+        assert_eq!(health.funded_open_channels, Some(0));
+        assert_eq!(health.unmet.len(), 1, "{health:?}");
+    }
+}
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use futures::future::BoxFuture;
 use hopr_chain_connector::{
     BlockchainConnectorConfig, HoprBlockchainBasicConnector,
     blokli_client::{
@@ -22,7 +23,9 @@ use hopr_lib::{
 };
 use url::Url;
 
-use crate::endpoint::BlokliEndpoint;
+use crate::endpoint::{BlokliEndpoint, BlokliEndpointSet, BlokliFailover};
+use crate::proxy::Forwarder;
+use crate::retry::{ErrorClass, RetryPolicy, classify};
 
 pub use hopr_lib::builder::ChainKeypair;
 
@@ -55,10 +58,14 @@ pub(crate) fn resolve_max_fee_per_gas(
 /// reports no gas price falls back per [`resolve_max_fee_per_gas`].
 pub(crate) async fn query_max_fee_per_gas<C: BlokliQueryClient>(
     client: &C,
+    endpoint: String,
 ) -> anyhow::Result<u128> {
-    let info = client.query_chain_info().await?;
+    let info =
+        crate::metrics::blokli_request("query_chain_info", endpoint, client.query_chain_info())
+            .await?;
     let resolved =
         resolve_max_fee_per_gas(info.max_fee_per_gas.as_deref(), info.gas_price.as_deref());
+    crate::metrics::record_max_fee_per_gas(resolved);
     tracing::debug!(
         max_fee_per_gas = ?info.max_fee_per_gas,
         gas_price = ?info.gas_price,
@@ -76,12 +83,17 @@ pub(crate) async fn query_max_fee_per_gas<C: BlokliQueryClient>(
 ///
 /// The endpoint's DNS override is honoured, so on-boarding works in environments
 /// where system DNS cannot resolve the Blokli host.
+///
+/// Given a [`BlokliEndpointSet`], the handle connects to the first endpoint that answers and
+/// fails over between them: queries move on to the next endpoint when one fails, and
+/// transactions are submitted once, through the preferred endpoint that connects.
 pub async fn make_incentive_operations(
-    blokli_endpoint: BlokliEndpoint,
+    blokli_endpoints: impl Into<BlokliEndpointSet>,
     chain_key: &ChainKeypair,
     connector_config: Option<BlockchainConnectorConfig>,
 ) -> anyhow::Result<Box<dyn IncentiveOperations>> {
-    let interactor = SafelessInteractor::new(blokli_endpoint, chain_key, connector_config).await?;
+    let interactor =
+        FailoverInteractor::new(blokli_endpoints.into(), chain_key, connector_config).await?;
     Ok(Box::new(interactor))
 }
 
@@ -144,7 +156,12 @@ pub trait IncentiveOperations: Send + Sync {
 /// callers obtain an [`IncentiveOperations`] handle via [`make_incentive_operations`].
 pub(crate) struct SafelessInteractor<C = BlokliClient> {
     connector: Arc<HoprBlockchainBasicConnector<C>>,
+    /// The endpoint's metrics label.
+    endpoint: String,
     chain_key: ChainKeypair,
+    retry: RetryPolicy,
+    /// Keeps the client's local forwarder running, when it has one.
+    _forwarder: Option<Forwarder>,
 }
 
 impl SafelessInteractor<BlokliClient> {
@@ -153,7 +170,18 @@ impl SafelessInteractor<BlokliClient> {
         chain_key: &ChainKeypair,
         connector_config: Option<BlockchainConnectorConfig>,
     ) -> anyhow::Result<Self> {
-        Self::new_with_client(blokli_endpoint.build_client(), chain_key, connector_config).await
+        let built = blokli_endpoint.build_client().await?;
+        let interactor = Self::new_with_client(
+            built.client,
+            blokli_endpoint.metrics_label(),
+            chain_key,
+            connector_config,
+        )
+        .await?;
+        Ok(Self {
+            _forwarder: built.forwarder,
+            ..interactor
+        })
     }
 }
 
@@ -168,6 +196,7 @@ where
 {
     pub(crate) async fn new_with_client(
         client: C,
+        endpoint: String,
         chain_key: &ChainKeypair,
         connector_config: Option<BlockchainConnectorConfig>,
     ) -> anyhow::Result<Self> {
@@ -178,10 +207,19 @@ where
 
         Ok(Self {
             connector: Arc::new(connector),
+            endpoint,
             chain_key: chain_key.clone(),
+            retry: RetryPolicy::default(),
+            _forwarder: None,
         })
     }
 
+    /// Sets how reads are retried; transactions are never retried.
+    pub(crate) fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     #[tracing::instrument(skip(self), ret)]
     pub async fn retrieve_safe(&self) -> anyhow::Result<Option<SafeModuleDeploymentResult>> {
         let me = self.chain_key.public().to_address();
@@ -195,12 +233,22 @@ where
         }
     }
 
+    /// Deploys the Safe unless one exists already.
+    ///
+    /// The submission is never retried: a request that failed may still have been broadcast.
+    /// When it failed transiently, the deployment event is awaited anyway, so a deployment
+    /// that went through is reported as such and a retry by the caller finds the Safe instead
+    /// of deploying a second one.
     #[tracing::instrument(skip(self), ret)]
     pub async fn deploy_safe(
         &self,
         token_amount: HoprBalance,
     ) -> anyhow::Result<SafeModuleDeploymentResult> {
-        if let Some(safe_info) = self.retrieve_safe().await? {
+        if let Some(safe_info) = self
+            .retry
+            .retry("retrieve_safe", || self.retrieve_safe())
+            .await?
+        {
             tracing::debug!(?safe_info, "safe already deployed");
             return Ok(safe_info);
         }
@@ -215,8 +263,28 @@ where
                 .await
         });
 
-        let tx_hash = self.connector.deploy_safe(token_amount).await?.await?;
-        tracing::debug!(%tx_hash, "safe deployment transaction submitted");
+        let submitted =
+            async { anyhow::Ok(self.connector.deploy_safe(token_amount).await?.await?) };
+        match submitted.await {
+            Ok(tx_hash) => tracing::debug!(%tx_hash, "safe deployment transaction submitted"),
+            Err(error) if classify(&error) != ErrorClass::Other => {
+                tracing::warn!(
+                    error = format!("{error:#}"),
+                    "safe deployment submission failed transiently, awaiting the deployment in case it went through"
+                );
+                return match subscription_handle.await {
+                    Ok(Ok(safe)) => Ok(SafeModuleDeploymentResult {
+                        safe_address: safe.address,
+                        module_address: safe.module,
+                    }),
+                    _ => Err(error),
+                };
+            }
+            Err(error) => {
+                subscription_handle.abort();
+                return Err(error);
+            }
+        }
 
         let safe = subscription_handle
             .await
@@ -253,7 +321,7 @@ where
     }
 
     pub async fn max_fee_per_gas(&self) -> anyhow::Result<u128> {
-        query_max_fee_per_gas(self.connector.client()).await
+        query_max_fee_per_gas(self.connector.client(), self.endpoint.clone()).await
     }
 
     pub async fn compute_costs_to_start(&self) -> anyhow::Result<crate::strategy::StartupCosts> {
@@ -286,30 +354,71 @@ where
         + 'static,
 {
     async fn retrieve_safe(&self) -> anyhow::Result<Option<SafeModuleDeploymentResult>> {
-        SafelessInteractor::retrieve_safe(self).await
+        self.retry
+            .retry("retrieve_safe", || {
+                crate::metrics::blokli_request(
+                    "retrieve_safe",
+                    self.endpoint.clone(),
+                    SafelessInteractor::retrieve_safe(self),
+                )
+            })
+            .await
     }
 
     async fn deploy_safe(
         &self,
         token_amount: HoprBalance,
     ) -> anyhow::Result<SafeModuleDeploymentResult> {
-        SafelessInteractor::deploy_safe(self, token_amount).await
+        crate::metrics::blokli_request(
+            "deploy_safe",
+            self.endpoint.clone(),
+            SafelessInteractor::deploy_safe(self, token_amount),
+        )
+        .await
     }
 
     async fn ticket_stats(&self) -> anyhow::Result<TicketStats> {
-        SafelessInteractor::ticket_stats(self).await
+        self.retry
+            .retry("ticket_stats", || {
+                crate::metrics::blokli_request(
+                    "ticket_stats",
+                    self.endpoint.clone(),
+                    SafelessInteractor::ticket_stats(self),
+                )
+            })
+            .await
     }
 
     async fn max_fee_per_gas(&self) -> anyhow::Result<u128> {
-        SafelessInteractor::max_fee_per_gas(self).await
+        self.retry
+            .retry("max_fee_per_gas", || {
+                SafelessInteractor::max_fee_per_gas(self)
+            })
+            .await
     }
 
     async fn compute_costs_to_start(&self) -> anyhow::Result<crate::strategy::StartupCosts> {
-        SafelessInteractor::compute_costs_to_start(self).await
+        self.retry
+            .retry("compute_costs_to_start", || {
+                crate::metrics::blokli_request(
+                    "compute_costs_to_start",
+                    self.endpoint.clone(),
+                    SafelessInteractor::compute_costs_to_start(self),
+                )
+            })
+            .await
     }
 
     async fn balances(&self) -> anyhow::Result<(HoprBalance, XDaiBalance)> {
-        SafelessInteractor::balances(self).await
+        self.retry
+            .retry("balances", || {
+                crate::metrics::blokli_request(
+                    "balances",
+                    self.endpoint.clone(),
+                    SafelessInteractor::balances(self),
+                )
+            })
+            .await
     }
 
     async fn withdraw_wxhopr(
@@ -317,7 +426,183 @@ where
         safe_address: Address,
         amount: HoprBalance,
     ) -> anyhow::Result<()> {
-        SafelessInteractor::withdraw_wxhopr(self, safe_address, amount).await
+        crate::metrics::blokli_request(
+            "withdraw_wxhopr",
+            self.endpoint.clone(),
+            SafelessInteractor::withdraw_wxhopr(self, safe_address, amount),
+        )
+        .await
+    }
+}
+
+/// [`IncentiveOperations`] over several Blokli endpoints, each connected on first use.
+///
+/// An endpoint's connection is dropped when a request through it fails to connect or times
+/// out, and made again on its next use, so a Blokli that restarted is reached afresh.
+pub(crate) struct FailoverInteractor {
+    failover: BlokliFailover,
+    chain_key: ChainKeypair,
+    connector_config: Option<BlockchainConnectorConfig>,
+    interactors: Vec<tokio::sync::Mutex<Option<Arc<SafelessInteractor>>>>,
+}
+
+/// Whether `error` means the connection to an endpoint is unusable and must be made anew.
+fn needs_reconnect(error: &anyhow::Error) -> bool {
+    matches!(
+        classify(error),
+        ErrorClass::Connection | ErrorClass::Timeout
+    )
+}
+
+impl FailoverInteractor {
+    /// Connects to the first endpoint that answers, so an unreachable set fails here.
+    pub(crate) async fn new(
+        endpoints: BlokliEndpointSet,
+        chain_key: &ChainKeypair,
+        connector_config: Option<BlockchainConnectorConfig>,
+    ) -> anyhow::Result<Self> {
+        let interactor = Self {
+            failover: endpoints.failover(),
+            chain_key: chain_key.clone(),
+            connector_config,
+            interactors: endpoints
+                .endpoints()
+                .iter()
+                .map(|_| tokio::sync::Mutex::default())
+                .collect(),
+        };
+        interactor.connected().await?;
+        Ok(interactor)
+    }
+
+    /// The connection to the endpoint at `index`, made now unless one is open.
+    async fn interactor(&self, index: usize) -> anyhow::Result<Arc<SafelessInteractor>> {
+        let mut slot = self.interactors[index].lock().await;
+        if let Some(interactor) = slot.as_ref() {
+            return Ok(interactor.clone());
+        }
+        let interactor = Arc::new(
+            SafelessInteractor::new(
+                self.failover.endpoint(index).clone(),
+                &self.chain_key,
+                self.connector_config.clone(),
+            )
+            .await?
+            .with_retry(self.failover.retry().clone()),
+        );
+        *slot = Some(interactor.clone());
+        Ok(interactor)
+    }
+
+    /// Records that a request to the endpoint at `index` failed with `error`, dropping its
+    /// connection if the error calls for it.
+    async fn record_failure(&self, index: usize, error: &anyhow::Error) {
+        self.failover.record_failure(index);
+        if needs_reconnect(error) {
+            self.interactors[index].lock().await.take();
+        }
+    }
+
+    /// The most preferred endpoint that connects, with its index.
+    async fn connected(&self) -> anyhow::Result<(usize, Arc<SafelessInteractor>)> {
+        let mut last_error = None;
+        for index in self.failover.candidates() {
+            match self.interactor(index).await {
+                Ok(interactor) => {
+                    self.failover.record_success(index);
+                    return Ok((index, interactor));
+                }
+                Err(error) => {
+                    self.failover.record_failure(index);
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("no Blokli endpoint configured")))
+    }
+
+    /// Submits the transaction `op` once, through the most preferred endpoint that connects.
+    async fn transact<T: Send>(
+        &self,
+        op: impl for<'a> FnOnce(&'a SafelessInteractor) -> BoxFuture<'a, anyhow::Result<T>> + Send,
+    ) -> anyhow::Result<T> {
+        let (index, interactor) = self.connected().await?;
+        let outcome = op(&interactor).await;
+        if let Err(error) = &outcome
+            && needs_reconnect(error)
+        {
+            self.interactors[index].lock().await.take();
+        }
+        outcome
+    }
+
+    /// Runs the query `op` against each endpoint in turn until one answers.
+    async fn query<T: Send>(
+        &self,
+        op: impl for<'a> Fn(&'a SafelessInteractor) -> BoxFuture<'a, anyhow::Result<T>> + Send + Sync,
+    ) -> anyhow::Result<T> {
+        let mut last_error = None;
+        for index in self.failover.candidates() {
+            let outcome = match self.interactor(index).await {
+                Ok(interactor) => op(&interactor).await,
+                Err(error) => Err(error),
+            };
+            match outcome {
+                Ok(value) => {
+                    self.failover.record_success(index);
+                    return Ok(value);
+                }
+                Err(error) => {
+                    self.record_failure(index, &error).await;
+                    last_error = Some(error);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("no Blokli endpoint configured")))
+    }
+}
+
+/// Each endpoint's [`SafelessInteractor`] retries and records its own requests, so failover
+/// only moves on once an endpoint exhausted its retries.
+#[async_trait::async_trait]
+impl IncentiveOperations for FailoverInteractor {
+    async fn retrieve_safe(&self) -> anyhow::Result<Option<SafeModuleDeploymentResult>> {
+        self.query(|i| IncentiveOperations::retrieve_safe(i)).await
+    }
+
+    async fn deploy_safe(
+        &self,
+        token_amount: HoprBalance,
+    ) -> anyhow::Result<SafeModuleDeploymentResult> {
+        self.transact(|i| IncentiveOperations::deploy_safe(i, token_amount))
+            .await
+    }
+
+    async fn ticket_stats(&self) -> anyhow::Result<TicketStats> {
+        self.query(|i| IncentiveOperations::ticket_stats(i)).await
+    }
+
+    async fn max_fee_per_gas(&self) -> anyhow::Result<u128> {
+        self.query(|i| IncentiveOperations::max_fee_per_gas(i))
+            .await
+    }
+
+    async fn compute_costs_to_start(&self) -> anyhow::Result<crate::strategy::StartupCosts> {
+        self.query(|i| IncentiveOperations::compute_costs_to_start(i))
+            .await
+    }
+
+    async fn balances(&self) -> anyhow::Result<(HoprBalance, XDaiBalance)> {
+        self.query(|i| IncentiveOperations::balances(i)).await
+    }
+
+    async fn withdraw_wxhopr(
+        &self,
+        safe_address: Address,
+        amount: HoprBalance,
+    ) -> anyhow::Result<()> {
+        self.transact(|i| IncentiveOperations::withdraw_wxhopr(i, safe_address, amount))
+            .await
     }
 }
 
@@ -425,7 +710,9 @@ mod tests {
         let recipient: Address = [0x22u8; 20].into();
 
         let client = build_test_client(me, HoprBalance::new_base(1000), recipient);
-        let interactor = SafelessInteractor::new_with_client(client, &chain_key, None).await?;
+        let interactor =
+            SafelessInteractor::new_with_client(client, "emulator".into(), &chain_key, None)
+                .await?;
 
         interactor
             .withdraw_wxhopr(recipient, HoprBalance::new_base(10))
@@ -441,7 +728,9 @@ mod tests {
         let recipient: Address = [0x22u8; 20].into();
 
         let client = build_test_client(me, HoprBalance::new_base(1), recipient);
-        let interactor = SafelessInteractor::new_with_client(client, &chain_key, None).await?;
+        let interactor =
+            SafelessInteractor::new_with_client(client, "emulator".into(), &chain_key, None)
+                .await?;
 
         let err = interactor
             .withdraw_wxhopr(recipient, HoprBalance::new_base(10))
@@ -465,7 +754,9 @@ mod tests {
         let recipient: Address = [0x22u8; 20].into();
 
         let client = build_test_client(me, HoprBalance::new_base(100), recipient);
-        let interactor = SafelessInteractor::new_with_client(client, &chain_key, None).await?;
+        let interactor =
+            SafelessInteractor::new_with_client(client, "emulator".into(), &chain_key, None)
+                .await?;
 
         let costs = interactor.compute_costs_to_start().await?;
         assert_eq!(
@@ -492,7 +783,9 @@ mod tests {
             )
             .with_hopr_network_chain_info("anvil-localhost")
             .build_dynamic_client(placeholder_module_addr());
-        let interactor = SafelessInteractor::new_with_client(client, &chain_key, None).await?;
+        let interactor =
+            SafelessInteractor::new_with_client(client, "emulator".into(), &chain_key, None)
+                .await?;
 
         let costs = interactor.compute_costs_to_start().await?;
         assert_eq!(costs.fee_to_start, HoprBalance::zero());
@@ -502,6 +795,19 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn only_connection_failures_drop_the_connection() {
+        let refused =
+            anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::ConnectionRefused));
+        assert!(needs_reconnect(&refused));
+        let timed_out = anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        assert!(needs_reconnect(&timed_out.context("querying chain info")));
+        assert!(!needs_reconnect(&anyhow::anyhow!(
+            "503 Service Unavailable"
+        )));
+        assert!(!needs_reconnect(&anyhow::anyhow!("safe not found")));
+    }
+
     #[tokio::test]
     async fn new_with_client_yields_connected_interactor() -> anyhow::Result<()> {
         let chain_key = ChainKeypair::random();
@@ -509,7 +815,9 @@ mod tests {
         let recipient: Address = [0x22u8; 20].into();
 
         let client = build_test_client(me, HoprBalance::new_base(100), recipient);
-        let interactor = SafelessInteractor::new_with_client(client, &chain_key, None).await?;
+        let interactor =
+            SafelessInteractor::new_with_client(client, "emulator".into(), &chain_key, None)
+                .await?;
 
         interactor
             .withdraw_wxhopr(recipient, HoprBalance::new_base(1))
@@ -0,0 +1,392 @@
+//! Named-setter construction of an [`Edgli`] node.
+//!
+//! [`EdgliBuilder`] starts from the two inputs every node needs, the [`HoprLibConfig`] and
+//! the [`HoprKeys`], and defaults everything else. [`EdgliBuilder::build`] checks the whole
+//! configuration with [`EdgliBuilder::validate`] before anything touches the network, so a
+//! misconfiguration surfaces as its own [`EdgliError`] variant rather than as a failure
+//! halfway through startup.
+//!
+//! ```no_run
+//! # async fn run(cfg: edgli::hopr_lib::config::HoprLibConfig, keys: edgli::hopr_lib::HoprKeys) -> anyhow::Result<()> {
+//! use edgli::{Edgli, EdgliInitState};
+//!
+//! let edgli = Edgli::builder(cfg, keys)
+//!     .probe_local_addresses(true)
+//!     .visitor(|state: EdgliInitState| tracing::info!(?state, "init"))
+//!     .build()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::time::Duration;
+
+use hopr_chain_connector::BlockchainConnectorConfig;
+use hopr_ct_full_network::ProberConfig as FullNetworkProberConfig;
+use hopr_lib::{HoprKeys, config::HoprLibConfig};
+
+use crate::client::{Edgli, EdgliInitState};
+use crate::endpoint::{BlokliEndpoint, BlokliEndpointSet};
+use crate::errors::{EdgliError, Result};
+use crate::gas::GasCap;
+use crate::strategy::{IncentiveConfiguration, ReactorBackoff, default_strategy_cfg};
+
+/// The libp2p protocol the mixnet packets are exchanged over.
+pub const DEFAULT_MIX_PROTOCOL: &str = "/hopr/mix/1.1.0";
+
+/// How often a node compares its channels, balances and peers for
+/// [`crate::EdgeNodeApi::events`] unless [`EdgliBuilder::event_poll_interval`] says otherwise.
+pub const DEFAULT_EVENT_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Where the node's ticket factory takes its initial ticket indices and balances from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TicketFactorySource {
+    /// The chain connector's view of the node's channels; waits as long as Blokli takes.
+    #[default]
+    Chain,
+    /// As [`Self::Chain`], but startup fails if seeding takes longer than the given time.
+    ChainWithTimeout(Duration),
+}
+
+/// How the node generates cover traffic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CoverTrafficPolicy {
+    /// Probe the whole network through the channel graph, paced by the
+    /// [`EdgliBuilder::probe_config`]. The only policy edge nodes support today.
+    #[default]
+    FullNetwork,
+}
+
+/// Builder for [`Edgli`]; obtain one from [`Edgli::builder`].
+pub struct EdgliBuilder {
+    pub(crate) cfg: HoprLibConfig,
+    pub(crate) keys: HoprKeys,
+    pub(crate) blokli_endpoints: BlokliEndpointSet,
+    pub(crate) connector_config: Option<BlockchainConnectorConfig>,
+    pub(crate) gas_cap: GasCap,
+    pub(crate) event_poll_interval: Duration,
+    pub(crate) probe_config: FullNetworkProberConfig,
+    pub(crate) probe_local_addresses: bool,
+    pub(crate) mix_protocol: &'static str,
+    pub(crate) ticket_factory: TicketFactorySource,
+    pub(crate) cover_traffic: CoverTrafficPolicy,
+    pub(crate) visitor: Box<dyn Fn(EdgliInitState) + Send>,
+    pub(crate) reactor: Option<(IncentiveConfiguration, ReactorBackoff)>,
+}
+
+impl EdgliBuilder {
+    /// Starts a builder for the node described by `cfg` and identified by `keys`.
+    ///
+    /// Set `cfg.protocol.path_planner` to control the routing strategy; see
+    /// [`crate::latency_path_planner_config`] for a latency-optimised default.
+    pub fn new(cfg: HoprLibConfig, keys: HoprKeys) -> Self {
+        Self {
+            cfg,
+            keys,
+            blokli_endpoints: BlokliEndpointSet::default(),
+            connector_config: None,
+            gas_cap: GasCap::default(),
+            event_poll_interval: DEFAULT_EVENT_POLL_INTERVAL,
+            probe_config: FullNetworkProberConfig {
+                interval: Duration::from_secs(3),
+                shuffle_ttl: Duration::from_secs(3),
+                ..Default::default()
+            },
+            probe_local_addresses: false,
+            mix_protocol: DEFAULT_MIX_PROTOCOL,
+            ticket_factory: TicketFactorySource::default(),
+            cover_traffic: CoverTrafficPolicy::default(),
+            visitor: Box::new(|_| {}),
+            reactor: None,
+        }
+    }
+
+    /// The Blokli service to read the chain from. Default: the production endpoint via
+    /// system DNS.
+    pub fn blokli_endpoint(mut self, endpoint: BlokliEndpoint) -> Self {
+        self.blokli_endpoints = endpoint.into();
+        self
+    }
+
+    /// Several Blokli services to read the chain from, the most preferred first.
+    ///
+    /// Failover only happens at startup: the node connects to the first one that answers and
+    /// stays on it until it is rebuilt, since the chain connector keeps the client it was
+    /// built with. A later outage of that service is not failed over; it shows as Blokli
+    /// being unreachable in [`crate::health`], so an orchestrator can restart the node.
+    pub fn blokli_endpoints(mut self, endpoints: BlokliEndpointSet) -> Self {
+        self.blokli_endpoints = endpoints;
+        self
+    }
+
+    /// Overrides the chain connector's tuning. Default: the connector defaults.
+    pub fn connector_config(mut self, config: BlockchainConnectorConfig) -> Self {
+        self.connector_config = Some(config);
+        self
+    }
+
+    /// The gas price above which the node refuses to submit transactions. Default: uncapped.
+    pub fn gas_cap(mut self, cap: GasCap) -> Self {
+        self.gas_cap = cap;
+        self
+    }
+
+    /// How often the node is compared against its last state to produce
+    /// [`crate::EdgeNodeApi::events`] when no transaction of its own confirmed in between.
+    /// Default: [`DEFAULT_EVENT_POLL_INTERVAL`].
+    pub fn event_poll_interval(mut self, interval: Duration) -> Self {
+        self.event_poll_interval = interval;
+        self
+    }
+
+    /// Pacing of the full-network prober. Default: a 3 s interval and shuffle TTL. Must fit
+    /// within `cfg.protocol.probe.timeout`.
+    pub fn probe_config(mut self, config: FullNetworkProberConfig) -> Self {
+        self.probe_config = config;
+        self
+    }
+
+    /// Whether to dial non-public (private, loopback, link-local) peer addresses from
+    /// announcements. Default: `false`, they are filtered out.
+    pub fn probe_local_addresses(mut self, probe: bool) -> Self {
+        self.probe_local_addresses = probe;
+        self
+    }
+
+    /// The libp2p protocol for mixnet packets. Default: [`DEFAULT_MIX_PROTOCOL`]; only
+    /// peers speaking the same protocol can relay for the node.
+    ///
+    /// The transport holds the name for the node's lifetime, hence `'static`.
+    pub fn mix_protocol(mut self, protocol: &'static str) -> Self {
+        self.mix_protocol = protocol;
+        self
+    }
+
+    /// Where the ticket factory is seeded from. Default: [`TicketFactorySource::Chain`].
+    pub fn ticket_factory(mut self, source: TicketFactorySource) -> Self {
+        self.ticket_factory = source;
+        self
+    }
+
+    /// How cover traffic is generated. Default: [`CoverTrafficPolicy::FullNetwork`].
+    pub fn cover_traffic(mut self, policy: CoverTrafficPolicy) -> Self {
+        self.cover_traffic = policy;
+        self
+    }
+
+    /// Called at each [`EdgliInitState`] transition, for progress reporting.
+    pub fn visitor(mut self, visitor: impl Fn(EdgliInitState) + Send + 'static) -> Self {
+        self.visitor = Box::new(visitor);
+        self
+    }
+
+    /// Starts the default edge reactor for `incentives` once the node runs, restarted on
+    /// failure as by [`Edgli::run_supervised_reactor`]. [`Edgli::shutdown`] stops it.
+    pub fn reactor(mut self, incentives: IncentiveConfiguration, backoff: ReactorBackoff) -> Self {
+        self.reactor = Some((incentives, backoff));
+        self
+    }
+
+    /// Checks the configuration without building anything.
+    pub fn validate(&self) -> Result<()> {
+        if let hopr_lib::config::HostType::IPv4(address) = &self.cfg.host.address {
+            let ipv4: std::net::Ipv4Addr = address
+                .parse()
+                .map_err(|e| EdgliError::InvalidHostAddress(format!("{address}: {e}")))?;
+            if ipv4.is_loopback() && !self.cfg.protocol.transport.prefer_local_addresses {
+                return Err(EdgliError::LoopbackAnnouncement(address.clone()));
+            }
+        }
+
+        self.probe_config
+            .validate_against_probe_timeout(self.cfg.protocol.probe.timeout)
+            .map_err(|e| EdgliError::InvalidProbeConfig(e.to_string()))?;
+
+        validate_mix_protocol(self.mix_protocol)?;
+
+        self.gas_cap.validate()?;
+
+        if self.event_poll_interval.is_zero() {
+            return Err(EdgliError::ConfigError(
+                "the event poll interval must be non-zero".into(),
+            ));
+        }
+
+        if let Some(endpoint) = self
+            .blokli_endpoints
+            .endpoints()
+            .iter()
+            .find(|e| e.terminates_tls() && e.url.scheme() != "https")
+        {
+            return Err(EdgliError::ConfigError(format!(
+                "certificate pinning requires an https Blokli URL, got '{}'",
+                endpoint.url
+            )));
+        }
+
+        if self.ticket_factory == TicketFactorySource::ChainWithTimeout(Duration::ZERO) {
+            return Err(EdgliError::InvalidTicketFactorySource(
+                "the seeding timeout must be non-zero".into(),
+            ));
+        }
+
+        if let Some((incentives, _)) = &self.reactor {
+            default_strategy_cfg(incentives)
+                .map_err(|e| EdgliError::InvalidReactorConfig(format!("{e:#}")))?;
+        }
+
+        Ok(())
+    }
+
+    /// Validates the configuration, then constructs and starts the node.
+    ///
+    /// Failures after validation, such as an unreachable Blokli, are reported as
+    /// [`EdgliError::StartupError`] unless hopr-lib raised them.
+    pub async fn build(mut self) -> Result<Edgli> {
+        let visitor = std::mem::replace(&mut self.visitor, Box::new(|_| {}));
+        self.visitor = Box::new(crate::client::timed_init_stages(visitor));
+        (self.visitor)(EdgliInitState::ValidatingConfig);
+        self.validate()?;
+        let reactor = self.reactor.clone();
+
+        let edgli = Edgli::start(self)
+            .await
+            .map_err(|e| match e.downcast::<EdgliError>() {
+                Ok(e) => e,
+                Err(e) => match e.downcast::<hopr_lib::errors::HoprLibError>() {
+                    Ok(e) => EdgliError::HoprLibError(e),
+                    Err(e) => EdgliError::StartupError(format!("{e:#}")),
+                },
+            })?;
+
+        if let Some((incentives, backoff)) = reactor {
+            edgli
+                .run_supervised_reactor(incentives, backoff)
+                .map_err(|e| EdgliError::StartupError(format!("{e:#}")))?;
+        }
+
+        Ok(edgli)
+    }
+}
+
+fn validate_mix_protocol(protocol: &str) -> Result<()> {
+    if protocol.len() < 2 || !protocol.starts_with('/') || protocol.contains(char::is_whitespace) {
+        return Err(EdgliError::InvalidMixProtocol(protocol.into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use hopr_lib::config::{HostConfig, HostType};
+
+    use super::*;
+
+    fn builder() -> EdgliBuilder {
+        let mut cfg = HoprLibConfig::default();
+        cfg.host = HostConfig::default();
+        cfg.host.address = HostType::IPv4("10.0.0.1".into());
+        EdgliBuilder::new(cfg, HoprKeys::random())
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        builder().validate().unwrap();
+    }
+
+    #[test]
+    fn malformed_host_address_is_rejected() {
+        let mut builder = builder();
+        builder.cfg.host.address = HostType::IPv4("10.0.0".into());
+        assert!(matches!(
+            builder.validate(),
+            Err(EdgliError::InvalidHostAddress(_))
+        ));
+    }
+
+    #[test]
+    fn loopback_is_only_announced_when_preferring_local_addresses() {
+        let mut builder = builder();
+        builder.cfg.host.address = HostType::IPv4("127.0.0.1".into());
+        assert!(matches!(
+            builder.validate(),
+            Err(EdgliError::LoopbackAnnouncement(_))
+        ));
+
+        builder.cfg.protocol.transport.prefer_local_addresses = true;
+        builder.validate().unwrap();
+    }
+
+    #[test]
+    fn mix_protocol_must_be_a_protocol_path() {
+        for protocol in ["", "/", "hopr/mix", "/hopr/ mix"] {
+            assert!(
+                matches!(
+                    builder().mix_protocol(protocol).validate(),
+                    Err(EdgliError::InvalidMixProtocol(_))
+                ),
+                "{protocol:?} accepted"
+            );
+        }
+        builder()
+            .mix_protocol("/hopr/mix/1.2.0")
+            .validate()
+            .unwrap();
+    }
+
+    #[test]
+    fn zero_event_poll_interval_is_rejected() {
+        assert!(matches!(
+            builder().event_poll_interval(Duration::ZERO).validate(),
+            Err(EdgliError::ConfigError(_))
+        ));
+        builder()
+            .event_poll_interval(Duration::from_secs(1))
+            .validate()
+            .unwrap();
+    }
+
+    #[test]
+    fn pinned_blokli_endpoint_must_use_https() {
+        let endpoint = BlokliEndpoint::new("http://blokli.example.com".parse().unwrap())
+            .with_ca_bundle(
+                crate::tls::CaBundle::from_pem(include_bytes!("../tests/data/blokli-test-ca.pem"))
+                    .unwrap(),
+            );
+        assert!(matches!(
+            builder().blokli_endpoint(endpoint).validate(),
+            Err(EdgliError::ConfigError(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_gas_cap_is_rejected() {
+        let err = builder().gas_cap(GasCap::new(0)).validate().unwrap_err();
+        assert!(matches!(err, EdgliError::ConfigError(_)));
+    }
+
+    #[test]
+    fn zero_ticket_factory_timeout_is_rejected() {
+        let builder =
+            builder().ticket_factory(TicketFactorySource::ChainWithTimeout(Duration::ZERO));
+        assert!(matches!(
+            builder.validate(),
+            Err(EdgliError::InvalidTicketFactorySource(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_reactor_config_is_rejected_before_startup() {
+        let incentives = IncentiveConfiguration {
+            min_open_channels: 5,
+            target_open_channels: 2,
+            ..Default::default()
+        };
+        let builder = builder().reactor(incentives, ReactorBackoff::default());
+        assert!(matches!(
+            builder.validate(),
+            Err(EdgliError::InvalidReactorConfig(_))
+        ));
+    }
+}
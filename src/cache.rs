@@ -0,0 +1,317 @@
+//! Response caching for [`IncentiveOperations`] chain reads.
+//!
+//! [`crate::minimum_balance_recommendation`] alone issues three Blokli queries per call, so a
+//! UI polling on-boarding state multiplies its poll rate by that. [`CachedIncentiveOperations`]
+//! answers reads from a per-call cache whose lifetimes follow how fast each value moves on
+//! chain, and drops the values a transaction changes once it went through.
+//!
+//! Concurrent reads of an expired value share a single query. Errors are never cached.
+
+use std::time::{Duration, Instant};
+
+use hopr_lib::api::types::primitive::prelude::{Address, HoprBalance, XDaiBalance};
+use tokio::sync::Mutex;
+
+use crate::blokli::{IncentiveOperations, SafeModuleDeploymentResult, TicketStats};
+use crate::strategy::StartupCosts;
+
+/// How long [`CachedIncentiveOperations`] serves each read from its cache.
+#[derive(Clone, Debug, smart_default::SmartDefault)]
+pub struct CacheTtls {
+    /// Ticket price and winning probability, which change with network governance.
+    /// Default: 5 min.
+    #[default(Duration::from_secs(300))]
+    pub ticket_stats: Duration,
+
+    /// Gas price, which moves from block to block. Default: 15 s.
+    #[default(Duration::from_secs(15))]
+    pub max_fee_per_gas: Duration,
+
+    /// Node balances, which change whenever the operator funds the node. Default: 10 s.
+    #[default(Duration::from_secs(10))]
+    pub balances: Duration,
+
+    /// Safe deployment and startup costs, which only the node's own transactions change.
+    /// Default: 1 min.
+    #[default(Duration::from_secs(60))]
+    pub deployment: Duration,
+}
+
+/// One cached value; the lock is held across a refresh so concurrent readers wait for it.
+struct Entry<T> {
+    ttl: Duration,
+    value: Mutex<Option<(Instant, T)>>,
+}
+
+impl<T: Clone> Entry<T> {
+    fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            value: Mutex::new(None),
+        }
+    }
+
+    async fn get_or_fetch(
+        &self,
+        fetch: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        let mut value = self.value.lock().await;
+        if let Some((fetched, cached)) = value.as_ref()
+            && fetched.elapsed() < self.ttl
+        {
+            return Ok(cached.clone());
+        }
+        let fresh = fetch.await?;
+        *value = Some((Instant::now(), fresh.clone()));
+        Ok(fresh)
+    }
+
+    async fn invalidate(&self) {
+        *self.value.lock().await = None;
+    }
+}
+
+/// An [`IncentiveOperations`] handle that caches the reads of the one it wraps.
+///
+/// [`IncentiveOperations::deploy_safe`] drops the cached Safe, startup costs and balances;
+/// [`IncentiveOperations::withdraw_wxhopr`] drops the balances. Call [`Self::invalidate`]
+/// after changing chain state by other means.
+pub struct CachedIncentiveOperations {
+    inner: Box<dyn IncentiveOperations>,
+    safe: Entry<Option<SafeModuleDeploymentResult>>,
+    ticket_stats: Entry<TicketStats>,
+    max_fee_per_gas: Entry<u128>,
+    costs_to_start: Entry<StartupCosts>,
+    balances: Entry<(HoprBalance, XDaiBalance)>,
+}
+
+impl CachedIncentiveOperations {
+    /// Wraps `inner` with the default [`CacheTtls`].
+    pub fn new(inner: Box<dyn IncentiveOperations>) -> Self {
+        Self::with_ttls(inner, CacheTtls::default())
+    }
+
+    /// Wraps `inner`, caching each read for its entry in `ttls`.
+    pub fn with_ttls(inner: Box<dyn IncentiveOperations>, ttls: CacheTtls) -> Self {
+        Self {
+            inner,
+            safe: Entry::new(ttls.deployment),
+            ticket_stats: Entry::new(ttls.ticket_stats),
+            max_fee_per_gas: Entry::new(ttls.max_fee_per_gas),
+            costs_to_start: Entry::new(ttls.deployment),
+            balances: Entry::new(ttls.balances),
+        }
+    }
+
+    /// Drops every cached value, so the next read of each goes to the chain.
+    pub async fn invalidate(&self) {
+        self.safe.invalidate().await;
+        self.ticket_stats.invalidate().await;
+        self.max_fee_per_gas.invalidate().await;
+        self.costs_to_start.invalidate().await;
+        self.balances.invalidate().await;
+    }
+}
+
+#[async_trait::async_trait]
+impl IncentiveOperations for CachedIncentiveOperations {
+    async fn retrieve_safe(&self) -> anyhow::Result<Option<SafeModuleDeploymentResult>> {
+        self.safe.get_or_fetch(self.inner.retrieve_safe()).await
+    }
+
+    async fn deploy_safe(
+        &self,
+        token_amount: HoprBalance,
+    ) -> anyhow::Result<SafeModuleDeploymentResult> {
+        let deployed = self.inner.deploy_safe(token_amount).await;
+        // Invalidate on failure too: a failed deployment may still have spent gas.
+        self.safe.invalidate().await;
+        self.costs_to_start.invalidate().await;
+        self.balances.invalidate().await;
+        deployed
+    }
+
+    async fn ticket_stats(&self) -> anyhow::Result<TicketStats> {
+        self.ticket_stats
+            .get_or_fetch(self.inner.ticket_stats())
+            .await
+    }
+
+    async fn max_fee_per_gas(&self) -> anyhow::Result<u128> {
+        self.max_fee_per_gas
+            .get_or_fetch(self.inner.max_fee_per_gas())
+            .await
+    }
+
+    async fn compute_costs_to_start(&self) -> anyhow::Result<StartupCosts> {
+        self.costs_to_start
+            .get_or_fetch(self.inner.compute_costs_to_start())
+            .await
+    }
+
+    async fn balances(&self) -> anyhow::Result<(HoprBalance, XDaiBalance)> {
+        self.balances.get_or_fetch(self.inner.balances()).await
+    }
+
+    async fn withdraw_wxhopr(
+        &self,
+        safe_address: Address,
+        amount: HoprBalance,
+    ) -> anyhow::Result<()> {
+        let withdrawn = self.inner.withdraw_wxhopr(safe_address, amount).await;
+        self.balances.invalidate().await;
+        withdrawn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use hopr_lib::api::types::internal::prelude::WinningProbability;
+
+    use super::*;
+
+    /// Counts the queries that reach the chain.
+    #[derive(Default)]
+    struct CountingChain {
+        queries: Arc<AtomicUsize>,
+        fail: bool,
+    }
+
+    impl CountingChain {
+        fn query(&self) -> anyhow::Result<()> {
+            self.queries.fetch_add(1, Ordering::SeqCst);
+            if self.fail {
+                anyhow::bail!("blokli unreachable");
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl IncentiveOperations for CountingChain {
+        async fn retrieve_safe(&self) -> anyhow::Result<Option<SafeModuleDeploymentResult>> {
+            self.query().map(|_| None)
+        }
+
+        async fn deploy_safe(
+            &self,
+            _token_amount: HoprBalance,
+        ) -> anyhow::Result<SafeModuleDeploymentResult> {
+            Ok(SafeModuleDeploymentResult {
+                safe_address: [0x11u8; 20].into(),
+                module_address: [0x22u8; 20].into(),
+            })
+        }
+
+        async fn ticket_stats(&self) -> anyhow::Result<TicketStats> {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.query().map(|_| TicketStats {
+                ticket_price: "0.000001 wxHOPR".parse().unwrap(),
+                winning_probability: WinningProbability::default(),
+            })
+        }
+
+        async fn max_fee_per_gas(&self) -> anyhow::Result<u128> {
+            self.query().map(|_| 1_000_000_000)
+        }
+
+        async fn compute_costs_to_start(&self) -> anyhow::Result<StartupCosts> {
+            self.query().map(|_| StartupCosts {
+                fee_to_start: HoprBalance::zero(),
+                txs_to_start: 3,
+            })
+        }
+
+        async fn balances(&self) -> anyhow::Result<(HoprBalance, XDaiBalance)> {
+            self.query()
+                .map(|_| (HoprBalance::zero(), XDaiBalance::zero()))
+        }
+
+        async fn withdraw_wxhopr(
+            &self,
+            _safe_address: Address,
+            _amount: HoprBalance,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn cached(fail: bool, ttls: CacheTtls) -> (CachedIncentiveOperations, Arc<AtomicUsize>) {
+        let chain = CountingChain {
+            fail,
+            ..Default::default()
+        };
+        let queries = chain.queries.clone();
+        (
+            CachedIncentiveOperations::with_ttls(Box::new(chain), ttls),
+            queries,
+        )
+    }
+
+    #[tokio::test]
+    async fn reads_are_served_from_the_cache_until_they_expire() {
+        let (ops, queries) = cached(false, CacheTtls::default());
+        ops.max_fee_per_gas().await.unwrap();
+        ops.max_fee_per_gas().await.unwrap();
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+
+        let (ops, queries) = cached(
+            false,
+            CacheTtls {
+                max_fee_per_gas: Duration::ZERO,
+                ..Default::default()
+            },
+        );
+        ops.max_fee_per_gas().await.unwrap();
+        ops.max_fee_per_gas().await.unwrap();
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn concurrent_reads_share_one_query() {
+        let (ops, queries) = cached(false, CacheTtls::default());
+        let (a, b) = tokio::join!(ops.ticket_stats(), ops.ticket_stats());
+        a.unwrap();
+        b.unwrap();
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn errors_are_not_cached() {
+        let (ops, queries) = cached(true, CacheTtls::default());
+        assert!(ops.balances().await.is_err());
+        assert!(ops.balances().await.is_err());
+        assert_eq!(queries.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn transactions_invalidate_what_they_change() {
+        let (ops, queries) = cached(false, CacheTtls::default());
+        ops.retrieve_safe().await.unwrap();
+        ops.compute_costs_to_start().await.unwrap();
+        ops.balances().await.unwrap();
+        ops.max_fee_per_gas().await.unwrap();
+        assert_eq!(queries.load(Ordering::SeqCst), 4);
+
+        ops.deploy_safe(HoprBalance::zero()).await.unwrap();
+        ops.retrieve_safe().await.unwrap();
+        ops.compute_costs_to_start().await.unwrap();
+        ops.balances().await.unwrap();
+        ops.max_fee_per_gas().await.unwrap();
+        assert_eq!(queries.load(Ordering::SeqCst), 7);
+
+        ops.withdraw_wxhopr([0x11u8; 20].into(), HoprBalance::zero())
+            .await
+            .unwrap();
+        ops.balances().await.unwrap();
+        ops.retrieve_safe().await.unwrap();
+        assert_eq!(queries.load(Ordering::SeqCst), 8);
+
+        ops.invalidate().await;
+        ops.max_fee_per_gas().await.unwrap();
+        assert_eq!(queries.load(Ordering::SeqCst), 9);
+    }
+}
@@ -3,15 +3,14 @@ use std::sync::Arc;
 
 use futures::StreamExt;
 use futures::future::{AbortHandle, abortable};
-use hopr_chain_connector::{BlockchainConnectorConfig, create_trustful_hopr_blokli_connector};
-use hopr_ct_full_network::ProberConfig as FullNetworkProberConfig;
+use hopr_chain_connector::create_trustful_hopr_blokli_connector;
 use hopr_lib::api::{
     chain::{ChainReadSafeOperations, ChainValues as _, SafeSelector},
     node::{HasChainApi, IncentiveChannelOperations},
     types::{
         crypto::prelude::OffchainPublicKey,
         internal::channels::ChannelStatus,
-        primitive::prelude::{Address, HoprBalance},
+        primitive::prelude::{Address, HoprBalance, XDai},
     },
 };
 use hopr_lib::builder::{ChainKeypair, HoprBuilder, Keypair, OffchainKeypair};
@@ -24,10 +23,16 @@ use multiaddr::Multiaddr;
 use strum::{AsRefStr, Display, EnumString};
 use tracing::info;
 
-#[cfg(feature = "blokli")]
-use crate::endpoint::BlokliEndpoint;
-
-use crate::errors::EdgliError;
+use crate::builder::{CoverTrafficPolicy, EdgliBuilder, TicketFactorySource};
+use crate::endpoint::BlokliFailover;
+use crate::events::EdgeEventChannel;
+use crate::gas::{GAS_CAP_CHECK_INTERVAL, GasCap, GasMeasurement, ObservedGas};
+use crate::monitor::{BalanceMonitor, BalanceMonitorConfig, BalanceSnapshot, BalanceThresholds};
+use crate::proxy::Forwarder;
+use crate::retry::RetryPolicy;
+use crate::session::SessionRegistry;
+use crate::shutdown::{Lifecycle, PauseReason, SessionLease, ShutdownSummary};
+use crate::sweep::{SweepConfig, SweepOutcome};
 
 /// The concrete HOPR edge node type used by this client.
 pub type HoprEdgeClient = hopr_lib::Hopr<
@@ -41,6 +46,30 @@ pub type HoprEdgeClient = hopr_lib::Hopr<
     (),
 >;
 
+/// Peers connecting (`true`) or disconnecting (`false`), by packet key, as the transport
+/// reports them.
+type PeerChanges = futures::stream::BoxStream<'static, (OffchainPublicKey, bool)>;
+
+/// The peer connections and disconnections `network` reports from now on.
+fn peer_changes(network: &HoprNetwork) -> PeerChanges {
+    use hopr_lib::api::network::{NetworkEvent, NetworkView};
+
+    network
+        .subscribe_network_events()
+        .filter_map(|event| {
+            let (peer, connected) = match event {
+                NetworkEvent::PeerConnected(peer) => (peer, true),
+                NetworkEvent::PeerDisconnected(peer) => (peer, false),
+            };
+            futures::future::ready(
+                OffchainPublicKey::from_peerid(&peer)
+                    .ok()
+                    .map(|key| (key, connected)),
+            )
+        })
+        .boxed()
+}
+
 /// Represents the initialization states of the Edgli client.
 /// Each state corresponds to a step in the `new()` function.
 ///
@@ -102,33 +131,39 @@ fn probeable_addresses(addrs: Vec<Multiaddr>, probe_local_addresses: bool) -> Ve
     }
 }
 
+/// Wraps an init-state visitor so the time spent in each stage is recorded as a metric when
+/// the next stage begins.
+pub(crate) fn timed_init_stages(
+    visitor: impl Fn(EdgliInitState) + Send + 'static,
+) -> impl Fn(EdgliInitState) + Send + 'static {
+    let current = std::sync::Mutex::new(None::<(EdgliInitState, std::time::Instant)>);
+    move |state| {
+        let now = std::time::Instant::now();
+        let previous = current
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace((state, now));
+        if let Some((stage, started)) = previous {
+            crate::metrics::record_init_stage(&format!("{stage:?}"), now - started);
+        }
+        visitor(state)
+    }
+}
+
 /// Spawns an abortable task that drives a user-supplied closure over the running node.
 ///
 /// Returns an [`AbortHandle`] that stops the closure task when aborted.
 /// `Edgli` is kept alive for the entire duration of `f` so that background tasks
 /// remain active until `f` completes or the returned [`AbortHandle`] is used to cancel it.
 pub async fn run_hopr_edge_node_with<F, T>(
-    cfg: HoprLibConfig,
-    hopr_keys: HoprKeys,
-    blokli_endpoint: BlokliEndpoint,
-    blokli_connector_config: Option<BlockchainConnectorConfig>,
-    probe_local_addresses: bool,
+    builder: EdgliBuilder,
     f: F,
-    visitor: impl Fn(EdgliInitState) + Send + 'static,
-) -> anyhow::Result<AbortHandle>
+) -> crate::errors::Result<AbortHandle>
 where
     F: Fn(Arc<HoprEdgeClient>) -> T + Send + 'static,
     T: std::future::Future<Output = ()> + Send + 'static,
 {
-    let edgli = Edgli::new(
-        cfg,
-        hopr_keys,
-        blokli_endpoint,
-        blokli_connector_config,
-        probe_local_addresses,
-        visitor,
-    )
-    .await?;
+    let edgli = builder.build().await?;
     let hopr = edgli.as_hopr();
     // Keep `edgli` alive inside the spawned task so the node and all its
     // background processes remain active until `f` completes (or the abort fires).
@@ -151,6 +186,34 @@ pub struct Edgli {
     hopr: Arc<HoprEdgeClient>,
     /// The node's packet-layer public key, stored at construction for peer-ID access.
     packet_public_key: OffchainPublicKey,
+    /// Shutdown bookkeeping shared by all clones.
+    lifecycle: Arc<Lifecycle>,
+    /// How the chain reads made through this handle retry transient Blokli failures.
+    retry: RetryPolicy,
+    /// The Blokli endpoints and which of them the node is connected to.
+    blokli: BlokliFailover,
+    /// The cap on the gas price transactions are submitted at.
+    gas_cap: GasCap,
+    /// Set once the task pausing reactors while gas is above the cap has started.
+    gas_cap_watch: Arc<std::sync::OnceLock<()>>,
+    /// Gas the channel transactions submitted through this handle used, which
+    /// [`Self::xdai_budget`] projects from.
+    observed_gas: Arc<ObservedGas>,
+    /// Where [`crate::EdgeNodeApi::events`] streams from; its poller starts with the first
+    /// subscription.
+    events: Arc<std::sync::OnceLock<EdgeEventChannel>>,
+    /// How often the event poller compares the node's channels, balances and peers when
+    /// nothing wakes it earlier.
+    event_poll_interval: std::time::Duration,
+    /// Wakes the event poller once a transaction submitted through this handle confirms.
+    event_resync: Arc<tokio::sync::Notify>,
+    /// The transport's peer changes, subscribed to as the network is built and taken by the
+    /// event poller.
+    peer_changes: Arc<std::sync::Mutex<Option<PeerChanges>>>,
+    /// The sessions opened through [`crate::EdgeNodeApi::open_session`].
+    sessions: SessionRegistry,
+    /// Keeps the chain connector's local Blokli forwarder running, when it has one.
+    _blokli_forwarder: Option<Arc<Forwarder>>,
 }
 
 impl std::ops::Deref for Edgli {
@@ -162,40 +225,58 @@ impl std::ops::Deref for Edgli {
 }
 
 impl Edgli {
+    /// Starts an [`EdgliBuilder`] for the node described by `cfg` and identified by
+    /// `hopr_keys`; every other setting has a default.
+    pub fn builder(cfg: HoprLibConfig, hopr_keys: HoprKeys) -> EdgliBuilder {
+        EdgliBuilder::new(cfg, hopr_keys)
+    }
+
     /// Constructs and starts an edge HOPR node.
     ///
-    /// # Arguments
-    /// * `cfg` – full HOPR node configuration; set `cfg.protocol.path_planner`
-    ///   before calling to control the routing strategy.  Use
-    ///   [`crate::latency_path_planner_config`] to obtain a latency-optimised default.
-    /// * `hopr_keys` – chain and packet keypairs
-    /// * `blokli_endpoint` – Blokli service URL and optional DNS override; use
-    ///   [`BlokliEndpoint::default`] for the production endpoint via system DNS
-    /// * `blokli_connector_config` – optional connector config overrides
-    /// * `probe_local_addresses` – when `true`, probe non-public (private,
-    ///   loopback, link-local) peer addresses from announcements; when `false`
-    ///   (default) they are filtered out before dialing
-    /// * `visitor` – called at each [`EdgliInitState`] transition for progress reporting
+    /// Equivalent to [`Self::builder`] with [`EdgliBuilder::blokli_endpoint`],
+    /// [`EdgliBuilder::connector_config`] (when given), [`EdgliBuilder::probe_local_addresses`]
+    /// and [`EdgliBuilder::visitor`] set from the arguments.
+    #[deprecated(
+        note = "use `Edgli::builder`, which validates the whole configuration; `Edgli::new` \
+                will be removed in the next release"
+    )]
     pub async fn new(
         cfg: HoprLibConfig,
         hopr_keys: HoprKeys,
-        blokli_endpoint: BlokliEndpoint,
-        blokli_connector_config: Option<BlockchainConnectorConfig>,
+        blokli_endpoint: crate::endpoint::BlokliEndpoint,
+        blokli_connector_config: Option<hopr_chain_connector::BlockchainConnectorConfig>,
         probe_local_addresses: bool,
         visitor: impl Fn(EdgliInitState) + Send + 'static,
     ) -> anyhow::Result<Self> {
-        visitor(EdgliInitState::ValidatingConfig);
-        if let hopr_lib::config::HostType::IPv4(address) = &cfg.host.address {
-            let ipv4: std::net::Ipv4Addr = address
-                .parse()
-                .map_err(|e| EdgliError::ConfigError(format!("{e}")))?;
-
-            if ipv4.is_loopback() && !cfg.protocol.transport.prefer_local_addresses {
-                Err(hopr_lib::errors::HoprLibError::GeneralError(
-                    "Cannot announce a loopback address".into(),
-                ))?;
-            }
+        let mut builder = Self::builder(cfg, hopr_keys)
+            .blokli_endpoint(blokli_endpoint)
+            .probe_local_addresses(probe_local_addresses)
+            .visitor(visitor);
+        if let Some(config) = blokli_connector_config {
+            builder = builder.connector_config(config);
         }
+        Ok(builder.build().await?)
+    }
+
+    /// Constructs and starts an edge HOPR node from a builder that passed validation.
+    ///
+    /// The builder reported [`EdgliInitState::ValidatingConfig`] already.
+    pub(crate) async fn start(builder: EdgliBuilder) -> anyhow::Result<Self> {
+        let EdgliBuilder {
+            cfg,
+            keys: hopr_keys,
+            blokli_endpoints,
+            connector_config: blokli_connector_config,
+            gas_cap,
+            event_poll_interval,
+            probe_config: probe_cfg,
+            probe_local_addresses,
+            mix_protocol,
+            ticket_factory: ticket_factory_source,
+            cover_traffic,
+            visitor,
+            reactor: _,
+        } = builder;
 
         let chain_key: &ChainKeypair = &hopr_keys.chain_key;
         let packet_key: &OffchainKeypair = &hopr_keys.packet_key;
@@ -208,34 +289,60 @@ impl Edgli {
             "Node public identifiers"
         );
 
+        let failover = blokli_endpoints.failover();
         #[cfg(feature = "blokli")]
-        let chain_connector = {
+        let (chain_connector, blokli_forwarder) = {
             let blokli_config = blokli_connector_config.unwrap_or_default();
             visitor(EdgliInitState::ConnectingBlockchain);
-            let mut connector = create_trustful_hopr_blokli_connector(
-                chain_key,
-                blokli_config,
-                blokli_endpoint.build_client(),
-                cfg.safe_module.module_address,
-            )
-            .await?;
-            connector.connect().await?;
-            Arc::new(connector)
+            // The connector keeps the client it was built with, so failover happens here: the
+            // node stays on the first endpoint that connects until it is restarted.
+            let retry = &blokli_endpoints.retry;
+            let mut connected = Err(anyhow::anyhow!("no Blokli endpoint configured"));
+            for index in failover.candidates() {
+                let endpoint = failover.endpoint(index);
+                // Transient failures are retried on the same endpoint before failing over.
+                let attempt = retry.retry("connect", || async {
+                    let built = endpoint.build_client().await?;
+                    let mut connector = create_trustful_hopr_blokli_connector(
+                        chain_key,
+                        blokli_config.clone(),
+                        built.client,
+                        cfg.safe_module.module_address,
+                    )
+                    .await?;
+                    connector.connect().await?;
+                    anyhow::Ok((connector, built.forwarder))
+                });
+                match attempt.await {
+                    Ok(connector) => {
+                        failover.record_success(index);
+                        connected = Ok(connector);
+                        break;
+                    }
+                    Err(error) => {
+                        tracing::warn!(url = %endpoint.url, %error, "Blokli endpoint unavailable");
+                        failover.record_failure(index);
+                        connected = Err(error);
+                    }
+                }
+            }
+            let (connector, forwarder) = connected?;
+            (Arc::new(connector), forwarder)
         };
 
         visitor(EdgliInitState::CreatingNode);
         info!("Building HOPR edge node directly via HoprBuilder");
 
-        let probe_cfg = FullNetworkProberConfig {
-            interval: std::time::Duration::from_secs(3),
-            shuffle_ttl: std::time::Duration::from_secs(3),
-            ..Default::default()
-        };
-        probe_cfg.validate_against_probe_timeout(cfg.protocol.probe.timeout)?;
-
-        let ticket_factory = ticket_factory_from_chain(&chain_connector)
-            .await
-            .map_err(|e| anyhow::anyhow!("failed to seed ticket factory: {e}"))?;
+        let seeding = ticket_factory_from_chain(&chain_connector);
+        let ticket_factory = match ticket_factory_source {
+            TicketFactorySource::Chain => seeding.await,
+            TicketFactorySource::ChainWithTimeout(timeout) => {
+                tokio::time::timeout(timeout, seeding).await.map_err(|_| {
+                    anyhow::anyhow!("seeding the ticket factory timed out after {timeout:?}")
+                })?
+            }
+        }
+        .map_err(|e| anyhow::anyhow!("failed to seed ticket factory: {e}"))?;
 
         let path_cfg = cfg.protocol.path_planner;
         let graph: SharedChannelGraph = Arc::new(ChannelGraph::with_edge_params(
@@ -245,6 +352,8 @@ impl Edgli {
             path_cfg.max_plausible_loopback_rtt,
         ));
         let graph_for_ct = graph.clone();
+        let peer_changes_slot: Arc<std::sync::Mutex<Option<PeerChanges>>> = Arc::default();
+        let network_peer_changes = peer_changes_slot.clone();
         let safe_address = cfg.safe_module.safe_address;
         let module_address = cfg.safe_module.module_address;
 
@@ -276,22 +385,31 @@ impl Edgli {
                                 )
                             },
                         ));
-                        nb.build(
-                            &ctx.packet_key,
-                            multiaddresses,
-                            "/hopr/mix/1.1.0",
-                            ctx.cfg.protocol.transport.prefer_local_addresses,
-                        )
-                        .await
-                        .map_err(|e| hopr_lib::errors::HoprLibError::GeneralError(e.to_string()))
+                        let network = nb
+                            .build(
+                                &ctx.packet_key,
+                                multiaddresses,
+                                mix_protocol,
+                                ctx.cfg.protocol.transport.prefer_local_addresses,
+                            )
+                            .await
+                            .map_err(|e| {
+                                hopr_lib::errors::HoprLibError::GeneralError(e.to_string())
+                            })?;
+                        *network_peer_changes
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner()) = Some(peer_changes(&network));
+                        Ok(network)
                     })
                 })
-                .with_cover_traffic(move |ctx| {
-                    hopr_ct_full_network::FullNetworkDiscovery::new(
-                        *ctx.packet_key.public(),
-                        probe_cfg,
-                        graph_for_ct,
-                    )
+                .with_cover_traffic(move |ctx| match cover_traffic {
+                    CoverTrafficPolicy::FullNetwork => {
+                        hopr_ct_full_network::FullNetworkDiscovery::new(
+                            *ctx.packet_key.public(),
+                            probe_cfg,
+                            graph_for_ct,
+                        )
+                    }
                 })
                 .build_edge(ticket_factory)
                 .await?,
@@ -301,10 +419,26 @@ impl Edgli {
         Ok(Self {
             hopr: node,
             packet_public_key,
+            lifecycle: Arc::default(),
+            retry: blokli_endpoints.retry,
+            blokli: failover,
+            gas_cap,
+            gas_cap_watch: Arc::default(),
+            observed_gas: Arc::default(),
+            events: Arc::default(),
+            event_poll_interval,
+            event_resync: Arc::default(),
+            peer_changes: peer_changes_slot,
+            sessions: SessionRegistry::default(),
+            _blokli_forwarder: blokli_forwarder.map(Arc::new),
         })
     }
 
     /// Returns the shared [`HoprEdgeClient`] handle.
+    ///
+    /// When Blokli is reached through a proxy or with certificate pinning, the handle's chain
+    /// connector depends on a local forwarder that stops with the last `Edgli` clone, so keep
+    /// one alive for as long as the handle is used.
     pub fn as_hopr(&self) -> Arc<HoprEdgeClient> {
         self.hopr.clone()
     }
@@ -317,6 +451,284 @@ impl Edgli {
         self.hopr.identity().node_address
     }
 
+    /// Registers a session so that [`Self::shutdown`] waits for it to close.
+    ///
+    /// Hold the returned lease for as long as the session is open. Fails with
+    /// [`EdgliError::ShuttingDown`] once a shutdown has begun.
+    pub fn session_lease(&self) -> crate::errors::Result<SessionLease> {
+        self.lifecycle.session_lease()
+    }
+
+    /// Flushes `provider` as the last step of [`Self::shutdown`].
+    #[cfg(feature = "telemetry")]
+    pub fn flush_on_shutdown(&self, provider: opentelemetry_sdk::trace::SdkTracerProvider) {
+        self.lifecycle.register_tracer_provider(provider);
+    }
+
+    /// Shuts the node down in order: refuses new [`SessionLease`]s, waits up to `timeout` for
+    /// the leased sessions to close, closes the sessions opened through
+    /// [`crate::EdgeNodeApi::open_session`] that are still open, aborts the strategy reactors
+    /// started from this node and flushes the telemetry exporters registered with
+    /// [`Self::flush_on_shutdown`].
+    ///
+    /// Only sessions covered by a [`SessionLease`] are waited for. `open_session` takes one
+    /// for each session it opens; a session opened on [`Self::as_hopr`] directly is only
+    /// waited for while its caller holds a lease from [`Self::session_lease`], and is neither
+    /// listed nor closed here. Such sessions, and hopr-lib's own session handling, stop once
+    /// the last clone of the node is dropped, together with its background tasks.
+    pub async fn shutdown(&self, timeout: std::time::Duration) -> ShutdownSummary {
+        self.lifecycle.shutdown(timeout, &self.sessions).await
+    }
+
+    /// The `endpoint` label of the Blokli requests this node makes: its active endpoint.
+    pub(crate) fn blokli_endpoint(&self) -> String {
+        self.blokli
+            .active()
+            .map(|endpoint| endpoint.metrics_label())
+            .unwrap_or_default()
+    }
+
+    /// Whether Blokli currently answers a chain-info query.
+    pub async fn blokli_reachable(&self) -> bool {
+        use hopr_chain_connector::blokli_client::BlokliQueryClient;
+        let chain = self.chain_api();
+        match crate::metrics::blokli_request(
+            "query_chain_info",
+            self.blokli_endpoint(),
+            chain.client().query_chain_info(),
+        )
+        .await
+        {
+            Ok(_) => true,
+            Err(error) => {
+                tracing::debug!(%error, "blokli is unreachable");
+                false
+            }
+        }
+    }
+
+    /// The `max_fee_per_gas` (wei per gas) Blokli currently reports, which fees are estimated
+    /// and transactions are signed with.
+    pub async fn max_fee_per_gas(&self) -> anyhow::Result<u128> {
+        let chain = self.chain_api();
+        self.retry
+            .retry("max_fee_per_gas", || {
+                crate::blokli::query_max_fee_per_gas(chain.client(), self.blokli_endpoint())
+            })
+            .await
+    }
+
+    /// Fails with [`crate::errors::EdgliError::GasPriceAboveCap`] while the chain's gas
+    /// price, the one transactions are signed with, is above the node's cap.
+    pub(crate) async fn check_gas_price(&self) -> anyhow::Result<()> {
+        let reported = self.max_fee_per_gas().await?;
+        Ok(self.gas_cap.check_submission(reported)?)
+    }
+
+    /// Starts measuring the gas of a channel transaction about to be submitted, from the
+    /// node's xDAI and the price the transaction is signed with; see [`ObservedGas`]. `None`
+    /// when either cannot be read, leaving the transaction unmeasured.
+    pub(crate) async fn start_gas_measurement(&self) -> Option<GasMeasurement> {
+        let xdai = IncentiveChannelOperations::get_balance::<XDai>(&*self.hopr)
+            .await
+            .ok()?;
+        let max_fee_per_gas = self.max_fee_per_gas().await.ok()?;
+        Some(self.observed_gas.start(xdai, max_fee_per_gas))
+    }
+
+    /// `budget` with the gas per channel transaction this node measured, when it has.
+    fn observed_budget(
+        &self,
+        budget: &super::strategy::XdaiBudgetConfig,
+    ) -> super::strategy::XdaiBudgetConfig {
+        super::strategy::XdaiBudgetConfig {
+            gas_per_channel_tx: self
+                .observed_gas
+                .median()
+                .unwrap_or(budget.gas_per_channel_tx),
+            ..budget.clone()
+        }
+    }
+
+    /// The node's cap on the gas price transactions are submitted at.
+    pub fn gas_cap(&self) -> GasCap {
+        self.gas_cap
+    }
+
+    /// Starts, once per node, the task holding reactors paused while the chain's gas price is
+    /// above the cap, checked every [`GAS_CAP_CHECK_INTERVAL`]. Does nothing without a cap.
+    ///
+    /// The strategies submit their transactions through hopr-lib, out of reach of
+    /// [`Self::check_gas_price`], so pausing them is how the cap applies to them. A spike
+    /// that starts between two checks is only noticed at the next one.
+    fn watch_gas_cap(&self) {
+        if self.gas_cap.max_fee_per_gas().is_none() {
+            return;
+        }
+        self.gas_cap_watch.get_or_init(|| {
+            let node = self.clone();
+            let (watch, abort_handle) = abortable(async move {
+                let mut ticks = tokio::time::interval(GAS_CAP_CHECK_INTERVAL);
+                ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    ticks.tick().await;
+                    match node.max_fee_per_gas().await {
+                        Ok(reported) => node.lifecycle.pause_reactors(
+                            PauseReason::GasAboveCap,
+                            node.gas_cap.check_submission(reported).is_err(),
+                        ),
+                        Err(error) => {
+                            tracing::warn!(%error, "cannot check the gas price against the cap")
+                        }
+                    }
+                }
+            });
+            tokio::spawn(watch);
+            self.lifecycle.register_task(abort_handle);
+        });
+    }
+
+    /// The channel [`crate::EdgeNodeApi::events`] subscribes to, starting its poller on first
+    /// use. [`Self::shutdown`] stops the poller.
+    pub(crate) fn event_channel(&self) -> &EdgeEventChannel {
+        self.events.get_or_init(|| {
+            let channel = EdgeEventChannel::default();
+            let node = self.clone();
+            let (poller, abort_handle) = abortable(node.run_event_poller(channel.clone()));
+            tokio::spawn(poller);
+            self.lifecycle.register_task(abort_handle);
+            channel
+        })
+    }
+
+    /// Wakes the event poller to compare the node right away rather than at its next
+    /// interval; held by transactions to signal their confirmation.
+    pub(crate) fn event_resync(&self) -> Arc<tokio::sync::Notify> {
+        self.event_resync.clone()
+    }
+
+    /// The sessions opened through [`crate::EdgeNodeApi::open_session`] on any clone.
+    pub(crate) fn session_registry(&self) -> &SessionRegistry {
+        &self.sessions
+    }
+
+    /// Sends the changes to the node's state, channels, balances and peers through `channel`.
+    ///
+    /// Changes to the node's outgoing channels, as the chain connector indexes them, and
+    /// peers connecting or leaving, as the transport reports them, are passed on as they
+    /// arrive, so one that is undone before the next comparison is still reported. The node is
+    /// compared in full after each channel change, which moves balances too, as soon as a
+    /// transaction submitted through [`crate::EdgeNodeApi`] confirms, and every
+    /// `event_poll_interval`. The comparison is what reports balances and state, and the
+    /// resync for anything the subscriptions missed, such as changes while either could not
+    /// be subscribed to.
+    ///
+    /// Skips the chain reads while nothing is subscribed, starting over from a new baseline.
+    async fn run_event_poller(self, channel: EdgeEventChannel) {
+        use crate::events::{ChannelSnapshot, EdgeEventTracker, EdgeSnapshot};
+        use crate::traits::EdgeNodeApi;
+
+        let mut channel_changes = self.channel_changes().fuse();
+        let mut peer_changes = self
+            .peer_changes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .unwrap_or_else(|| futures::stream::pending().boxed())
+            .fuse();
+        let mut tracker = EdgeEventTracker::default();
+        let mut ticks = tokio::time::interval(self.event_poll_interval);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            let (reported, compare) = tokio::select! {
+                _ = ticks.tick() => (Vec::new(), true),
+                _ = self.event_resync.notified() => (Vec::new(), true),
+                Some(changed) = channel_changes.next() => (tracker.update_channel(changed), true),
+                Some((key, connected)) = peer_changes.next() => {
+                    match self.chain_api().packet_key_to_chain_key(&key) {
+                        Ok(Some(peer)) => (tracker.update_peer(peer, connected), false),
+                        _ => (Vec::new(), false),
+                    }
+                }
+            };
+            if channel.is_idle() {
+                tracker = EdgeEventTracker::default();
+                continue;
+            }
+            for event in reported {
+                channel.send(event);
+            }
+            if !compare {
+                continue;
+            }
+            let snapshot = EdgeSnapshot {
+                state: EdgeNodeApi::status(&self),
+                channels: EdgeNodeApi::my_outgoing_channels(&self)
+                    .await
+                    .inspect_err(|error| tracing::debug!(%error, "event poller: no channels"))
+                    .ok()
+                    .map(|channels| channels.iter().map(ChannelSnapshot::from).collect()),
+                balances: EdgeNodeApi::balances(&self)
+                    .await
+                    .inspect_err(|error| tracing::debug!(%error, "event poller: no balances"))
+                    .ok(),
+                peers: EdgeNodeApi::connected_peer_addresses(&self)
+                    .await
+                    .inspect_err(|error| tracing::debug!(%error, "event poller: no peers"))
+                    .ok(),
+            };
+            for event in tracker.update(snapshot) {
+                channel.send(event);
+            }
+        }
+    }
+
+    /// Changes to the node's outgoing channels as the chain connector indexes them, or a
+    /// stream that never yields when the connector cannot be subscribed to.
+    fn channel_changes(
+        &self,
+    ) -> futures::stream::BoxStream<'static, crate::events::ChannelSnapshot> {
+        use hopr_lib::api::chain::{ChainEvent, ChainEvents};
+
+        let me = self.me_onchain();
+        match self.chain_api().subscribe() {
+            Ok(events) => events
+                .filter_map(move |event| {
+                    let channel = match event {
+                        ChainEvent::ChannelOpened(channel)
+                        | ChainEvent::ChannelClosureInitiated(channel)
+                        | ChainEvent::ChannelClosed(channel)
+                        | ChainEvent::ChannelBalanceIncreased(channel, _)
+                        | ChainEvent::ChannelBalanceDecreased(channel, _) => Some(channel),
+                        _ => None,
+                    };
+                    futures::future::ready(
+                        channel
+                            .filter(|channel| channel.source == me)
+                            .map(|channel| crate::events::ChannelSnapshot::from(&channel)),
+                    )
+                })
+                .boxed(),
+            Err(error) => {
+                tracing::warn!(%error, "cannot subscribe to chain events; channel changes are only polled");
+                futures::stream::pending().boxed()
+            }
+        }
+    }
+
+    /// The current minimum ticket price and incoming winning probability, retried under the
+    /// node's [`RetryPolicy`].
+    async fn ticket_stats(&self) -> anyhow::Result<(HoprBalance, f64)> {
+        let chain = self.chain_api();
+        self.retry
+            .retry("ticket_stats", || async {
+                let ticket_price = chain.minimum_ticket_price().await?;
+                let win_prob = chain.minimum_incoming_ticket_win_prob().await?.as_f64();
+                anyhow::Ok((ticket_price, win_prob))
+            })
+            .await
+    }
+
     /// The node's off-chain peer ID as a string (libp2p representation).
     ///
     /// Derived from the packet key stored at construction time.
@@ -332,15 +744,68 @@ impl Edgli {
     /// recommendation reflects only the additional stake the strategy needs.
     /// Channels open to disconnected peers are not counted — the strategy will
     /// close and replace them.
+    ///
+    /// The xDAI is projected over the default [`super::strategy::XdaiBudgetConfig`] as in
+    /// [`Self::xdai_budget`], from the gas this node's own channel transactions used.
     pub async fn ideal_balance_recommendation(
         &self,
         cfg: &super::strategy::IncentiveConfiguration,
     ) -> anyhow::Result<super::strategy::BalanceRecommendation> {
         let chain = self.chain_api();
-        let ticket_price = chain.minimum_ticket_price().await?;
-        let win_prob = chain.minimum_incoming_ticket_win_prob().await?.as_f64();
-        let max_fee_per_gas = crate::blokli::query_max_fee_per_gas(chain.client()).await?;
+        let (ticket_price, win_prob) = self.ticket_stats().await?;
+        let max_fee_per_gas = self.max_fee_per_gas().await?;
 
+        let source = HasChainApi::identity(&*self.hopr).node_address;
+        let missing = self.missing_channels(cfg).await?;
+        // Zero for a running node — hopr-lib announces during startup — but
+        // verified on-chain rather than assumed.
+        // A running node cannot start without a Safe, so it is always deployed here.
+        let costs = super::strategy::compute_costs_to_start(chain, Some(source), true).await?;
+        super::strategy::compute_balance_recommendation(
+            ticket_price,
+            win_prob,
+            missing,
+            costs,
+            cfg,
+            &self.observed_budget(&super::strategy::XdaiBudgetConfig::default()),
+            max_fee_per_gas,
+        )
+    }
+
+    /// Projects the xDAI this node spends on gas over `budget.horizon`: the channels still
+    /// missing towards `cfg.target_open_channels` (counted as by
+    /// [`Self::ideal_balance_recommendation`]), their churn and top-ups, priced at
+    /// [`Self::max_fee_per_gas`]. See [`crate::strategy::project_xdai_budget`].
+    ///
+    /// Channel transactions are counted at the median gas the ones submitted through this
+    /// handle used (see [`ObservedGas`]); `budget.gas_per_channel_tx` stands in until one has
+    /// been measured.
+    pub async fn xdai_budget(
+        &self,
+        cfg: &super::strategy::IncentiveConfiguration,
+        budget: &super::strategy::XdaiBudgetConfig,
+    ) -> anyhow::Result<super::strategy::XdaiBudget> {
+        let source = HasChainApi::identity(&*self.hopr).node_address;
+        let missing = self.missing_channels(cfg).await?;
+        let max_fee_per_gas = self.max_fee_per_gas().await?;
+        // Verified on-chain as in `ideal_balance_recommendation`; zero for a running node.
+        let costs =
+            super::strategy::compute_costs_to_start(self.chain_api(), Some(source), true).await?;
+        super::strategy::project_xdai_budget(
+            cfg,
+            &self.observed_budget(budget),
+            costs.txs_to_start,
+            missing,
+            max_fee_per_gas,
+        )
+    }
+
+    /// Channels still to open towards `cfg.target_open_channels`; only channels to connected
+    /// peers count as open.
+    async fn missing_channels(
+        &self,
+        cfg: &super::strategy::IncentiveConfiguration,
+    ) -> anyhow::Result<usize> {
         let source = HasChainApi::identity(&*self.hopr).node_address;
         let all_channels = IncentiveChannelOperations::channels_from(&*self.hopr, source)
             .await
@@ -357,19 +822,7 @@ impl Edgli {
             .filter(|c| c.status == ChannelStatus::Open && connected.contains(&c.destination))
             .count();
 
-        let missing = cfg.target_open_channels.saturating_sub(open_to_connected);
-        // Zero for a running node — hopr-lib announces during startup — but
-        // verified on-chain rather than assumed.
-        // A running node cannot start without a Safe, so it is always deployed here.
-        let costs = super::strategy::compute_costs_to_start(chain, Some(source), true).await?;
-        super::strategy::compute_balance_recommendation(
-            ticket_price,
-            win_prob,
-            missing,
-            costs,
-            cfg,
-            max_fee_per_gas,
-        )
+        Ok(cfg.target_open_channels.saturating_sub(open_to_connected))
     }
 
     /// Returns the data-throughput capacities of every wxHOPR stake the node can
@@ -383,8 +836,7 @@ impl Edgli {
         &self,
     ) -> anyhow::Result<super::strategy::CapacityAllocations> {
         let chain = self.chain_api();
-        let ticket_price = chain.minimum_ticket_price().await?;
-        let win_prob = chain.minimum_incoming_ticket_win_prob().await?.as_f64();
+        let (ticket_price, win_prob) = self.ticket_stats().await?;
 
         let node_address = HasChainApi::identity(&*self.hopr).node_address;
         let channels = IncentiveChannelOperations::channels_from(&*self.hopr, node_address)
@@ -398,17 +850,28 @@ impl Edgli {
         .await
         .map_err(|e| anyhow::anyhow!("{e}"))?
         {
-            Some(safe) => chain
-                .balance(safe.address)
-                .await
-                .map_err(|e| anyhow::anyhow!("{e}"))?,
+            Some(safe) => {
+                self.retry
+                    .retry("balance", || async {
+                        chain
+                            .balance(safe.address)
+                            .await
+                            .map_err(|e| anyhow::anyhow!("{e}"))
+                    })
+                    .await?
+            }
             None => HoprBalance::zero(),
         };
 
-        let node_wxhopr: HoprBalance = chain
-            .balance(node_address)
-            .await
-            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        let node_wxhopr: HoprBalance = self
+            .retry
+            .retry("balance", || async {
+                chain
+                    .balance(node_address)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{e}"))
+            })
+            .await?;
 
         let mut peer_allocations = std::collections::HashMap::new();
         for c in channels
@@ -419,11 +882,161 @@ impl Edgli {
             peer_allocations.insert(c.destination, capacity);
         }
 
-        Ok(super::strategy::CapacityAllocations {
+        let allocations = super::strategy::CapacityAllocations {
             peer_allocations,
             node: super::strategy::compute_capacity(node_wxhopr, ticket_price, win_prob)?,
             safe: super::strategy::compute_capacity(safe_balance, ticket_price, win_prob)?,
+        };
+        crate::metrics::record_capacity_allocations(&allocations);
+        Ok(allocations)
+    }
+
+    /// Starts a [`BalanceMonitor`] checking the node's balances every `cfg.interval` against
+    /// the thresholds `incentives` resolve to; see [`crate::monitor`]. [`Self::shutdown`]
+    /// stops it.
+    ///
+    /// With [`BalanceMonitorConfig::pause_reactor_on_low_xdai`], the node's reactors are paused
+    /// while xDAI is low; see [`Self::run_supervised_reactor`] and [`Self::run_reactor_from_cfg`].
+    pub fn spawn_balance_monitor(
+        &self,
+        incentives: super::strategy::IncentiveConfiguration,
+        cfg: BalanceMonitorConfig,
+    ) -> anyhow::Result<BalanceMonitor> {
+        incentives.validate()?;
+        super::strategy::compute_funding_config(&incentives)?;
+
+        let publisher = crate::monitor::Publisher::new(&cfg);
+        let events = publisher.sender();
+        let pauses = cfg
+            .pause_reactor_on_low_xdai
+            .then(|| self.lifecycle.clone());
+        let (min_xdai_transactions, gas_per_tx) = (cfg.min_xdai_transactions, cfg.gas_per_tx);
+        let node = self.clone();
+        let (monitor, abort_handle) = futures::future::abortable(crate::monitor::run(
+            cfg,
+            publisher,
+            move || {
+                let node = node.clone();
+                let incentives = incentives.clone();
+                async move {
+                    node.balance_check(&incentives, min_xdai_transactions, gas_per_tx)
+                        .await
+                }
+            },
+            {
+                let pauses = pauses.clone();
+                move |xdai_low| {
+                    if let Some(lifecycle) = &pauses {
+                        lifecycle.pause_reactors(PauseReason::LowXdai, xdai_low);
+                    }
+                }
+            },
+        ));
+
+        tokio::spawn(monitor);
+        self.lifecycle.register_task(abort_handle.clone());
+        Ok(BalanceMonitor {
+            events,
+            abort_handle,
+            pauses,
+        })
+    }
+
+    /// The balances a [`BalanceMonitor`] checks and the thresholds it checks them against.
+    async fn balance_check(
+        &self,
+        incentives: &super::strategy::IncentiveConfiguration,
+        min_xdai_transactions: u64,
+        gas_per_tx: u64,
+    ) -> anyhow::Result<(BalanceSnapshot, BalanceThresholds)> {
+        use crate::traits::EdgeNodeApi;
+
+        let balances = EdgeNodeApi::balances(self).await?;
+        let channels = EdgeNodeApi::my_outgoing_channels(self)
+            .await?
+            .into_iter()
+            .filter(|c| c.status == ChannelStatus::Open)
+            .map(|c| (c.destination, c.balance))
+            .collect();
+        let (ticket_price, win_prob) = self.ticket_stats().await?;
+        let funding =
+            super::strategy::compute_funding_thresholds(incentives, ticket_price, win_prob)?;
+        let xdai = u128::from(min_xdai_transactions)
+            .saturating_mul(gas_per_tx.into())
+            .saturating_mul(self.max_fee_per_gas().await?);
+
+        Ok((
+            BalanceSnapshot {
+                node_xdai: balances.node_xdai,
+                safe_wxhopr: balances.safe_wxhopr,
+                channels,
+            },
+            BalanceThresholds {
+                xdai: xdai.into(),
+                safe: funding.min_safe_balance,
+                channel: funding.channel_lower_threshold,
+            },
+        ))
+    }
+
+    /// Moves the node's wxHOPR above `cfg.reserve` into its Safe, unless that is less than
+    /// `cfg.min_amount` or gas is above [`SweepConfig::gas_limit`]; see [`crate::sweep`].
+    pub async fn sweep_wxhopr(&self, cfg: &SweepConfig) -> anyhow::Result<SweepOutcome> {
+        use crate::traits::EdgeNodeApi;
+        use hopr_lib::api::chain::ChainWriteAccountOperations;
+
+        let node_wxhopr = EdgeNodeApi::balances(self).await?.node_wxhopr;
+        let Some(amount) = cfg.sweepable(node_wxhopr) else {
+            return Ok(SweepOutcome::BelowMinimum { node_wxhopr });
+        };
+        let max_fee_per_gas = self.max_fee_per_gas().await?;
+        if let Some(limit) = cfg.gas_limit(self.gas_cap.max_fee_per_gas())
+            && max_fee_per_gas > limit
+        {
+            return Ok(SweepOutcome::GasTooExpensive {
+                max_fee_per_gas,
+                limit,
+            });
+        }
+
+        let safe_address = EdgeNodeApi::safe_address(self);
+        crate::metrics::blokli_request("withdraw_wxhopr", self.blokli_endpoint(), async {
+            self.chain_api()
+                .withdraw(amount, &safe_address)
+                .await?
+                .await?;
+            anyhow::Ok(())
         })
+        .await?;
+        Ok(SweepOutcome::Swept(amount))
+    }
+
+    /// Starts a task sweeping the node's wxHOPR into its Safe every `cfg.interval`; see
+    /// [`Self::sweep_wxhopr`]. [`Self::shutdown`] stops it.
+    ///
+    /// Returns an [`AbortHandle`] that stops the sweeper when aborted.
+    pub fn spawn_wxhopr_sweeper(&self, cfg: SweepConfig) -> anyhow::Result<AbortHandle> {
+        cfg.validate()?;
+        let node = self.clone();
+        let (sweeper, abort_handle) = abortable(async move { node.run_wxhopr_sweeper(&cfg).await });
+
+        tokio::spawn(sweeper);
+        self.lifecycle.register_task(abort_handle.clone());
+        Ok(abort_handle)
+    }
+
+    /// Sweeps every `cfg.interval`, logging what each sweep did; never returns.
+    async fn run_wxhopr_sweeper(&self, cfg: &SweepConfig) {
+        let mut ticks = tokio::time::interval(cfg.interval);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticks.tick().await;
+            match self.sweep_wxhopr(cfg).await {
+                Ok(outcome @ SweepOutcome::Swept(_)) => info!(%outcome, "wxHOPR sweep"),
+                Ok(outcome) => tracing::debug!(%outcome, "wxHOPR sweep skipped"),
+                Err(error) => tracing::warn!(%error, "wxHOPR sweep failed"),
+            }
+        }
     }
 
     /// Run a node with HOPR edge strategies integrated.
@@ -431,48 +1044,198 @@ impl Edgli {
     /// The default reactor runs a single [`ChannelLifecycleStrategy`] which
     /// owns open / fund / close / finalize for outgoing payment channels.
     ///
-    /// Returns an [`AbortHandle`] that stops the strategy reactor when aborted.
+    /// Returns an [`AbortHandle`] that stops the strategy reactor when aborted; [`Self::shutdown`]
+    /// aborts it as well. A reactor that fails is not restarted; see [`Self::run_supervised_reactor`].
+    ///
+    /// While the chain's gas price is above the node's cap, or a balance monitor pauses
+    /// reactors, the reactor is no longer polled, so it submits nothing until resumed.
+    ///
+    /// [`ChannelLifecycleStrategy`]: hopr_strategy::channel_lifecycle::ChannelLifecycleStrategy
     #[cfg(feature = "blokli")]
     pub fn run_reactor_from_cfg(
         &self,
         cfg: super::strategy::MultiStrategyConfig,
     ) -> anyhow::Result<AbortHandle> {
-        use super::strategy::EdgeStrategyKind;
-        use hopr_strategy::{
-            channel_lifecycle::ChannelLifecycleStrategy,
-            strategy::{MultiStrategy, Strategy},
-        };
+        let mut reactor = build_reactor(self, cfg)?;
+        let mut paused = self.lifecycle.reactors_paused();
+        self.watch_gas_cap();
 
-        let node = self.hopr.clone();
+        let (abortable, abort_handle) = futures::future::abortable(async move {
+            crate::metrics::record_reactor_start();
+            if let Err(e) = reactor.run_unless_paused(&mut paused).await {
+                crate::metrics::record_reactor_failure();
+                tracing::error!(%e, "edge strategy reactor failed");
+            }
+        });
 
-        // `build` became fallible in hopr-strategy 0.26. Propagate rather than unwrap: a strategy
-        // that failed to construct would otherwise leave the reactor running with nothing driving
-        // channel lifecycle, which looks like a healthy node that never opens a channel.
-        let strategies = cfg
-            .strategies
-            .into_iter()
-            .map(|kind| -> anyhow::Result<Box<dyn Strategy + Send>> {
-                match kind {
-                    EdgeStrategyKind::ChannelLifecycle(sub_cfg) => {
-                        Ok(ChannelLifecycleStrategy::new(sub_cfg).build(Arc::clone(&node))?)
-                    }
-                }
-            })
-            .collect::<anyhow::Result<Vec<_>>>()?;
+        tokio::spawn(abortable);
+        self.lifecycle.register_reactor(abort_handle.clone());
+        Ok(abort_handle)
+    }
+
+    /// Runs the default edge reactor for `incentives` and restarts it whenever it fails.
+    ///
+    /// The reactor is rebuilt from `incentives` on every restart, paced by `backoff`. A
+    /// configuration that cannot build a reactor is rejected here rather than retried; a
+    /// reactor that returns without error is considered finished and is not restarted.
+    ///
+    /// While the chain's gas price is above the node's cap, or a balance monitor pauses
+    /// reactors, the reactor is stopped, and it is rebuilt once no pause reason holds.
+    ///
+    /// Returns an [`AbortHandle`] that stops the reactor and its supervision when aborted.
+    #[cfg(feature = "blokli")]
+    pub fn run_supervised_reactor(
+        &self,
+        incentives: super::strategy::IncentiveConfiguration,
+        backoff: super::strategy::ReactorBackoff,
+    ) -> anyhow::Result<AbortHandle> {
+        use super::strategy::default_strategy_cfg;
 
-        let mut multi_strategy = MultiStrategy::new(strategies);
+        let node = self.clone();
+        let first = build_reactor(&node, default_strategy_cfg(&incentives)?)?;
+        let mut paused = self.lifecycle.reactors_paused();
+        self.watch_gas_cap();
 
         let (abortable, abort_handle) = futures::future::abortable(async move {
-            if let Err(e) = multi_strategy.run().await {
-                tracing::error!(%e, "edge strategy reactor failed");
+            let mut next = Some(first);
+            let mut failures = 0u32;
+            loop {
+                let pause = *paused.borrow_and_update();
+                if pause.any() {
+                    tracing::warn!(?pause, "edge strategy reactor paused");
+                    let _ = paused.wait_for(|p| !p.any()).await;
+                    tracing::info!("edge strategy reactor resumed");
+                }
+                let started = std::time::Instant::now();
+                let built = match next.take() {
+                    Some(reactor) => Ok(reactor),
+                    None => {
+                        default_strategy_cfg(&incentives).and_then(|cfg| build_reactor(&node, cfg))
+                    }
+                };
+                let outcome = match built {
+                    Ok(mut reactor) => {
+                        crate::metrics::record_reactor_start();
+                        tokio::select! {
+                            result = reactor.run() => result,
+                            // A pause reason arose; the reactor is rebuilt on resuming.
+                            _ = paused.wait_for(|p| p.any()) => continue,
+                        }
+                    }
+                    Err(e) => Err(e),
+                };
+
+                match outcome {
+                    Ok(()) => {
+                        tracing::info!("edge strategy reactor finished");
+                        break;
+                    }
+                    Err(e) => {
+                        crate::metrics::record_reactor_failure();
+                        if started.elapsed() >= backoff.reset_after {
+                            failures = 0;
+                        }
+                        failures = failures.saturating_add(1);
+                        let delay = backoff.delay(failures);
+                        tracing::error!(%e, failures, ?delay, "edge strategy reactor failed, restarting");
+                        tokio::time::sleep(delay).await;
+                    }
+                }
             }
         });
 
         tokio::spawn(abortable);
+        self.lifecycle.register_reactor(abort_handle.clone());
         Ok(abort_handle)
     }
 }
 
+/// One run of an edge reactor: the hopr-strategy strategies and the sweepers beside them.
+#[cfg(feature = "blokli")]
+struct EdgeReactor {
+    strategies: hopr_strategy::strategy::MultiStrategy,
+    sweeps: Vec<SweepConfig>,
+    node: Edgli,
+}
+
+#[cfg(feature = "blokli")]
+impl EdgeReactor {
+    /// Runs until the hopr-strategy strategies stop; the sweepers never do on their own.
+    async fn run(&mut self) -> anyhow::Result<()> {
+        let Self {
+            strategies,
+            sweeps,
+            node,
+        } = self;
+        let sweeping = async {
+            futures::future::join_all(sweeps.iter().map(|cfg| node.run_wxhopr_sweeper(cfg))).await;
+            std::future::pending::<()>().await
+        };
+        tokio::select! {
+            result = strategies.run() => result.map_err(|e| anyhow::anyhow!("{e}")),
+            () = sweeping => unreachable!("sweepers run until the reactor stops"),
+        }
+    }
+
+    /// Like [`Self::run`], but stops polling the reactor while `paused` holds a reason, and
+    /// picks it up where it stopped once none holds.
+    async fn run_unless_paused(
+        &mut self,
+        paused: &mut tokio::sync::watch::Receiver<crate::shutdown::ReactorPause>,
+    ) -> anyhow::Result<()> {
+        let run = self.run();
+        tokio::pin!(run);
+        loop {
+            let pause = *paused.borrow_and_update();
+            if pause.any() {
+                tracing::warn!(?pause, "edge strategy reactor paused");
+                let _ = paused.wait_for(|p| !p.any()).await;
+                tracing::info!("edge strategy reactor resumed");
+            }
+            tokio::select! {
+                result = &mut run => return result,
+                _ = paused.wait_for(|p| p.any()) => {}
+            }
+        }
+    }
+}
+
+/// Builds the strategies in `cfg` against `node` into one reactor.
+#[cfg(feature = "blokli")]
+fn build_reactor(
+    node: &Edgli,
+    cfg: super::strategy::MultiStrategyConfig,
+) -> anyhow::Result<EdgeReactor> {
+    use super::strategy::EdgeStrategyKind;
+    use hopr_strategy::{
+        channel_lifecycle::ChannelLifecycleStrategy,
+        strategy::{MultiStrategy, Strategy},
+    };
+
+    // `build` became fallible in hopr-strategy 0.26. Propagate rather than unwrap: a strategy
+    // that failed to construct would otherwise leave the reactor running with nothing driving
+    // channel lifecycle, which looks like a healthy node that never opens a channel.
+    let mut strategies: Vec<Box<dyn Strategy + Send>> = Vec::new();
+    let mut sweeps = Vec::new();
+    for kind in cfg.strategies {
+        match kind {
+            EdgeStrategyKind::ChannelLifecycle(sub_cfg) => {
+                strategies.push(ChannelLifecycleStrategy::new(sub_cfg).build(node.as_hopr())?)
+            }
+            EdgeStrategyKind::WxHoprSweep(sweep) => {
+                sweep.validate()?;
+                sweeps.push(sweep);
+            }
+        }
+    }
+
+    Ok(EdgeReactor {
+        strategies: MultiStrategy::new(strategies),
+        sweeps,
+        node: node.clone(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,955 @@
+//! The `edgli` binary's configuration file and its scaffolding for `edgli config init`.
+//!
+//! The file is a [`HoprLibConfig`] with one extra, optional top-level `edge` section
+//! ([`EdgeSection`]) for settings that belong to the edge client rather than hopr-lib.
+//! [`NodeConfig`] holds both halves.
+//!
+//! [`edge_config`] builds a complete configuration with the edge defaults the binary
+//! runs with, and [`render_config`] serialises it to YAML with inline comments on the
+//! sections operators are expected to touch. The rendered text is parsed back before it is
+//! returned, so a file written from it always loads.
+
+use std::path::Path;
+use std::time::Duration;
+
+use bytesize::ByteSize;
+use hopr_lib::{
+    api::types::primitive::prelude::{Address, HoprBalance},
+    config::{HoprLibConfig, HostConfig},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{EdgliError, Result};
+use crate::strategy::{CapacitySizingMode, IncentiveConfiguration};
+use crate::sweep::SweepConfig;
+
+/// Top-level key of the [`EdgeSection`] in the configuration file.
+pub const EDGE_SECTION_KEY: &str = "edge";
+
+/// Minimum message-acknowledgement rate passed to [`crate::latency_path_planner_config`]
+/// for the edge path planner.
+pub const DEFAULT_MIN_ACK_RATE: f64 = 0.1;
+
+/// Edge mixer minimum delay, applied over the file's unless `edge.custom_protocol` is set.
+/// `HOPR_INTERNAL_MIXER_MINIMUM_DELAY_IN_MS` overrides either in the binary.
+pub const EDGE_MIXER_MIN_DELAY: Duration = Duration::ZERO;
+
+/// Edge mixer delay range, applied over the file's unless `edge.custom_protocol` is set.
+/// `HOPR_INTERNAL_MIXER_DELAY_RANGE_IN_MS` overrides either in the binary.
+pub const EDGE_MIXER_DELAY_RANGE: Duration = Duration::from_millis(1);
+
+const HEADER: &str = "\
+# edgli node configuration (HoprLibConfig).
+# Generated by `edgli config init`; pass it to `edgli --config <path>`.
+";
+
+/// Comments emitted above a section, keyed by its dotted path in the YAML document.
+const SECTION_COMMENTS: &[(&str, &str)] = &[
+    (
+        "host",
+        "Address and port this node listens on and announces. A loopback address is \
+         rejected unless protocol.transport.prefer_local_addresses is true.",
+    ),
+    (
+        "safe_module",
+        "Safe and node-management module the node operates through. All-zero addresses are \
+         placeholders: deploy a Safe first, then fill them in (or re-run `edgli config init \
+         --discover-safe`).",
+    ),
+    ("protocol", "Mixnet protocol settings."),
+    (
+        "protocol.mixer",
+        "Packet mixer delays; edge nodes favour latency (0 ms minimum, 1 ms range) and use \
+         these over the ones set here unless edge.custom_protocol is true. \
+         HOPR_INTERNAL_MIXER_MINIMUM_DELAY_IN_MS and HOPR_INTERNAL_MIXER_DELAY_RANGE_IN_MS, \
+         when set, override the delays at startup.",
+    ),
+    (
+        "protocol.path_planner",
+        "Latency-optimised path planner (see edgli::latency_path_planner_config), used over \
+         the one set here unless edge.custom_protocol is true.",
+    ),
+    (
+        "protocol.transport",
+        "Transport settings. Set prefer_local_addresses to true to run against a same-host \
+         test cluster.",
+    ),
+];
+
+/// Sections whose children get their own comments, rather than being emitted as one block.
+const EXPANDED_SECTIONS: &[&str] = &["protocol"];
+
+const EDGE_SECTION_COMMENT: &str = "Edge client settings. These are re-read on SIGHUP and \
+     applied without a restart, except custom_protocol.";
+
+/// Emitted in place of an empty [`EdgeSection`], so operators can discover it.
+const EDGE_SECTION_EXAMPLE: &str = "\
+# Edge client settings, re-read on SIGHUP and applied without a restart:
+# edge:
+#   # tracing filter directives; RUST_LOG takes precedence when set.
+#   log_filter: info,hopr_lib=debug
+#   # Keep protocol.mixer and protocol.path_planner as set above instead of the edge
+#   # defaults; takes effect after a restart.
+#   custom_protocol: false
+#   # Channel-lifecycle strategy; every field is optional.
+#   strategy:
+#     min_open_channels: 5
+#     target_open_channels: 8
+#     channel_allowlist: []
+#     channel_capacity: 100 MiB
+#     sizing_mode:
+#       mode: probabilistic
+#       success_probability: 0.99
+#     # Move wxHOPR sent to the node address into the Safe.
+#     sweep:
+#       reserve: 0 wxHOPR
+";
+
+/// The binary's configuration file: hopr-lib's configuration plus the `edge` section.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeConfig {
+    /// Everything except the `edge` section.
+    pub hopr: HoprLibConfig,
+    /// The `edge` section; all defaults when the file has none.
+    pub edge: EdgeSection,
+}
+
+/// Edge-client settings kept under the top-level `edge` key.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EdgeSection {
+    /// `tracing` filter directives, e.g. `info,hopr_lib=debug`. `RUST_LOG` takes precedence
+    /// when set. Default: `None` — the binary's built-in filter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_filter: Option<String>,
+
+    /// Keep the file's `protocol.mixer` delays and `protocol.path_planner`, filling in the edge
+    /// defaults only where they are left out. Without it the edge client applies its defaults
+    /// ([`EDGE_MIXER_MIN_DELAY`], [`EDGE_MIXER_DELAY_RANGE`] and the latency path planner)
+    /// over whatever the file sets. Default: `false`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub custom_protocol: bool,
+
+    /// Channel-lifecycle strategy settings, mapped onto [`IncentiveConfiguration`].
+    /// Default: `None` — no strategy configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<StrategySection>,
+}
+
+impl EdgeSection {
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The configured [`IncentiveConfiguration`], or its defaults without a `strategy`.
+    pub fn incentive_configuration(&self) -> anyhow::Result<IncentiveConfiguration> {
+        match &self.strategy {
+            Some(strategy) => strategy.to_incentive_configuration(),
+            None => Ok(IncentiveConfiguration::default()),
+        }
+    }
+}
+
+/// Serialisable mirror of [`IncentiveConfiguration`]; unset fields keep its defaults.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StrategySection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_open_channels: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_open_channels: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_allowlist: Option<Vec<Address>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel_capacity: Option<ByteSize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub topup_capacity: Option<ByteSize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lower_capacity_threshold: Option<ByteSize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_safe_capacity_required: Option<ByteSize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sizing_mode: Option<SizingModeSection>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sweep: Option<SweepSection>,
+}
+
+/// Serialisable mirror of [`CapacitySizingMode`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case", deny_unknown_fields)]
+pub enum SizingModeSection {
+    Deterministic,
+    Probabilistic { success_probability: f64 },
+}
+
+/// Serialisable mirror of [`SweepConfig`]; unset fields keep its defaults.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SweepSection {
+    /// wxHOPR left on the node address, e.g. `5 wxHOPR`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reserve: Option<String>,
+    /// Smallest amount worth sweeping, e.g. `1 wxHOPR`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_amount: Option<String>,
+    /// Highest `max_fee_per_gas`, in wei, a sweep is submitted at.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
+}
+
+impl SweepSection {
+    /// The [`SweepConfig`] this section describes, validated.
+    pub fn to_sweep_config(&self) -> anyhow::Result<SweepConfig> {
+        let balance = |field: &str, value: &Option<String>, default: HoprBalance| {
+            value.as_deref().map_or(Ok(default), |amount| {
+                amount
+                    .parse::<HoprBalance>()
+                    .map_err(|e| anyhow::anyhow!("invalid sweep {field} '{amount}': {e}"))
+            })
+        };
+        let defaults = SweepConfig::default();
+        let cfg = SweepConfig {
+            reserve: balance("reserve", &self.reserve, defaults.reserve)?,
+            min_amount: balance("min_amount", &self.min_amount, defaults.min_amount)?,
+            max_fee_per_gas: self.max_fee_per_gas,
+            interval: self
+                .interval_secs
+                .map_or(defaults.interval, Duration::from_secs),
+        };
+        cfg.validate()?;
+        Ok(cfg)
+    }
+}
+
+impl From<&SizingModeSection> for CapacitySizingMode {
+    fn from(mode: &SizingModeSection) -> Self {
+        match mode {
+            SizingModeSection::Deterministic => CapacitySizingMode::Deterministic,
+            SizingModeSection::Probabilistic {
+                success_probability,
+            } => CapacitySizingMode::Probabilistic {
+                success_probability: *success_probability,
+            },
+        }
+    }
+}
+
+impl StrategySection {
+    /// The [`IncentiveConfiguration`] this section describes, validated.
+    pub fn to_incentive_configuration(&self) -> anyhow::Result<IncentiveConfiguration> {
+        let defaults = IncentiveConfiguration::default();
+        let cfg = IncentiveConfiguration {
+            min_open_channels: self.min_open_channels.unwrap_or(defaults.min_open_channels),
+            target_open_channels: self
+                .target_open_channels
+                .unwrap_or(defaults.target_open_channels),
+            channel_allowlist: self
+                .channel_allowlist
+                .as_ref()
+                .map(|addresses| addresses.iter().copied().collect()),
+            channel_capacity: self.channel_capacity,
+            topup_capacity: self.topup_capacity,
+            lower_capacity_threshold: self.lower_capacity_threshold,
+            min_safe_capacity_required: self.min_safe_capacity_required,
+            sizing_mode: self.sizing_mode.as_ref().map(CapacitySizingMode::from),
+            sweep: self
+                .sweep
+                .as_ref()
+                .map(SweepSection::to_sweep_config)
+                .transpose()?,
+        };
+        cfg.validate()?;
+        Ok(cfg)
+    }
+}
+
+/// Returns a complete [`NodeConfig`] with the edge defaults applied: the low-latency
+/// mixer and [`crate::latency_path_planner_config`], and an empty `edge` section.
+///
+/// The Safe and module addresses are left as all-zero placeholders; set
+/// `hopr.safe_module.safe_address` and `hopr.safe_module.module_address` once they are
+/// known.
+pub fn edge_config(host: HostConfig) -> NodeConfig {
+    let mut hopr = HoprLibConfig {
+        host,
+        ..Default::default()
+    };
+    hopr.protocol.mixer.min_delay = EDGE_MIXER_MIN_DELAY;
+    hopr.protocol.mixer.delay_range = EDGE_MIXER_DELAY_RANGE;
+    hopr.protocol.path_planner = crate::latency_path_planner_config(DEFAULT_MIN_ACK_RATE);
+    NodeConfig {
+        hopr,
+        edge: EdgeSection::default(),
+    }
+}
+
+/// Whether `cfg` still carries the all-zero Safe or module placeholder.
+pub fn has_placeholder_safe_module(cfg: &HoprLibConfig) -> bool {
+    cfg.safe_module.safe_address == Address::default()
+        || cfg.safe_module.module_address == Address::default()
+}
+
+fn comment_lines(out: &mut String, comment: &str, indent: usize) {
+    // Wrap at roughly 88 columns so the generated file reads like a hand-written one.
+    let mut line = String::new();
+    for word in comment.split_whitespace() {
+        if !line.is_empty() && indent + 2 + line.len() + 1 + word.len() > 88 {
+            out.push_str(&format!("{:indent$}# {line}\n", ""));
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        out.push_str(&format!("{:indent$}# {line}\n", ""));
+    }
+}
+
+fn section_comment(path: &str) -> Option<&'static str> {
+    SECTION_COMMENTS
+        .iter()
+        .find(|(p, _)| *p == path)
+        .map(|(_, c)| *c)
+}
+
+fn emit_entry(
+    out: &mut String,
+    key: &serde_yaml::Value,
+    value: &serde_yaml::Value,
+    path: &str,
+    indent: usize,
+) -> Result<()> {
+    if let Some(comment) = section_comment(path) {
+        comment_lines(out, comment, indent);
+    }
+
+    if let (Some(children), true) = (value.as_mapping(), EXPANDED_SECTIONS.contains(&path)) {
+        let key = key.as_str().unwrap_or_default();
+        out.push_str(&format!("{:indent$}{key}:\n", ""));
+        for (child_key, child_value) in children {
+            let child_path = format!("{path}.{}", child_key.as_str().unwrap_or_default());
+            emit_entry(out, child_key, child_value, &child_path, indent + 2)?;
+        }
+        return Ok(());
+    }
+
+    let mut single = serde_yaml::Mapping::new();
+    single.insert(key.clone(), value.clone());
+    let rendered = serde_yaml::to_string(&single)
+        .map_err(|e| EdgliError::SerializationError(e.to_string()))?;
+    for line in rendered.lines() {
+        out.push_str(&format!("{:indent$}{line}\n", ""));
+    }
+    Ok(())
+}
+
+/// Serialises `cfg` to YAML with inline comments on the edge-relevant sections.
+///
+/// The output is parsed back and compared against `cfg`, so a [`EdgliError::ValidationError`]
+/// here means the rendering lost information, never that the caller's config is invalid.
+pub fn render_config(cfg: &NodeConfig) -> Result<String> {
+    let value = serde_yaml::to_value(&cfg.hopr)
+        .map_err(|e| EdgliError::SerializationError(e.to_string()))?;
+    let mapping = value.as_mapping().ok_or_else(|| {
+        EdgliError::SerializationError("configuration did not serialise to a mapping".into())
+    })?;
+
+    let mut out = String::from(HEADER);
+    for (key, value) in mapping {
+        out.push('\n');
+        let path = key.as_str().unwrap_or_default().to_string();
+        emit_entry(&mut out, key, value, &path, 0)?;
+    }
+
+    out.push('\n');
+    if cfg.edge.is_empty() {
+        out.push_str(EDGE_SECTION_EXAMPLE);
+    } else {
+        comment_lines(&mut out, EDGE_SECTION_COMMENT, 0);
+        let edge = serde_yaml::to_value(&cfg.edge)
+            .map_err(|e| EdgliError::SerializationError(e.to_string()))?;
+        emit_entry(
+            &mut out,
+            &EDGE_SECTION_KEY.into(),
+            &edge,
+            EDGE_SECTION_KEY,
+            0,
+        )?;
+    }
+
+    let parsed = parse_config(&out)
+        .map_err(|e| EdgliError::ValidationError(format!("rendered configuration: {e}")))?;
+    if parsed != *cfg {
+        return Err(EdgliError::ValidationError(
+            "rendered configuration does not round-trip".into(),
+        ));
+    }
+
+    Ok(out)
+}
+
+/// Parses a configuration file's contents, splitting off the `edge` section.
+///
+/// The section is removed before the rest is handed to [`HoprLibConfig`], so hopr-lib never
+/// sees a key it does not know. The settings the edge client defaults differently from
+/// hopr-lib, the mixer delays and the path planner, get the edge defaults. With
+/// [`EdgeSection::custom_protocol`] set, only where the file leaves them out.
+pub fn parse_config(contents: &str) -> Result<NodeConfig> {
+    let mut value: serde_yaml::Value = serde_yaml::from_str(contents)
+        .map_err(|e| EdgliError::SerializationError(e.to_string()))?;
+
+    let edge = match value.as_mapping_mut() {
+        Some(mapping) => mapping.remove(EDGE_SECTION_KEY),
+        None => None,
+    };
+    let edge = match edge {
+        Some(edge) => serde_yaml::from_value(edge).map_err(|e| {
+            EdgliError::SerializationError(format!("'{EDGE_SECTION_KEY}' section: {e}"))
+        })?,
+        None => EdgeSection::default(),
+    };
+    let defaults = if edge.custom_protocol {
+        EdgeProtocolDefaults::missing_from(&value)
+    } else {
+        EdgeProtocolDefaults::ALL
+    };
+    let mut hopr =
+        serde_yaml::from_value(value).map_err(|e| EdgliError::SerializationError(e.to_string()))?;
+    defaults.apply(&mut hopr);
+
+    Ok(NodeConfig { hopr, edge })
+}
+
+/// Which edge protocol defaults are applied over a configuration file.
+struct EdgeProtocolDefaults {
+    min_delay: bool,
+    delay_range: bool,
+    path_planner: bool,
+}
+
+impl EdgeProtocolDefaults {
+    const ALL: Self = Self {
+        min_delay: true,
+        delay_range: true,
+        path_planner: true,
+    };
+
+    /// The defaults `file` leaves out.
+    fn missing_from(file: &serde_yaml::Value) -> Self {
+        let protocol = file.get("protocol");
+        let mixer = protocol.and_then(|p| p.get("mixer"));
+        Self {
+            min_delay: mixer.and_then(|m| m.get("min_delay")).is_none(),
+            delay_range: mixer.and_then(|m| m.get("delay_range")).is_none(),
+            path_planner: protocol.and_then(|p| p.get("path_planner")).is_none(),
+        }
+    }
+
+    fn apply(&self, cfg: &mut HoprLibConfig) {
+        if self.min_delay {
+            cfg.protocol.mixer.min_delay = EDGE_MIXER_MIN_DELAY;
+        }
+        if self.delay_range {
+            cfg.protocol.mixer.delay_range = EDGE_MIXER_DELAY_RANGE;
+        }
+        if self.path_planner {
+            cfg.protocol.path_planner = crate::latency_path_planner_config(DEFAULT_MIN_ACK_RATE);
+        }
+    }
+}
+
+/// Reads and parses the YAML configuration file at `path`.
+pub fn load_config(path: &Path) -> Result<NodeConfig> {
+    if !path.exists() {
+        return Err(EdgliError::ConfigError(format!(
+            "The configuration file '{}' does not exist",
+            path.display()
+        )));
+    }
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| EdgliError::FileError(format!("failed to read '{}': {e}", path.display())))?;
+    parse_config(&contents).map_err(|e| {
+        EdgliError::SerializationError(format!("failed to parse '{}': {e}", path.display()))
+    })
+}
+
+/// Sets `safe_module.safe_address` and `safe_module.module_address` in the configuration
+/// file `contents`, rewriting only those two lines so comments and formatting are kept.
+///
+/// A key the file leaves out is added at the top of its `safe_module` block, and a missing
+/// block at the end of the file. The result is parsed back and must differ from `contents` in
+/// the two addresses only; a `safe_module` this cannot edit line by line, such as one written
+/// as a flow mapping, is refused with [`EdgliError::ConfigError`] instead.
+pub fn with_safe_module(
+    contents: &str,
+    safe_address: Address,
+    module_address: Address,
+) -> Result<String> {
+    let refused = |reason: &str| {
+        EdgliError::ConfigError(format!(
+            "cannot record the Safe in the configuration file: {reason}"
+        ))
+    };
+
+    let blank = serde_yaml::from_str::<serde_yaml::Value>(contents)
+        .map_err(|e| EdgliError::SerializationError(e.to_string()))?
+        .is_null();
+    let mut expected = parse_config(if blank { "{}" } else { contents })?;
+    expected.hopr.safe_module.safe_address = safe_address;
+    expected.hopr.safe_module.module_address = module_address;
+
+    let values = [
+        ("safe_address", yaml_scalar(&safe_address)?),
+        ("module_address", yaml_scalar(&module_address)?),
+    ];
+    let newline = if contents.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<String> = contents.split_inclusive('\n').map(str::to_owned).collect();
+
+    match lines
+        .iter()
+        .position(|line| is_yaml_key(line, 0, "safe_module"))
+    {
+        Some(start) => {
+            if !inline_yaml_value(&lines[start]).is_empty() {
+                return Err(refused("`safe_module` is not a block mapping"));
+            }
+            let end = lines[start + 1..]
+                .iter()
+                .position(|line| is_yaml_entry(line) && yaml_indent(line) == 0)
+                .map_or(lines.len(), |offset| start + 1 + offset);
+            let indent = lines[start + 1..end]
+                .iter()
+                .find(|line| is_yaml_entry(line))
+                .map_or(2, |line| yaml_indent(line));
+            let mut missing = Vec::new();
+            for (key, value) in &values {
+                match (start + 1..end).find(|&i| is_yaml_key(&lines[i], indent, key)) {
+                    Some(i) => lines[i] = with_yaml_value(&lines[i], value),
+                    None => missing.push(format!("{}{key}: {value}{newline}", " ".repeat(indent))),
+                }
+            }
+            let rest = lines.split_off(start + 1);
+            lines.extend(missing);
+            lines.extend(rest);
+        }
+        None => {
+            if let Some(last) = lines.last_mut()
+                && !last.ends_with('\n')
+            {
+                last.push_str(newline);
+            }
+            lines.push(format!("safe_module:{newline}"));
+            for (key, value) in &values {
+                lines.push(format!("  {key}: {value}{newline}"));
+            }
+        }
+    }
+
+    let updated = lines.concat();
+    if parse_config(&updated)? != expected {
+        return Err(refused(
+            "editing `safe_module` line by line would change other settings",
+        ));
+    }
+    Ok(updated)
+}
+
+/// `value` as a YAML scalar on one line.
+fn yaml_scalar(value: &impl Serialize) -> Result<String> {
+    let yaml =
+        serde_yaml::to_string(value).map_err(|e| EdgliError::SerializationError(e.to_string()))?;
+    Ok(yaml.trim_end().to_string())
+}
+
+fn yaml_indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Whether `line` holds content, rather than being blank or a comment.
+fn is_yaml_entry(line: &str) -> bool {
+    let trimmed = line.trim();
+    !trimmed.is_empty() && !trimmed.starts_with('#')
+}
+
+/// Whether `line` is the mapping key `key` at `indent`.
+fn is_yaml_key(line: &str, indent: usize, key: &str) -> bool {
+    yaml_indent(line) == indent
+        && line[indent..]
+            .strip_prefix(key)
+            .is_some_and(|rest| rest.starts_with(':'))
+}
+
+/// The value following the key on `line`, without a trailing comment.
+fn inline_yaml_value(line: &str) -> &str {
+    let value = line.split_once(':').map_or("", |(_, rest)| rest).trim();
+    if value.starts_with('#') { "" } else { value }
+}
+
+/// `line`, a `key: value` line, with its value replaced by `value`; the indentation, a
+/// trailing comment and the line ending are kept.
+fn with_yaml_value(line: &str, value: &str) -> String {
+    let (key, rest) = line.split_once(':').unwrap_or((line, ""));
+    let body = rest.trim_end_matches(['\r', '\n']);
+    let ending = &rest[body.len()..];
+    let comment = body.find(" #").map_or("", |i| &body[i..]);
+    format!("{key}: {value}{comment}{ending}")
+}
+
+/// Copies the configuration file at `path` to `<path>.bak`, replacing an earlier backup, and
+/// returns the backup's path.
+pub fn backup_config(path: &Path) -> Result<std::path::PathBuf> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    let backup = std::path::PathBuf::from(backup);
+    std::fs::copy(path, &backup).map_err(|e| {
+        EdgliError::FileError(format!(
+            "failed to back up '{}' to '{}': {e}",
+            path.display(),
+            backup.display()
+        ))
+    })?;
+    Ok(backup)
+}
+
+/// Writes `contents` to `path`, refusing to replace an existing file unless `overwrite`.
+///
+/// The contents are written to `<path>.tmp` first and renamed over `path`, so a failed write
+/// leaves the previous file intact. A replaced file's permissions are kept.
+pub fn write_config(path: &Path, contents: &str, overwrite: bool) -> Result<()> {
+    use std::io::Write;
+
+    if path.exists() && !overwrite {
+        return Err(EdgliError::FileError(format!(
+            "the configuration file '{}' already exists",
+            path.display()
+        )));
+    }
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    let temporary = std::path::PathBuf::from(temporary);
+    let failed = |e: std::io::Error| {
+        let _ = std::fs::remove_file(&temporary);
+        EdgliError::FileError(format!("failed to write '{}': {e}", path.display()))
+    };
+
+    let mut file = std::fs::File::create(&temporary).map_err(failed)?;
+    file.write_all(contents.as_bytes()).map_err(failed)?;
+    if let Ok(metadata) = std::fs::metadata(path) {
+        file.set_permissions(metadata.permissions())
+            .map_err(failed)?;
+    }
+    file.sync_all().map_err(failed)?;
+    drop(file);
+    std::fs::rename(&temporary, path).map_err(failed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn edge_config_applies_edge_protocol_defaults() {
+        let cfg = edge_config(HostConfig::default()).hopr;
+        assert_eq!(cfg.protocol.mixer.min_delay, EDGE_MIXER_MIN_DELAY);
+        assert_eq!(cfg.protocol.mixer.delay_range, EDGE_MIXER_DELAY_RANGE);
+        assert_eq!(
+            cfg.protocol.path_planner,
+            crate::latency_path_planner_config(DEFAULT_MIN_ACK_RATE)
+        );
+    }
+
+    #[test]
+    fn edge_config_leaves_placeholder_safe_module() {
+        let cfg = edge_config(HostConfig::default());
+        assert!(has_placeholder_safe_module(&cfg.hopr));
+    }
+
+    #[test]
+    fn prefilled_safe_module_is_not_a_placeholder() {
+        let mut cfg = edge_config(HostConfig::default()).hopr;
+        cfg.safe_module.safe_address = [0x11u8; 20].into();
+        cfg.safe_module.module_address = [0x22u8; 20].into();
+        assert!(!has_placeholder_safe_module(&cfg));
+    }
+
+    #[test]
+    fn rendered_config_round_trips() {
+        let mut cfg = edge_config(HostConfig::default());
+        cfg.hopr.safe_module.safe_address = [0x11u8; 20].into();
+        cfg.hopr.safe_module.module_address = [0x22u8; 20].into();
+
+        let rendered = render_config(&cfg).unwrap();
+        assert_eq!(parse_config(&rendered).unwrap(), cfg);
+    }
+
+    #[test]
+    fn with_safe_module_rewrites_only_the_address_lines() {
+        let mut cfg = edge_config(HostConfig::default());
+        cfg.edge.log_filter = Some("debug".into());
+        cfg.edge.custom_protocol = true;
+        cfg.hopr.protocol.mixer.min_delay = Duration::from_millis(7);
+        let rendered = render_config(&cfg).unwrap();
+
+        let safe_address: Address = [0x11u8; 20].into();
+        let module_address: Address = [0x22u8; 20].into();
+        let updated = with_safe_module(&rendered, safe_address, module_address).unwrap();
+
+        // Comments included, every line but the two addresses is kept as written.
+        assert_eq!(updated.lines().count(), rendered.lines().count());
+        let changed: Vec<_> = rendered
+            .lines()
+            .zip(updated.lines())
+            .filter(|(before, after)| before != after)
+            .map(|(_, after)| after.trim_start())
+            .collect();
+        assert_eq!(changed.len(), 2, "{changed:?}");
+        assert!(changed[0].starts_with("safe_address: "));
+        assert!(changed[1].starts_with("module_address: "));
+
+        cfg.hopr.safe_module.safe_address = safe_address;
+        cfg.hopr.safe_module.module_address = module_address;
+        assert_eq!(parse_config(&updated).unwrap(), cfg);
+    }
+
+    #[test]
+    fn with_safe_module_keeps_a_hand_written_layout() {
+        let contents = "\
+# hand-written
+safe_module:
+    # deployed by hand
+    module_address: '0x0000000000000000000000000000000000000000'
+    safe_address: '0x0000000000000000000000000000000000000000' # the Safe
+host:
+  port: 9091
+";
+        let safe_address: Address = [0x11u8; 20].into();
+        let module_address: Address = [0x22u8; 20].into();
+        let updated = with_safe_module(contents, safe_address, module_address).unwrap();
+
+        assert!(updated.starts_with("# hand-written\nsafe_module:\n    module_address: "));
+        assert!(updated.contains("    # deployed by hand\n"));
+        assert!(updated.contains(" # the Safe\nhost:\n  port: 9091\n"));
+        let parsed = parse_config(&updated).unwrap();
+        assert_eq!(parsed.hopr.safe_module.safe_address, safe_address);
+        assert_eq!(parsed.hopr.safe_module.module_address, module_address);
+        assert_eq!(parsed.hopr.host.port, 9091);
+    }
+
+    #[test]
+    fn with_safe_module_refuses_a_flow_mapping() {
+        let err = with_safe_module(
+            "safe_module: {}\n",
+            [0x11u8; 20].into(),
+            [0x22u8; 20].into(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, EdgliError::ConfigError(_)), "got {err}");
+    }
+
+    #[test]
+    fn with_safe_module_fills_an_empty_file() {
+        let safe_address: Address = [0x11u8; 20].into();
+        let updated =
+            parse_config(&with_safe_module("", safe_address, safe_address).unwrap()).unwrap();
+        assert_eq!(updated.hopr.safe_module.safe_address, safe_address);
+        assert_eq!(updated.hopr.safe_module.module_address, safe_address);
+    }
+
+    #[test]
+    fn rendered_config_comments_the_edge_sections() {
+        let rendered = render_config(&edge_config(HostConfig::default())).unwrap();
+        assert!(rendered.starts_with("# edgli node configuration"));
+        assert!(rendered.contains("# Packet mixer delays"));
+        assert!(rendered.contains("  # Latency-optimised path planner"));
+        assert!(rendered.contains("# Address and port this node listens on"));
+        // An empty edge section is advertised as a commented-out example.
+        assert!(rendered.contains("# edge:"));
+    }
+
+    #[test]
+    fn rendered_config_round_trips_the_edge_section() {
+        let mut cfg = edge_config(HostConfig::default());
+        cfg.edge = EdgeSection {
+            log_filter: Some("info,hopr_lib=debug".into()),
+            strategy: Some(StrategySection {
+                target_open_channels: Some(3),
+                channel_allowlist: Some(vec![[0x33u8; 20].into()]),
+                channel_capacity: Some(ByteSize::mib(64)),
+                sizing_mode: Some(SizingModeSection::Deterministic),
+                ..Default::default()
+            }),
+            custom_protocol: true,
+        };
+
+        let rendered = render_config(&cfg).unwrap();
+        assert!(rendered.contains("\nedge:\n"));
+        assert_eq!(parse_config(&rendered).unwrap(), cfg);
+    }
+
+    #[test]
+    fn parse_config_without_edge_section_defaults_it() {
+        let cfg = parse_config("{}").unwrap();
+        assert_eq!(cfg.hopr, edge_config(HostConfig::default()).hopr);
+        assert_eq!(cfg.edge, EdgeSection::default());
+    }
+
+    #[test]
+    fn parse_config_applies_the_edge_protocol_defaults_over_the_file() {
+        let mut written = edge_config(HostConfig::default()).hopr;
+        written.protocol.mixer.min_delay = Duration::from_millis(7);
+        written.protocol.path_planner = crate::latency_path_planner_config(0.5);
+
+        let parsed = parse_config(&serde_yaml::to_string(&written).unwrap()).unwrap();
+        assert_eq!(parsed.hopr, edge_config(HostConfig::default()).hopr);
+    }
+
+    #[test]
+    fn parse_config_keeps_the_protocol_settings_the_file_sets_when_asked_to() {
+        let mut written = edge_config(HostConfig::default());
+        written.edge.custom_protocol = true;
+        written.hopr.protocol.mixer.min_delay = Duration::from_millis(7);
+        written.hopr.protocol.path_planner = crate::latency_path_planner_config(0.5);
+
+        let parsed = parse_config(&render_config(&written).unwrap()).unwrap();
+        assert_eq!(
+            parsed.hopr.protocol.mixer.min_delay,
+            Duration::from_millis(7)
+        );
+        assert_eq!(
+            parsed.hopr.protocol.mixer.delay_range,
+            EDGE_MIXER_DELAY_RANGE
+        );
+        assert_eq!(
+            parsed.hopr.protocol.path_planner,
+            crate::latency_path_planner_config(0.5)
+        );
+    }
+
+    #[test]
+    fn parse_config_rejects_unknown_edge_keys() {
+        let err = parse_config("edge:\n  log_filtre: debug\n").unwrap_err();
+        assert!(
+            matches!(err, EdgliError::SerializationError(_)),
+            "got {err}"
+        );
+    }
+
+    #[test]
+    fn strategy_section_maps_onto_incentive_configuration() {
+        let allowlisted: Address = [0x33u8; 20].into();
+        let cfg = StrategySection {
+            min_open_channels: Some(2),
+            target_open_channels: Some(4),
+            channel_allowlist: Some(vec![allowlisted]),
+            channel_capacity: Some(ByteSize::mib(64)),
+            sizing_mode: Some(SizingModeSection::Probabilistic {
+                success_probability: 0.9,
+            }),
+            ..Default::default()
+        }
+        .to_incentive_configuration()
+        .unwrap();
+
+        assert_eq!(cfg.min_open_channels, 2);
+        assert_eq!(cfg.target_open_channels, 4);
+        assert_eq!(cfg.channel_allowlist, Some([allowlisted].into()));
+        assert_eq!(cfg.channel_capacity, Some(ByteSize::mib(64)));
+        assert_eq!(cfg.topup_capacity, None);
+        assert_eq!(
+            cfg.sizing_mode,
+            Some(CapacitySizingMode::Probabilistic {
+                success_probability: 0.9
+            })
+        );
+    }
+
+    #[test]
+    fn empty_strategy_section_is_the_default_configuration() {
+        let cfg = StrategySection::default()
+            .to_incentive_configuration()
+            .unwrap();
+        let defaults = IncentiveConfiguration::default();
+        assert_eq!(cfg.min_open_channels, defaults.min_open_channels);
+        assert_eq!(cfg.target_open_channels, defaults.target_open_channels);
+        assert_eq!(cfg.channel_allowlist, None);
+    }
+
+    #[test]
+    fn strategy_section_is_validated() {
+        let section = StrategySection {
+            min_open_channels: Some(9),
+            target_open_channels: Some(3),
+            ..Default::default()
+        };
+        assert!(section.to_incentive_configuration().is_err());
+    }
+
+    #[test]
+    fn sweep_section_maps_onto_sweep_config() {
+        let edge: EdgeSection = serde_yaml::from_str(
+            "strategy:\n  sweep:\n    reserve: 5 wxHOPR\n    max_fee_per_gas: 2000000000\n",
+        )
+        .unwrap();
+        let sweep = edge.incentive_configuration().unwrap().sweep.unwrap();
+        assert_eq!(sweep.reserve, "5 wxHOPR".parse::<HoprBalance>().unwrap());
+        assert_eq!(sweep.min_amount, SweepConfig::default().min_amount);
+        assert_eq!(sweep.max_fee_per_gas, Some(2_000_000_000));
+
+        let invalid = SweepSection {
+            reserve: Some("five".into()),
+            ..Default::default()
+        };
+        assert!(invalid.to_sweep_config().is_err());
+    }
+
+    #[test]
+    fn comment_lines_wrap_long_text() {
+        let mut out = String::new();
+        comment_lines(&mut out, &"word ".repeat(40), 2);
+        for line in out.lines() {
+            assert!(line.starts_with("  # "), "{line:?}");
+            assert!(line.len() <= 88, "{line:?} is {} columns", line.len());
+        }
+    }
+
+    #[test]
+    fn load_config_rejects_missing_file() {
+        let path =
+            std::env::temp_dir().join(format!("edgli-config-{}-missing.yaml", std::process::id()));
+        let err = load_config(&path).unwrap_err();
+        assert!(matches!(err, EdgliError::ConfigError(_)), "got {err}");
+    }
+
+    #[test]
+    fn load_config_reads_a_rendered_config() {
+        let path =
+            std::env::temp_dir().join(format!("edgli-config-{}-rendered.yaml", std::process::id()));
+        let cfg = edge_config(HostConfig::default());
+        write_config(&path, &render_config(&cfg).unwrap(), true).unwrap();
+        assert_eq!(load_config(&path).unwrap(), cfg);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn write_config_refuses_to_overwrite() {
+        let path =
+            std::env::temp_dir().join(format!("edgli-config-{}-existing.yaml", std::process::id()));
+        std::fs::write(&path, "keep me").unwrap();
+
+        let err = write_config(&path, "replaced", false).unwrap_err();
+        assert!(matches!(err, EdgliError::FileError(_)), "got {err}");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep me");
+
+        write_config(&path, "replaced", true).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "replaced");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -4,54 +4,59 @@
 //! [`HoprBlokliClientConfig`] or calls [`create_blokli_client`]. Every path that needs a
 //! Blokli client resolves through [`BlokliEndpoint::build_client`], so a caller-supplied
 //! DNS override cannot be dropped on the way to the client.
+//!
+//! A [`BlokliEndpointSet`] lists several endpoints in order of preference. Its
+//! [`BlokliFailover`] tracks which of them recently failed, so callers try the preferred
+//! healthy endpoint first and return to a failed one once its cooldown expires. The
+//! on-boarding handle from [`crate::make_incentive_operations`] fails over per request; a
+//! node only picks its endpoint once, when it starts.
+//!
+//! An endpoint with a [`BlokliProxy`] reaches Blokli through a SOCKS5 or HTTP proxy, and one
+//! with [`SpkiPin`]s or a [`CaBundle`] only trusts matching certificates; see
+//! [`crate::proxy`] and [`crate::tls`] for how.
 
 use std::fmt;
 use std::net::{IpAddr, SocketAddr};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use hopr_chain_connector::{
     DEFAULT_REQUEST_TIMEOUT, HoprBlokliClientConfig, blokli_client::BlokliClient,
     create_blokli_client,
 };
+use tokio_rustls::rustls::pki_types::ServerName;
 use url::Url;
 
 use crate::blokli::DEFAULT_BLOKLI_URL;
 use crate::errors::EdgliError;
+use crate::proxy::{BlokliProxy, Forwarder, TunnelHost, Upstream, spawn_forwarder};
+use crate::retry::RetryPolicy;
+use crate::tls::{CaBundle, SpkiPin};
 
 /// Error returned when a [`BlokliDnsOverride`] cannot be parsed.
 #[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
 #[non_exhaustive]
 pub enum ParseBlokliDnsOverrideError {
-    /// The input is neither an IP address nor an IP address with a port.
-    #[error("invalid DNS override '{input}', expected <IP_ADDRESS> or <IP_ADDRESS>:<PORT>")]
+    /// An entry is neither an IP address nor an IP address with a port.
+    #[error(
+        "invalid DNS override '{input}', expected a comma-separated list of <IP_ADDRESS> or <IP_ADDRESS>:<PORT>"
+    )]
     InvalidFormat { input: String },
 }
 
-/// DNS resolution override for the Blokli endpoint host.
-///
-/// Pins the endpoint hostname to a fixed address so no system DNS lookup is needed.
-/// The request URL is not rewritten: the HTTP `Host` header, TLS SNI and certificate
-/// validation still use the original hostname.
+/// One address a [`BlokliDnsOverride`] can pin the endpoint host to.
 ///
 /// When [`Self::port`] is `None`, the endpoint URL's port (or its scheme default) is used.
-/// IPv6 addresses with a port must use brackets, for example `[::1]:3002`;
-/// an unbracketed value such as `::1:3002` is parsed as an address without a port.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub struct BlokliDnsOverride {
+pub struct DnsOverrideAddr {
     /// IP address to connect to instead of resolving the endpoint host.
     pub ip: IpAddr,
     /// Optional port override.
     pub port: Option<u16>,
 }
 
-impl BlokliDnsOverride {
-    /// Creates an override for `ip`, optionally pinning the port as well.
-    pub const fn new(ip: IpAddr, port: Option<u16>) -> Self {
-        Self { ip, port }
-    }
-}
-
-impl FromStr for BlokliDnsOverride {
+impl FromStr for DnsOverrideAddr {
     type Err = ParseBlokliDnsOverrideError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
@@ -72,7 +77,7 @@ impl FromStr for BlokliDnsOverride {
     }
 }
 
-impl fmt::Display for BlokliDnsOverride {
+impl fmt::Display for DnsOverrideAddr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self.port {
             Some(port) => write!(f, "{}", SocketAddr::new(self.ip, port)),
@@ -81,8 +86,131 @@ impl fmt::Display for BlokliDnsOverride {
     }
 }
 
-/// The Blokli service endpoint: a URL, an optional DNS-resolution override, and how long a
-/// single request to it may take.
+/// DNS resolution override for the Blokli endpoint host.
+///
+/// Pins the endpoint hostname to fixed addresses so no system DNS lookup is needed.
+/// The request URL is not rewritten: the HTTP `Host` header, TLS SNI and certificate
+/// validation still use the original hostname.
+///
+/// With several addresses, a client races connections to them in the given order, each
+/// attempt starting [`HAPPY_EYEBALLS_DELAY`] after the previous one, and keeps the first
+/// that connects. Mixing IPv4 and IPv6 addresses lets a client on a broken network family
+/// fall through to the other quickly. The race runs once, when the client is built: if the
+/// winning address goes down later, the client keeps using it and its requests fail until
+/// the client is rebuilt. The other addresses are not raced again.
+///
+/// Parsed from and displayed as a comma-separated list. IPv6 addresses with a port must
+/// use brackets, for example `[::1]:3002`; an unbracketed value such as `::1:3002` is parsed
+/// as an address without a port.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BlokliDnsOverride {
+    first: DnsOverrideAddr,
+    rest: Vec<DnsOverrideAddr>,
+}
+
+impl BlokliDnsOverride {
+    /// Creates an override for `ip`, optionally pinning the port as well.
+    pub const fn new(ip: IpAddr, port: Option<u16>) -> Self {
+        Self {
+            first: DnsOverrideAddr { ip, port },
+            rest: Vec::new(),
+        }
+    }
+
+    /// Creates an override racing `addresses`; `None` when there are none.
+    pub fn from_addresses(addresses: Vec<DnsOverrideAddr>) -> Option<Self> {
+        let mut addresses = addresses.into_iter();
+        Some(Self {
+            first: addresses.next()?,
+            rest: addresses.collect(),
+        })
+    }
+
+    /// The candidate addresses, in the order they are tried.
+    pub fn addresses(&self) -> impl Iterator<Item = DnsOverrideAddr> + '_ {
+        std::iter::once(self.first).chain(self.rest.iter().copied())
+    }
+
+    /// The first address, which is used without racing when it is the only one.
+    pub fn first(&self) -> DnsOverrideAddr {
+        self.first
+    }
+
+    /// IP address of the first candidate.
+    pub fn ip(&self) -> IpAddr {
+        self.first.ip
+    }
+
+    /// Port override of the first candidate.
+    pub fn port(&self) -> Option<u16> {
+        self.first.port
+    }
+}
+
+impl FromStr for BlokliDnsOverride {
+    type Err = ParseBlokliDnsOverrideError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let addresses = value
+            .split(',')
+            .map(|address| address.trim().parse())
+            .collect::<Result<_, _>>()?;
+        Ok(Self::from_addresses(addresses).expect("split yields at least one entry"))
+    }
+}
+
+impl fmt::Display for BlokliDnsOverride {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, address) in self.addresses().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{address}")?;
+        }
+        Ok(())
+    }
+}
+
+/// How long a happy-eyeballs race waits before starting the next connection attempt, as
+/// recommended by RFC 8305.
+pub const HAPPY_EYEBALLS_DELAY: Duration = Duration::from_millis(250);
+
+/// Races connections to `targets`, through their proxy when they have one, starting them
+/// `delay` apart in order, and returns the index of the first that connects within
+/// `timeout`.
+async fn race_connections(
+    targets: &[Upstream],
+    delay: Duration,
+    timeout: Duration,
+) -> Option<usize> {
+    use futures::StreamExt;
+
+    let mut attempts: futures::stream::FuturesUnordered<_> = targets
+        .iter()
+        .enumerate()
+        .map(|(i, target)| async move {
+            tokio::time::sleep(delay * i as u32).await;
+            target.connect().await.map(|_| i).map_err(|error| {
+                tracing::debug!(host = %target.host, port = target.port, %error, "DNS override candidate failed")
+            })
+        })
+        .collect();
+
+    tokio::time::timeout(timeout, async {
+        while let Some(attempt) = attempts.next().await {
+            if let Ok(i) = attempt {
+                return Some(i);
+            }
+        }
+        None
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// The Blokli service endpoint: a URL, an optional DNS-resolution override, an optional
+/// proxy, optional certificate pinning, and how long a single request to it may take.
 ///
 /// [`Default`] yields [`DEFAULT_BLOKLI_URL`] resolved through system DNS, with the connector's
 /// [`DEFAULT_REQUEST_TIMEOUT`].
@@ -95,6 +223,14 @@ pub struct BlokliEndpoint {
     /// Timeout for a single request to this endpoint, covering DNS, TCP, TLS, request and
     /// response. Raise it for endpoints reached over a high-latency link.
     pub request_timeout: std::time::Duration,
+    /// When set, all traffic to this endpoint is tunneled through the proxy.
+    pub proxy: Option<BlokliProxy>,
+    /// When non-empty, every connection must present a server certificate with one of these
+    /// keys.
+    pub spki_pins: Vec<SpkiPin>,
+    /// When set, the certificate chain is validated against these roots instead of the
+    /// system ones.
+    pub ca_bundle: Option<CaBundle>,
 }
 
 impl Default for BlokliEndpoint {
@@ -103,6 +239,9 @@ impl Default for BlokliEndpoint {
             url: DEFAULT_BLOKLI_URL.clone(),
             dns_override: None,
             request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            proxy: None,
+            spki_pins: Vec::new(),
+            ca_bundle: None,
         }
     }
 }
@@ -114,9 +253,18 @@ impl BlokliEndpoint {
             url,
             dns_override: None,
             request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            proxy: None,
+            spki_pins: Vec::new(),
+            ca_bundle: None,
         }
     }
 
+    /// The endpoint as the `endpoint` label of the Blokli metrics: the URL's origin, so no
+    /// path or credentials end up in a metric.
+    pub(crate) fn metrics_label(&self) -> String {
+        self.url.origin().ascii_serialization()
+    }
+
     /// Sets the DNS override, replacing any previously configured one.
     pub fn with_dns_override(mut self, dns_override: BlokliDnsOverride) -> Self {
         self.dns_override = Some(dns_override);
@@ -129,6 +277,24 @@ impl BlokliEndpoint {
         self
     }
 
+    /// Sets the proxy, replacing any previously configured one.
+    pub fn with_proxy(mut self, proxy: BlokliProxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets the SPKI pins, replacing any previously configured ones. Requires an `https` URL.
+    pub fn with_spki_pins(mut self, pins: impl IntoIterator<Item = SpkiPin>) -> Self {
+        self.spki_pins = pins.into_iter().collect();
+        self
+    }
+
+    /// Sets the root certificates, replacing the system roots. Requires an `https` URL.
+    pub fn with_ca_bundle(mut self, ca_bundle: CaBundle) -> Self {
+        self.ca_bundle = Some(ca_bundle);
+        self
+    }
+
     /// Creates an endpoint from an optional URL string.
     ///
     /// Uses [`DEFAULT_BLOKLI_URL`] when `url` is `None`.
@@ -144,24 +310,357 @@ impl BlokliEndpoint {
             url,
             dns_override: None,
             request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            proxy: None,
+            spki_pins: Vec::new(),
+            ca_bundle: None,
         })
     }
 
     /// Converts the endpoint into the chain connector's client configuration.
     ///
-    /// The connector represents the override as a tuple, so this is where
-    /// [`BlokliDnsOverride`] is destructured.
+    /// The connector represents the override as a single tuple, so this is where
+    /// [`BlokliDnsOverride`] is destructured, using its first address; call
+    /// [`Self::race_dns_override`] first to put the fastest address there.
     pub(crate) fn to_client_config(&self) -> HoprBlokliClientConfig {
         HoprBlokliClientConfig {
             url: self.url.clone(),
-            dns_override: self.dns_override.map(|o| (o.ip, o.port)),
+            dns_override: self.dns_override.as_ref().map(|o| (o.ip(), o.port())),
             request_timeout: self.request_timeout,
         }
     }
 
-    /// Builds a Blokli client for this endpoint.
-    pub(crate) fn build_client(&self) -> BlokliClient {
-        create_blokli_client(self.to_client_config())
+    /// Narrows a DNS override with several addresses down to the first one that accepts a
+    /// connection, racing them happy-eyeballs style, through [`Self::proxy`] when set. Keeps
+    /// the override unchanged when none connects, so the client reports the connection error
+    /// itself.
+    ///
+    /// The winner is final for the client built from the result; nothing re-races the
+    /// candidates when it later becomes unreachable.
+    pub(crate) async fn race_dns_override(mut self) -> Self {
+        let Some(dns_override) = &self.dns_override else {
+            return self;
+        };
+        if dns_override.rest.is_empty() {
+            return self;
+        }
+
+        let default_port = self.url.port_or_known_default().unwrap_or(443);
+        let targets: Vec<Upstream> = dns_override
+            .addresses()
+            .map(|a| Upstream {
+                proxy: self.proxy.clone(),
+                host: TunnelHost::Ip(a.ip),
+                port: a.port.unwrap_or(default_port),
+            })
+            .collect();
+        match race_connections(&targets, HAPPY_EYEBALLS_DELAY, self.request_timeout).await {
+            Some(winner) => {
+                let address = dns_override
+                    .addresses()
+                    .nth(winner)
+                    .expect("the winner is one of the targets");
+                tracing::info!(url = %self.url, %address, "Pinned Blokli host to the fastest DNS override");
+                self.dns_override = Some(BlokliDnsOverride::new(address.ip, address.port));
+            }
+            None => {
+                tracing::warn!(url = %self.url, "No DNS override candidate accepted a connection")
+            }
+        }
+        self
+    }
+
+    /// Whether edgli terminates TLS for this endpoint, to enforce [`Self::spki_pins`] and
+    /// [`Self::ca_bundle`].
+    pub(crate) fn terminates_tls(&self) -> bool {
+        !self.spki_pins.is_empty() || self.ca_bundle.is_some()
+    }
+
+    /// Points the DNS override at a loopback forwarder reaching the real host: the first
+    /// DNS override address when set, otherwise the URL's host, which a `socks5h` proxy
+    /// resolves itself. The forwarder goes through [`Self::proxy`] when set.
+    ///
+    /// When pinning, the forwarder terminates TLS and the client is switched to plain HTTP.
+    /// One handshake is made up front so that a pin mismatch fails here, with a clear error,
+    /// rather than as a connection reset on every request.
+    ///
+    /// The forwarder stops when the returned [`Forwarder`] is dropped.
+    async fn route_through_forwarder(mut self) -> Result<(Self, Forwarder), EdgliError> {
+        let default_port = self.url.port_or_known_default().unwrap_or(443);
+        let (host, port) = match self.dns_override.as_ref().map(BlokliDnsOverride::first) {
+            Some(address) => (
+                TunnelHost::Ip(address.ip),
+                address.port.unwrap_or(default_port),
+            ),
+            None => match self.url.host() {
+                Some(url::Host::Domain(name)) => (TunnelHost::Domain(name.into()), default_port),
+                Some(url::Host::Ipv4(ip)) => (TunnelHost::Ip(ip.into()), default_port),
+                Some(url::Host::Ipv6(ip)) => (TunnelHost::Ip(ip.into()), default_port),
+                None => {
+                    return Err(EdgliError::ConfigError(format!(
+                        "Blokli URL '{}' has no host",
+                        self.url
+                    )));
+                }
+            },
+        };
+        let upstream = Upstream {
+            proxy: self.proxy.clone(),
+            host,
+            port,
+        };
+        tracing::info!(url = %self.url, proxy = ?self.proxy, pinned = self.terminates_tls(), "Relaying Blokli traffic through a local forwarder");
+
+        let forwarder = if self.terminates_tls() {
+            if self.url.scheme() != "https" {
+                return Err(EdgliError::ConfigError(format!(
+                    "certificate pinning requires an https Blokli URL, got '{}'",
+                    self.url
+                )));
+            }
+            let host_name = self.url.host_str().unwrap_or_default().to_string();
+            let server_name = ServerName::try_from(host_name.clone())
+                .map_err(|e| EdgliError::TlsError(format!("{host_name}: {e}")))?;
+            let connector = crate::tls::connector(&self.spki_pins, self.ca_bundle.as_ref())?;
+            let handshake = move || {
+                let (upstream, connector, server_name) =
+                    (upstream.clone(), connector.clone(), server_name.clone());
+                async move {
+                    connector
+                        .connect(server_name, upstream.connect().await?)
+                        .await
+                }
+            };
+
+            // Failures below TLS are left for the client to report, as without pinning.
+            if let Ok(Err(error)) = tokio::time::timeout(self.request_timeout, handshake()).await
+                && let Some(error) = crate::tls::handshake_error(&host_name, &error)
+            {
+                return Err(error);
+            }
+            self.url
+                .set_scheme("http")
+                .expect("https and http are both special schemes");
+            spawn_forwarder(handshake).await
+        } else {
+            spawn_forwarder(move || {
+                let upstream = upstream.clone();
+                async move { upstream.connect().await }
+            })
+            .await
+        }
+        .map_err(|e| EdgliError::ProxyError(format!("cannot start the local forwarder: {e}")))?;
+
+        let local = forwarder.local_addr();
+        self.dns_override = Some(BlokliDnsOverride::new(local.ip(), Some(local.port())));
+        Ok((self, forwarder))
+    }
+
+    /// Builds a Blokli client for this endpoint. The DNS override candidates are raced
+    /// first, through the proxy when one is set; with a proxy or pinning, traffic then goes
+    /// through a forwarder to the winner.
+    pub(crate) async fn build_client(&self) -> Result<EndpointClient, EdgliError> {
+        let endpoint = self.clone().race_dns_override().await;
+        if endpoint.proxy.is_some() || endpoint.terminates_tls() {
+            let (endpoint, forwarder) = endpoint.route_through_forwarder().await?;
+            Ok(EndpointClient {
+                client: create_blokli_client(endpoint.to_client_config()),
+                forwarder: Some(forwarder),
+            })
+        } else {
+            Ok(EndpointClient {
+                client: create_blokli_client(endpoint.to_client_config()),
+                forwarder: None,
+            })
+        }
+    }
+}
+
+/// A Blokli client and the local forwarder it connects through, if any.
+///
+/// The forwarder stops when this is dropped, so whoever keeps the client must keep the
+/// forwarder for as long.
+pub(crate) struct EndpointClient {
+    pub(crate) client: BlokliClient,
+    pub(crate) forwarder: Option<Forwarder>,
+}
+
+/// How long a [`BlokliEndpointSet`] passes over an endpoint after it failed, by default.
+pub const DEFAULT_ENDPOINT_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Blokli endpoints in order of preference, for failover, and how requests to them are
+/// retried.
+///
+/// [`Default`] holds the single default [`BlokliEndpoint`] and the default [`RetryPolicy`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlokliEndpointSet {
+    endpoints: Vec<BlokliEndpoint>,
+    /// How long an endpoint that failed is passed over before it is preferred again.
+    pub cooldown: Duration,
+    /// How reads that failed transiently are retried on the same endpoint before failing
+    /// over.
+    pub retry: RetryPolicy,
+}
+
+impl Default for BlokliEndpointSet {
+    fn default() -> Self {
+        BlokliEndpoint::default().into()
+    }
+}
+
+impl From<BlokliEndpoint> for BlokliEndpointSet {
+    fn from(endpoint: BlokliEndpoint) -> Self {
+        Self {
+            endpoints: vec![endpoint],
+            cooldown: DEFAULT_ENDPOINT_COOLDOWN,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+impl BlokliEndpointSet {
+    /// Creates a set from `endpoints`, the most preferred first.
+    pub fn new(endpoints: Vec<BlokliEndpoint>) -> Result<Self, EdgliError> {
+        if endpoints.is_empty() {
+            return Err(EdgliError::ConfigError(
+                "at least one Blokli endpoint is required".into(),
+            ));
+        }
+        Ok(Self {
+            endpoints,
+            cooldown: DEFAULT_ENDPOINT_COOLDOWN,
+            retry: RetryPolicy::default(),
+        })
+    }
+
+    /// Creates a set from URL strings, the most preferred first.
+    ///
+    /// Falls back to [`DEFAULT_BLOKLI_URL`] when `urls` is empty.
+    pub fn from_urls<S: AsRef<str>>(urls: &[S]) -> Result<Self, EdgliError> {
+        if urls.is_empty() {
+            return Ok(Self::default());
+        }
+        urls.iter()
+            .map(|url| BlokliEndpoint::from_optional_url(Some(url.as_ref())))
+            .collect::<Result<Vec<_>, _>>()
+            .and_then(Self::new)
+    }
+
+    /// Sets the cooldown, replacing any previously configured one.
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Sets the retry policy, replacing any previously configured one.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// The endpoints, the most preferred first.
+    pub fn endpoints(&self) -> &[BlokliEndpoint] {
+        &self.endpoints
+    }
+
+    /// The most preferred endpoint.
+    pub fn primary(&self) -> &BlokliEndpoint {
+        &self.endpoints[0]
+    }
+
+    /// Starts tracking the health of this set's endpoints, all initially healthy.
+    pub fn failover(&self) -> BlokliFailover {
+        BlokliFailover {
+            set: self.clone(),
+            state: Arc::new(Mutex::new(FailoverState {
+                down_until: vec![None; self.endpoints.len()],
+                active: None,
+            })),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct FailoverState {
+    /// Per endpoint, when its cooldown after the last failure ends.
+    down_until: Vec<Option<Instant>>,
+    /// The endpoint that answered last.
+    active: Option<usize>,
+}
+
+/// Health of the endpoints of a [`BlokliEndpointSet`], shared between clones.
+///
+/// Report every outcome with [`Self::record_success`] or [`Self::record_failure`]; a failed
+/// endpoint drops to the back of [`Self::candidates`] for the set's cooldown and returns to
+/// its place, failing back, once the cooldown expired.
+#[derive(Clone, Debug)]
+pub struct BlokliFailover {
+    set: BlokliEndpointSet,
+    state: Arc<Mutex<FailoverState>>,
+}
+
+impl BlokliFailover {
+    fn state(&self) -> std::sync::MutexGuard<'_, FailoverState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The endpoint at `index` of the set.
+    pub fn endpoint(&self, index: usize) -> &BlokliEndpoint {
+        &self.set.endpoints[index]
+    }
+
+    /// How reads against the set's endpoints are retried.
+    pub fn retry(&self) -> &RetryPolicy {
+        &self.set.retry
+    }
+
+    /// Endpoint indices in the order to try them: healthy endpoints by preference, then
+    /// those cooling down, the one recovering soonest first.
+    pub fn candidates(&self) -> Vec<usize> {
+        self.candidates_at(Instant::now())
+    }
+
+    fn candidates_at(&self, now: Instant) -> Vec<usize> {
+        let state = self.state();
+        let mut candidates: Vec<usize> = (0..self.set.endpoints.len()).collect();
+        candidates.sort_by_key(|&i| match state.down_until[i] {
+            Some(until) if until > now => (true, Some(until)),
+            _ => (false, None),
+        });
+        candidates
+    }
+
+    /// The endpoint that answered last, if any did.
+    pub fn active(&self) -> Option<&BlokliEndpoint> {
+        self.state().active.map(|i| self.endpoint(i))
+    }
+
+    /// Records that the endpoint at `index` answered, making it the active one.
+    pub fn record_success(&self, index: usize) {
+        let mut state = self.state();
+        state.down_until[index] = None;
+        if state.active.replace(index) != Some(index) {
+            tracing::info!(
+                url = %self.endpoint(index).url,
+                preference = index,
+                "Using Blokli endpoint"
+            );
+        }
+    }
+
+    /// Records that the endpoint at `index` failed, passing it over for the cooldown.
+    pub fn record_failure(&self, index: usize) {
+        self.record_failure_at(index, Instant::now());
+    }
+
+    fn record_failure_at(&self, index: usize, now: Instant) {
+        self.state().down_until[index] = Some(now + self.set.cooldown);
+        if self.set.endpoints.len() > 1 {
+            tracing::warn!(
+                url = %self.endpoint(index).url,
+                cooldown = ?self.set.cooldown,
+                "Blokli endpoint failed, failing over"
+            );
+        }
     }
 }
 
@@ -309,12 +808,283 @@ mod tests {
         );
     }
 
+    #[test]
+    fn from_str_parses_address_lists() {
+        let parsed: BlokliDnsOverride = "10.1.2.1:3002, [::1]:3002,10.1.2.2".parse().unwrap();
+        assert_eq!(
+            parsed.addresses().collect::<Vec<_>>(),
+            [
+                DnsOverrideAddr {
+                    ip: v4(10, 1, 2, 1),
+                    port: Some(3002)
+                },
+                DnsOverrideAddr {
+                    ip: IpAddr::V6(Ipv6Addr::LOCALHOST),
+                    port: Some(3002)
+                },
+                DnsOverrideAddr {
+                    ip: v4(10, 1, 2, 2),
+                    port: None
+                },
+            ]
+        );
+        assert_eq!(parsed.to_string(), "10.1.2.1:3002,[::1]:3002,10.1.2.2");
+    }
+
+    #[test]
+    fn from_str_rejects_a_bad_list_entry() {
+        let error = "10.1.2.1,nope".parse::<BlokliDnsOverride>().unwrap_err();
+        assert_eq!(
+            error,
+            ParseBlokliDnsOverrideError::InvalidFormat {
+                input: "nope".to_string()
+            }
+        );
+        assert!("10.1.2.1,".parse::<BlokliDnsOverride>().is_err());
+    }
+
+    #[test]
+    fn accessors_report_the_first_candidate() {
+        const PINNED: BlokliDnsOverride =
+            BlokliDnsOverride::new(IpAddr::V4(std::net::Ipv4Addr::LOCALHOST), Some(3002));
+        assert_eq!(PINNED.ip(), v4(127, 0, 0, 1));
+        assert_eq!(PINNED.port(), Some(3002));
+
+        let listed: BlokliDnsOverride = "10.1.2.1,[::1]:3002".parse().unwrap();
+        assert_eq!(listed.ip(), v4(10, 1, 2, 1));
+        assert_eq!(listed.port(), None);
+        assert_eq!(BlokliDnsOverride::from_addresses(Vec::new()), None);
+    }
+
+    #[test]
+    fn to_client_config_uses_the_first_candidate() {
+        let dns_override: BlokliDnsOverride = "10.1.2.1:3002,10.1.2.2".parse().unwrap();
+        let config = BlokliEndpoint::default()
+            .with_dns_override(dns_override)
+            .to_client_config();
+        assert_eq!(config.dns_override, Some((v4(10, 1, 2, 1), Some(3002))));
+    }
+
+    #[tokio::test]
+    async fn race_connections_picks_the_candidate_that_connects() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap();
+        // Bound then dropped, so connecting to it is refused.
+        let closed = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let direct = |addr: SocketAddr| Upstream {
+            proxy: None,
+            host: TunnelHost::Ip(addr.ip()),
+            port: addr.port(),
+        };
+
+        let winner = race_connections(
+            &[direct(closed), direct(open)],
+            Duration::from_millis(10),
+            Duration::from_secs(5),
+        )
+        .await;
+        assert_eq!(winner, Some(1));
+        assert_eq!(
+            race_connections(&[direct(closed)], Duration::ZERO, Duration::from_secs(5)).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn race_dns_override_races_through_the_proxy() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // An HTTP proxy that reaches 10.1.2.2 only.
+        let proxy = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy_addr = proxy.local_addr().unwrap();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = proxy.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut head = Vec::new();
+                    while !head.ends_with(b"\r\n\r\n") {
+                        head.push(stream.read_u8().await.unwrap());
+                    }
+                    let reply: &[u8] = if head.starts_with(b"CONNECT 10.1.2.2:443 ") {
+                        b"HTTP/1.1 200 Connection established\r\n\r\n"
+                    } else {
+                        b"HTTP/1.1 502 Bad Gateway\r\n\r\n"
+                    };
+                    stream.write_all(reply).await.unwrap();
+                });
+            }
+        });
+
+        let endpoint = BlokliEndpoint::default()
+            .with_dns_override("10.1.2.1,10.1.2.2".parse().unwrap())
+            .with_proxy(format!("http://{proxy_addr}").parse().unwrap())
+            .race_dns_override()
+            .await;
+        assert_eq!(
+            endpoint.dns_override,
+            Some(BlokliDnsOverride::new(v4(10, 1, 2, 2), None))
+        );
+    }
+
+    #[tokio::test]
+    async fn race_dns_override_narrows_to_the_winner() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let open = listener.local_addr().unwrap();
+        let closed = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let dns_override: BlokliDnsOverride = format!("{closed},{open}").parse().unwrap();
+        let endpoint = BlokliEndpoint::default()
+            .with_dns_override(dns_override)
+            .race_dns_override()
+            .await;
+        assert_eq!(
+            endpoint.dns_override,
+            Some(BlokliDnsOverride::new(open.ip(), Some(open.port())))
+        );
+    }
+
+    #[tokio::test]
+    async fn proxy_routes_the_endpoint_through_a_local_forwarder() {
+        let proxy: BlokliProxy = "socks5h://127.0.0.1:9050".parse().unwrap();
+        let (endpoint, _forwarder) = BlokliEndpoint::default()
+            .with_dns_override("10.1.2.1,10.1.2.2".parse().unwrap())
+            .with_proxy(proxy)
+            .route_through_forwarder()
+            .await
+            .unwrap();
+
+        let config = endpoint.to_client_config();
+        assert_eq!(config.url, *DEFAULT_BLOKLI_URL);
+        let (ip, port) = config.dns_override.unwrap();
+        assert!(ip.is_loopback());
+        assert!(port.is_some());
+    }
+
+    #[tokio::test]
+    async fn pinning_requires_https() {
+        let pin: SpkiPin = "sha256/l6Eti2CGmzuAj17/g/POvYDLICxEK2OpJ1SmhN4vSXo="
+            .parse()
+            .unwrap();
+        let error = BlokliEndpoint::new("http://blokli.example.com".parse().unwrap())
+            .with_spki_pins([pin])
+            .route_through_forwarder()
+            .await
+            .unwrap_err();
+        assert!(matches!(error, EdgliError::ConfigError(_)), "{error}");
+    }
+
+    #[tokio::test]
+    async fn pinning_switches_the_client_to_the_tls_terminating_forwarder() {
+        // Bound then dropped: the up-front handshake fails below TLS, which is tolerated.
+        let closed = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let pin: SpkiPin = "sha256/l6Eti2CGmzuAj17/g/POvYDLICxEK2OpJ1SmhN4vSXo="
+            .parse()
+            .unwrap();
+        let (endpoint, _forwarder) = BlokliEndpoint::default()
+            .with_dns_override(BlokliDnsOverride::new(closed.ip(), Some(closed.port())))
+            .with_spki_pins([pin])
+            .route_through_forwarder()
+            .await
+            .unwrap();
+
+        assert_eq!(endpoint.url.scheme(), "http");
+        assert_eq!(endpoint.url.host_str(), DEFAULT_BLOKLI_URL.host_str());
+        let (ip, port) = endpoint.to_client_config().dns_override.unwrap();
+        assert!(ip.is_loopback());
+        assert_ne!(port, Some(closed.port()));
+    }
+
     #[test]
     fn display_from_str_roundtrip() {
-        for input in ["127.0.0.1", "10.1.2.1:3002", "::1", "[::1]:3002"] {
+        for input in [
+            "127.0.0.1",
+            "10.1.2.1:3002",
+            "::1",
+            "[::1]:3002",
+            "10.1.2.1,[::1]:3002",
+        ] {
             let parsed: BlokliDnsOverride = input.parse().unwrap();
             let rendered = parsed.to_string();
             assert_eq!(rendered.parse::<BlokliDnsOverride>().unwrap(), parsed);
         }
     }
+
+    fn three_endpoints() -> BlokliEndpointSet {
+        BlokliEndpointSet::from_urls(&[
+            "https://a.example.com",
+            "https://b.example.com",
+            "https://c.example.com",
+        ])
+        .unwrap()
+        .with_cooldown(Duration::from_secs(60))
+    }
+
+    #[test]
+    fn endpoint_set_requires_an_endpoint() {
+        assert!(BlokliEndpointSet::new(vec![]).is_err());
+        let empty: [&str; 0] = [];
+        assert_eq!(
+            BlokliEndpointSet::from_urls(&empty).unwrap(),
+            BlokliEndpointSet::default()
+        );
+        assert_eq!(
+            BlokliEndpointSet::default().primary().url,
+            *DEFAULT_BLOKLI_URL
+        );
+    }
+
+    #[test]
+    fn failover_prefers_healthy_endpoints_in_order() {
+        let failover = three_endpoints().failover();
+        assert_eq!(failover.candidates(), vec![0, 1, 2]);
+        assert!(failover.active().is_none());
+
+        let now = Instant::now();
+        failover.record_failure_at(0, now);
+        assert_eq!(failover.candidates_at(now), vec![1, 2, 0]);
+
+        failover.record_success(1);
+        assert_eq!(
+            failover.active().unwrap().url.as_str(),
+            "https://b.example.com/"
+        );
+    }
+
+    #[test]
+    fn failover_orders_cooling_endpoints_by_recovery() {
+        let failover = three_endpoints().failover();
+        let now = Instant::now();
+        failover.record_failure_at(1, now);
+        failover.record_failure_at(0, now + Duration::from_secs(10));
+        assert_eq!(failover.candidates_at(now), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn failover_falls_back_after_the_cooldown() {
+        let failover = three_endpoints().failover();
+        let now = Instant::now();
+        failover.record_failure_at(0, now);
+        assert_eq!(failover.candidates_at(now)[0], 1);
+        assert_eq!(
+            failover.candidates_at(now + Duration::from_secs(61)),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn failover_clones_share_health() {
+        let failover = three_endpoints().failover();
+        failover.clone().record_failure_at(0, Instant::now());
+        assert_eq!(failover.candidates()[0], 1);
+    }
 }
@@ -19,6 +19,45 @@ pub enum EdgliError {
 
     #[error("os error: '{0}'")]
     OsError(String),
+
+    #[error("identity error: '{0}'")]
+    IdentityError(String),
+
+    #[error("the node is shutting down")]
+    ShuttingDown,
+
+    #[error("invalid host address: '{0}'")]
+    InvalidHostAddress(String),
+
+    #[error("cannot announce the loopback address '{0}' without preferring local addresses")]
+    LoopbackAnnouncement(String),
+
+    #[error("invalid probe configuration: '{0}'")]
+    InvalidProbeConfig(String),
+
+    #[error("invalid mix protocol: '{0}'")]
+    InvalidMixProtocol(String),
+
+    #[error("invalid ticket factory source: '{0}'")]
+    InvalidTicketFactorySource(String),
+
+    #[error("invalid reactor configuration: '{0}'")]
+    InvalidReactorConfig(String),
+
+    #[error("node startup failed: '{0}'")]
+    StartupError(String),
+
+    #[error("Blokli proxy error: '{0}'")]
+    ProxyError(String),
+
+    #[error("Blokli certificate matches no pinned key: '{0}'")]
+    SpkiPinMismatch(String),
+
+    #[error("Blokli TLS error: '{0}'")]
+    TlsError(String),
+
+    #[error("gas price above the configured cap: '{0}'")]
+    GasPriceAboveCap(String),
 }
 
 pub type Result<T> = std::result::Result<T, EdgliError>;
@@ -57,6 +96,56 @@ mod tests {
         assert_eq!(e.to_string(), "os error: 'signal failed'");
     }
 
+    #[test]
+    fn identity_error_display() {
+        let e = EdgliError::IdentityError("wrong password".into());
+        assert_eq!(e.to_string(), "identity error: 'wrong password'");
+    }
+
+    #[test]
+    fn shutting_down_display() {
+        assert_eq!(
+            EdgliError::ShuttingDown.to_string(),
+            "the node is shutting down"
+        );
+    }
+
+    #[test]
+    fn loopback_announcement_display() {
+        let e = EdgliError::LoopbackAnnouncement("127.0.0.1".into());
+        assert_eq!(
+            e.to_string(),
+            "cannot announce the loopback address '127.0.0.1' without preferring local addresses"
+        );
+    }
+
+    #[test]
+    fn startup_error_display() {
+        let e = EdgliError::StartupError("blokli unreachable".into());
+        assert_eq!(e.to_string(), "node startup failed: 'blokli unreachable'");
+    }
+
+    #[test]
+    fn proxy_error_display() {
+        let e = EdgliError::ProxyError("connection refused".into());
+        assert_eq!(e.to_string(), "Blokli proxy error: 'connection refused'");
+    }
+
+    #[test]
+    fn spki_pin_mismatch_display() {
+        let e = EdgliError::SpkiPinMismatch("blokli.example.com".into());
+        assert_eq!(
+            e.to_string(),
+            "Blokli certificate matches no pinned key: 'blokli.example.com'"
+        );
+    }
+
+    #[test]
+    fn gas_price_above_cap_display() {
+        let e = EdgliError::GasPriceAboveCap("5 > 2".into());
+        assert_eq!(e.to_string(), "gas price above the configured cap: '5 > 2'");
+    }
+
     #[test]
     fn hopr_lib_error_converts_via_from() {
         let hopr_err = hopr_lib::errors::HoprLibError::GeneralError("something broke".into());
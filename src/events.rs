@@ -0,0 +1,468 @@
+//! Typed change notifications for [`crate::EdgeNodeApi::events`].
+//!
+//! [`EdgeEvent`]s report what changed between two looks at a node: a channel opened,
+//! funded, pending to close or closed, new balances, a peer connected or disconnected, or
+//! a new [`HoprState`]. [`EdgeEventTracker`] derives them from successive
+//! [`EdgeSnapshot`]s and from single changes reported as they happen, and
+//! [`EdgeEventChannel`] fans them out to every subscribed stream;
+//! stub [`crate::EdgeNodeApi`] implementations can send their own events through one.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use futures::StreamExt as _;
+use futures::channel::mpsc;
+use futures::stream::BoxStream;
+use hopr_lib::api::{
+    node::HoprState,
+    types::{
+        internal::channels::{ChannelEntry, ChannelStatus},
+        primitive::prelude::{Address, HoprBalance},
+    },
+};
+
+use crate::traits::NodeBalances;
+
+/// A change to the node's channels, balances, peers or state.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EdgeEvent {
+    /// An outgoing channel to `destination` opened with `balance`.
+    ChannelOpened {
+        destination: Address,
+        balance: HoprBalance,
+    },
+    /// The open channel to `destination` was topped up to `balance`.
+    ChannelFunded {
+        destination: Address,
+        balance: HoprBalance,
+    },
+    /// Closing the channel to `destination` started; it can be finalized after the grace
+    /// period.
+    ChannelPendingToClose { destination: Address },
+    /// The channel to `destination` closed.
+    ChannelClosed { destination: Address },
+    /// The node or Safe balances changed.
+    BalancesChanged(NodeBalances),
+    /// A peer, by on-chain address, connected.
+    PeerConnected(Address),
+    /// A peer, by on-chain address, disconnected.
+    PeerDisconnected(Address),
+    /// The node moved to a new lifecycle state.
+    StateChanged(HoprState),
+}
+
+/// Where a channel is in its lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelPhase {
+    Open,
+    PendingToClose,
+    Closed,
+}
+
+impl From<&ChannelStatus> for ChannelPhase {
+    fn from(status: &ChannelStatus) -> Self {
+        match status {
+            ChannelStatus::Open => Self::Open,
+            ChannelStatus::PendingToClose(_) => Self::PendingToClose,
+            ChannelStatus::Closed => Self::Closed,
+        }
+    }
+}
+
+/// One outgoing channel as an [`EdgeEventTracker`] compares it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChannelSnapshot {
+    pub destination: Address,
+    pub phase: ChannelPhase,
+    pub balance: HoprBalance,
+}
+
+impl From<&ChannelEntry> for ChannelSnapshot {
+    fn from(channel: &ChannelEntry) -> Self {
+        Self {
+            destination: channel.destination,
+            phase: (&channel.status).into(),
+            balance: channel.balance,
+        }
+    }
+}
+
+/// What an [`EdgeEventTracker`] compares; a part that could not be read is `None` and keeps
+/// its previous value.
+#[derive(Clone, Debug)]
+pub struct EdgeSnapshot {
+    pub state: HoprState,
+    pub channels: Option<Vec<ChannelSnapshot>>,
+    pub balances: Option<NodeBalances>,
+    pub peers: Option<Vec<Address>>,
+}
+
+/// Derives [`EdgeEvent`]s from successive [`EdgeSnapshot`]s.
+///
+/// The first snapshot of each part is the baseline and raises no events.
+#[derive(Debug, Default)]
+pub struct EdgeEventTracker {
+    state: Option<HoprState>,
+    channels: Option<HashMap<Address, ChannelSnapshot>>,
+    balances: Option<NodeBalances>,
+    peers: Option<HashSet<Address>>,
+}
+
+impl EdgeEventTracker {
+    /// The events between the previous snapshot and `snapshot`.
+    pub fn update(&mut self, snapshot: EdgeSnapshot) -> Vec<EdgeEvent> {
+        let mut events = Vec::new();
+
+        if self
+            .state
+            .replace(snapshot.state)
+            .is_some_and(|s| s != snapshot.state)
+        {
+            events.push(EdgeEvent::StateChanged(snapshot.state));
+        }
+
+        if let Some(channels) = snapshot.channels {
+            let current: HashMap<_, _> = channels.into_iter().map(|c| (c.destination, c)).collect();
+            if let Some(previous) = &self.channels {
+                events.extend(channel_events(previous, &current));
+            }
+            self.channels = Some(current);
+        }
+
+        if let Some(balances) = snapshot.balances {
+            if self.balances.as_ref().is_some_and(|b| *b != balances) {
+                events.push(EdgeEvent::BalancesChanged(balances.clone()));
+            }
+            self.balances = Some(balances);
+        }
+
+        if let Some(peers) = snapshot.peers {
+            let current: HashSet<_> = peers.into_iter().collect();
+            if let Some(previous) = &self.peers {
+                events.extend(
+                    current
+                        .difference(previous)
+                        .map(|p| EdgeEvent::PeerConnected(*p)),
+                );
+                events.extend(
+                    previous
+                        .difference(&current)
+                        .map(|p| EdgeEvent::PeerDisconnected(*p)),
+                );
+            }
+            self.peers = Some(current);
+        }
+
+        events
+    }
+
+    /// The events from one channel having changed to `channel`, as reported when it changed.
+    ///
+    /// Later snapshots are compared against it, so the change is not reported again, and a
+    /// channel that opened and closed between two snapshots is still reported.
+    pub fn update_channel(&mut self, channel: ChannelSnapshot) -> Vec<EdgeEvent> {
+        let destination = channel.destination;
+        let previous: HashMap<_, _> = self
+            .channels
+            .as_ref()
+            .and_then(|channels| channels.get(&destination))
+            .map(|before| (destination, *before))
+            .into_iter()
+            .collect();
+        let events = channel_events(&previous, &HashMap::from([(destination, channel)]));
+        if let Some(channels) = &mut self.channels {
+            channels.insert(destination, channel);
+        }
+        events
+    }
+
+    /// The events from `peer` having connected or disconnected, as reported when it did.
+    pub fn update_peer(&mut self, peer: Address, connected: bool) -> Vec<EdgeEvent> {
+        let changed = match &mut self.peers {
+            Some(peers) if connected => peers.insert(peer),
+            Some(peers) => peers.remove(&peer),
+            None => true,
+        };
+        match (changed, connected) {
+            (false, _) => Vec::new(),
+            (true, true) => vec![EdgeEvent::PeerConnected(peer)],
+            (true, false) => vec![EdgeEvent::PeerDisconnected(peer)],
+        }
+    }
+}
+
+fn channel_events(
+    previous: &HashMap<Address, ChannelSnapshot>,
+    current: &HashMap<Address, ChannelSnapshot>,
+) -> Vec<EdgeEvent> {
+    let mut events = Vec::new();
+    for (destination, channel) in current {
+        let destination = *destination;
+        let before = previous
+            .get(&destination)
+            .filter(|c| c.phase != ChannelPhase::Closed);
+        match (before.map(|c| (c.phase, c.balance)), channel.phase) {
+            (None, ChannelPhase::Open) => events.push(EdgeEvent::ChannelOpened {
+                destination,
+                balance: channel.balance,
+            }),
+            (Some((ChannelPhase::Open, balance)), ChannelPhase::Open)
+                if channel.balance > balance =>
+            {
+                events.push(EdgeEvent::ChannelFunded {
+                    destination,
+                    balance: channel.balance,
+                })
+            }
+            (Some((ChannelPhase::Open, _)), ChannelPhase::PendingToClose) => {
+                events.push(EdgeEvent::ChannelPendingToClose { destination })
+            }
+            (Some(_), ChannelPhase::Closed) => {
+                events.push(EdgeEvent::ChannelClosed { destination })
+            }
+            _ => {}
+        }
+    }
+    // A channel that is no longer listed at all has closed too.
+    events.extend(
+        previous
+            .values()
+            .filter(|c| c.phase != ChannelPhase::Closed && !current.contains_key(&c.destination))
+            .map(|c| EdgeEvent::ChannelClosed {
+                destination: c.destination,
+            }),
+    );
+    events
+}
+
+/// Fans [`EdgeEvent`]s out to every stream subscribed to it.
+///
+/// Clones share their subscribers. Streams only see events sent after they subscribed, and
+/// end once every clone of the channel is dropped.
+#[derive(Clone, Debug, Default)]
+pub struct EdgeEventChannel {
+    subscribers: Arc<Mutex<Vec<mpsc::UnboundedSender<EdgeEvent>>>>,
+}
+
+impl EdgeEventChannel {
+    /// A stream of the events sent from now on.
+    pub fn subscribe(&self) -> BoxStream<'static, EdgeEvent> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(sender);
+        receiver.boxed()
+    }
+
+    /// Sends `event` to every live subscriber, forgetting those whose stream was dropped.
+    pub fn send(&self, event: EdgeEvent) {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|s| s.unbounded_send(event.clone()).is_ok());
+    }
+
+    /// Whether no stream is subscribed.
+    pub fn is_idle(&self) -> bool {
+        let mut subscribers = self.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        subscribers.retain(|s| !s.is_closed());
+        subscribers.is_empty()
+    }
+}
+
+impl Drop for EdgeEventChannel {
+    fn drop(&mut self) {
+        // Subscriber streams end with the last clone.
+        if Arc::strong_count(&self.subscribers) == 1 {
+            self.subscribers
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hopr_lib::api::types::primitive::prelude::XDaiBalance;
+
+    use super::*;
+
+    const PEER: [u8; 20] = [0x44; 20];
+
+    fn channel(phase: ChannelPhase, balance: u32) -> ChannelSnapshot {
+        ChannelSnapshot {
+            destination: PEER.into(),
+            phase,
+            balance: HoprBalance::from(balance),
+        }
+    }
+
+    fn snapshot(channels: Vec<ChannelSnapshot>) -> EdgeSnapshot {
+        EdgeSnapshot {
+            state: HoprState::Running,
+            channels: Some(channels),
+            balances: None,
+            peers: None,
+        }
+    }
+
+    #[test]
+    fn first_snapshot_is_the_baseline() {
+        let mut tracker = EdgeEventTracker::default();
+        let events = tracker.update(EdgeSnapshot {
+            state: HoprState::Running,
+            channels: Some(vec![channel(ChannelPhase::Open, 10)]),
+            balances: Some(NodeBalances {
+                node_wxhopr: HoprBalance::zero(),
+                safe_wxhopr: HoprBalance::zero(),
+                node_xdai: XDaiBalance::zero(),
+            }),
+            peers: Some(vec![PEER.into()]),
+        });
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn channel_lifecycle_is_reported_in_order() {
+        let destination: Address = PEER.into();
+        let mut tracker = EdgeEventTracker::default();
+        tracker.update(snapshot(vec![]));
+
+        let steps = [
+            (
+                channel(ChannelPhase::Open, 10),
+                Some(EdgeEvent::ChannelOpened {
+                    destination,
+                    balance: HoprBalance::from(10u32),
+                }),
+            ),
+            // Spending tickets lowers the balance; that is not news.
+            (channel(ChannelPhase::Open, 4), None),
+            (
+                channel(ChannelPhase::Open, 20),
+                Some(EdgeEvent::ChannelFunded {
+                    destination,
+                    balance: HoprBalance::from(20u32),
+                }),
+            ),
+            (
+                channel(ChannelPhase::PendingToClose, 20),
+                Some(EdgeEvent::ChannelPendingToClose { destination }),
+            ),
+            (
+                channel(ChannelPhase::Closed, 0),
+                Some(EdgeEvent::ChannelClosed { destination }),
+            ),
+            (channel(ChannelPhase::Closed, 0), None),
+        ];
+        for (step, expected) in steps {
+            assert_eq!(
+                tracker.update(snapshot(vec![step])),
+                Vec::from_iter(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn vanished_channels_are_closed_and_unread_parts_keep_their_value() {
+        let mut tracker = EdgeEventTracker::default();
+        tracker.update(snapshot(vec![channel(ChannelPhase::Open, 10)]));
+
+        let unread = EdgeSnapshot {
+            channels: None,
+            ..snapshot(vec![])
+        };
+        assert!(tracker.update(unread).is_empty());
+        assert_eq!(
+            tracker.update(snapshot(vec![])),
+            [EdgeEvent::ChannelClosed {
+                destination: PEER.into()
+            }]
+        );
+    }
+
+    #[test]
+    fn peers_and_state_changes_are_reported() {
+        let other: Address = [0x55u8; 20].into();
+        let mut tracker = EdgeEventTracker::default();
+        tracker.update(EdgeSnapshot {
+            peers: Some(vec![PEER.into()]),
+            ..snapshot(vec![])
+        });
+
+        let events = tracker.update(EdgeSnapshot {
+            state: HoprState::Uninitialized,
+            peers: Some(vec![other]),
+            ..snapshot(vec![])
+        });
+        assert_eq!(
+            events,
+            [
+                EdgeEvent::StateChanged(HoprState::Uninitialized),
+                EdgeEvent::PeerConnected(other),
+                EdgeEvent::PeerDisconnected(PEER.into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reported_changes_are_not_repeated_by_the_next_snapshot() {
+        let destination: Address = PEER.into();
+        let mut tracker = EdgeEventTracker::default();
+        tracker.update(EdgeSnapshot {
+            peers: Some(vec![]),
+            ..snapshot(vec![])
+        });
+
+        // Open and closed again before the next snapshot.
+        assert_eq!(
+            tracker.update_channel(channel(ChannelPhase::Open, 10)),
+            [EdgeEvent::ChannelOpened {
+                destination,
+                balance: HoprBalance::from(10u32),
+            }]
+        );
+        assert_eq!(
+            tracker.update_channel(channel(ChannelPhase::Closed, 0)),
+            [EdgeEvent::ChannelClosed { destination }]
+        );
+        assert_eq!(
+            tracker.update_peer(destination, true),
+            [EdgeEvent::PeerConnected(destination)]
+        );
+        assert!(tracker.update_peer(destination, true).is_empty());
+
+        let events = tracker.update(EdgeSnapshot {
+            peers: Some(vec![destination]),
+            ..snapshot(vec![channel(ChannelPhase::Closed, 0)])
+        });
+        assert!(events.is_empty(), "{events:?}");
+        assert_eq!(
+            tracker.update_peer(destination, false),
+            [EdgeEvent::PeerDisconnected(destination)]
+        );
+    }
+
+    #[tokio::test]
+    async fn channel_fans_events_out_until_dropped() {
+        let channel = EdgeEventChannel::default();
+        let before = channel.subscribe();
+        assert!(!channel.is_idle());
+
+        let event = EdgeEvent::PeerConnected(PEER.into());
+        channel.send(event.clone());
+        let mut after = channel.subscribe();
+        drop(channel.clone());
+        channel.send(event.clone());
+        drop(channel);
+
+        assert_eq!(
+            before.collect::<Vec<_>>().await,
+            [event.clone(), event.clone()]
+        );
+        assert_eq!(after.next().await, Some(event));
+        assert_eq!(after.next().await, None);
+    }
+}
@@ -0,0 +1,355 @@
+//! Bounding the gas price edgli submits transactions at.
+//!
+//! Blokli reports the chain's current `max_fee_per_gas`, which the connector signs every
+//! transaction with. A [`GasCap`] refuses transactions while that price is above the user's
+//! cap, so the cap bounds the fee actually paid; it does not change the price signed with.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use hopr_lib::api::types::primitive::prelude::{Address, HoprBalance, XDaiBalance};
+
+use crate::blokli::{IncentiveOperations, SafeModuleDeploymentResult, TicketStats};
+use crate::errors::EdgliError;
+use crate::strategy::StartupCosts;
+
+/// Number of channel transactions [`ObservedGas`] takes its median over.
+const OBSERVED_GAS_WINDOW: usize = 20;
+
+/// How often a node running strategy reactors checks the chain's gas price against its cap.
+pub(crate) const GAS_CAP_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// The user's cap on the gas price transactions are submitted at. The default is uncapped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GasCap {
+    max_fee_per_gas: Option<u128>,
+}
+
+impl GasCap {
+    /// Refuses transactions while Blokli reports more than `max_fee_per_gas` wei per gas.
+    pub fn new(max_fee_per_gas: u128) -> Self {
+        Self {
+            max_fee_per_gas: Some(max_fee_per_gas),
+        }
+    }
+
+    /// The cap in wei per gas, if any.
+    pub fn max_fee_per_gas(&self) -> Option<u128> {
+        self.max_fee_per_gas
+    }
+
+    /// Checks the cap.
+    pub fn validate(&self) -> Result<(), EdgliError> {
+        if self.max_fee_per_gas == Some(0) {
+            return Err(EdgliError::ConfigError(
+                "max fee per gas cap must be non-zero".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Fails with [`EdgliError::GasPriceAboveCap`] while the `reported` price, the one a
+    /// transaction would be signed with, is above the cap.
+    pub fn check_submission(&self, reported: u128) -> Result<(), EdgliError> {
+        match self.max_fee_per_gas {
+            Some(cap) if reported > cap => Err(EdgliError::GasPriceAboveCap(format!(
+                "{reported} wei per gas exceeds the cap of {cap}"
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Gas the node's own channel transactions used, measured from its xDAI balance.
+///
+/// The chain connector reports a confirmed transaction by its hash alone, so the gas used is
+/// inferred: the node's xDAI spent across the transaction divided by the `max_fee_per_gas` it
+/// was signed with. That is the gas *effective* at the reported price, which is what
+/// [`crate::strategy::project_xdai_budget`] multiplies back by a reported price. The strategy
+/// reactors submit transactions this cannot see, so a measurement is dropped when another
+/// measured transaction overlapped it or when it exceeds the gas limit, and the median of the
+/// last [`OBSERVED_GAS_WINDOW`] is reported so that a stray sample does not move it.
+#[derive(Debug, Default)]
+pub(crate) struct ObservedGas {
+    samples: Mutex<VecDeque<u64>>,
+    in_flight: AtomicUsize,
+    started: AtomicU64,
+}
+
+impl ObservedGas {
+    /// Starts measuring a transaction about to be signed at `max_fee_per_gas`, with the node
+    /// holding `xdai_before`.
+    pub(crate) fn start(
+        self: &Arc<Self>,
+        xdai_before: XDaiBalance,
+        max_fee_per_gas: u128,
+    ) -> GasMeasurement {
+        let id = self.started.fetch_add(1, Ordering::SeqCst) + 1;
+        let alone = self.in_flight.fetch_add(1, Ordering::SeqCst) == 0;
+        GasMeasurement {
+            observed: self.clone(),
+            id,
+            alone,
+            xdai_before,
+            max_fee_per_gas,
+        }
+    }
+
+    /// Median gas of the measured transactions, or `None` before the first measurement.
+    pub(crate) fn median(&self) -> Option<u64> {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let mut sorted: Vec<u64> = samples.iter().copied().collect();
+        sorted.sort_unstable();
+        sorted.get(sorted.len() / 2).copied()
+    }
+}
+
+/// A transaction being measured by [`ObservedGas`]; see [`Self::record`].
+#[derive(Debug)]
+pub(crate) struct GasMeasurement {
+    observed: Arc<ObservedGas>,
+    id: u64,
+    alone: bool,
+    xdai_before: XDaiBalance,
+    max_fee_per_gas: u128,
+}
+
+impl GasMeasurement {
+    /// Records the transaction's gas from the node holding `xdai_after` once it confirmed.
+    ///
+    /// Nothing is recorded when another measured transaction overlapped this one, when the
+    /// balance does not yet show any spend, or when the spend is more than the gas limit
+    /// could cost, which means something other than this transaction spent too.
+    pub(crate) fn record(self, xdai_after: XDaiBalance) {
+        let overlapped = !self.alone || self.observed.started.load(Ordering::SeqCst) != self.id;
+        if overlapped || self.max_fee_per_gas == 0 || xdai_after >= self.xdai_before {
+            return;
+        }
+        let spent = (self.xdai_before - xdai_after)
+            .amount()
+            .min(u128::MAX.into())
+            .low_u128();
+        let gas = spent / self.max_fee_per_gas;
+        let gas_limit = hopr_lib::api::types::chain::payload::GasEstimation::default().gas_limit;
+        if gas == 0 || gas > gas_limit as u128 {
+            return;
+        }
+        let mut samples = self
+            .observed
+            .samples
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        samples.push_back(gas as u64);
+        while samples.len() > OBSERVED_GAS_WINDOW {
+            samples.pop_front();
+        }
+    }
+}
+
+impl Drop for GasMeasurement {
+    fn drop(&mut self) {
+        self.observed.in_flight.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// An [`IncentiveOperations`] handle that enforces a [`GasCap`].
+///
+/// [`IncentiveOperations::deploy_safe`] and [`IncentiveOperations::withdraw_wxhopr`] query the
+/// current price first and fail with [`EdgliError::GasPriceAboveCap`] instead of submitting
+/// while it is above the cap. Everything else, the reported price included, passes through.
+pub struct GasCappedIncentiveOperations {
+    inner: Box<dyn IncentiveOperations>,
+    cap: GasCap,
+}
+
+impl GasCappedIncentiveOperations {
+    /// Wraps `inner`, refusing its transactions above `cap`.
+    pub fn new(inner: Box<dyn IncentiveOperations>, cap: GasCap) -> Self {
+        Self { inner, cap }
+    }
+
+    async fn check_submission(&self) -> anyhow::Result<()> {
+        let reported = self.inner.max_fee_per_gas().await?;
+        self.cap.check_submission(reported)?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl IncentiveOperations for GasCappedIncentiveOperations {
+    async fn retrieve_safe(&self) -> anyhow::Result<Option<SafeModuleDeploymentResult>> {
+        self.inner.retrieve_safe().await
+    }
+
+    async fn deploy_safe(
+        &self,
+        token_amount: HoprBalance,
+    ) -> anyhow::Result<SafeModuleDeploymentResult> {
+        // An existing Safe is reported without a transaction, so the cap does not apply.
+        if let Some(safe) = self.inner.retrieve_safe().await? {
+            return Ok(safe);
+        }
+        self.check_submission().await?;
+        self.inner.deploy_safe(token_amount).await
+    }
+
+    async fn ticket_stats(&self) -> anyhow::Result<TicketStats> {
+        self.inner.ticket_stats().await
+    }
+
+    async fn max_fee_per_gas(&self) -> anyhow::Result<u128> {
+        self.inner.max_fee_per_gas().await
+    }
+
+    async fn compute_costs_to_start(&self) -> anyhow::Result<StartupCosts> {
+        self.inner.compute_costs_to_start().await
+    }
+
+    async fn balances(&self) -> anyhow::Result<(HoprBalance, XDaiBalance)> {
+        self.inner.balances().await
+    }
+
+    async fn withdraw_wxhopr(
+        &self,
+        safe_address: Address,
+        amount: HoprBalance,
+    ) -> anyhow::Result<()> {
+        self.check_submission().await?;
+        self.inner.withdraw_wxhopr(safe_address, amount).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hopr_lib::api::types::internal::prelude::WinningProbability;
+
+    use super::*;
+
+    const GWEI: u128 = 1_000_000_000;
+
+    #[test]
+    fn cap_refuses_submissions_above_it() {
+        let cap = GasCap::new(3 * GWEI);
+        assert!(cap.check_submission(3 * GWEI).is_ok());
+        assert!(matches!(
+            cap.check_submission(4 * GWEI),
+            Err(EdgliError::GasPriceAboveCap(_))
+        ));
+        assert!(GasCap::default().check_submission(u128::MAX).is_ok());
+        assert!(GasCap::new(0).validate().is_err());
+    }
+
+    /// Reports a settable gas price and counts the transactions submitted.
+    #[derive(Default)]
+    struct Chain {
+        gas_price: Arc<AtomicU64>,
+        submitted: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl IncentiveOperations for Chain {
+        async fn retrieve_safe(&self) -> anyhow::Result<Option<SafeModuleDeploymentResult>> {
+            Ok(None)
+        }
+
+        async fn deploy_safe(
+            &self,
+            _token_amount: HoprBalance,
+        ) -> anyhow::Result<SafeModuleDeploymentResult> {
+            self.submitted.fetch_add(1, Ordering::SeqCst);
+            Ok(SafeModuleDeploymentResult {
+                safe_address: [0x11u8; 20].into(),
+                module_address: [0x22u8; 20].into(),
+            })
+        }
+
+        async fn ticket_stats(&self) -> anyhow::Result<TicketStats> {
+            Ok(TicketStats {
+                ticket_price: "0.000001 wxHOPR".parse().unwrap(),
+                winning_probability: WinningProbability::default(),
+            })
+        }
+
+        async fn max_fee_per_gas(&self) -> anyhow::Result<u128> {
+            Ok(self.gas_price.load(Ordering::SeqCst).into())
+        }
+
+        async fn compute_costs_to_start(&self) -> anyhow::Result<StartupCosts> {
+            Ok(StartupCosts {
+                fee_to_start: HoprBalance::zero(),
+                txs_to_start: 3,
+            })
+        }
+
+        async fn balances(&self) -> anyhow::Result<(HoprBalance, XDaiBalance)> {
+            Ok((HoprBalance::zero(), XDaiBalance::zero()))
+        }
+
+        async fn withdraw_wxhopr(
+            &self,
+            _safe_address: Address,
+            _amount: HoprBalance,
+        ) -> anyhow::Result<()> {
+            self.submitted.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn transactions_wait_for_the_price_to_fall_under_the_cap() {
+        let chain = Chain::default();
+        let (gas_price, submitted) = (chain.gas_price.clone(), chain.submitted.clone());
+        let ops = GasCappedIncentiveOperations::new(Box::new(chain), GasCap::new(2 * GWEI));
+
+        gas_price.store(5 * GWEI as u64, Ordering::SeqCst);
+        assert_eq!(ops.max_fee_per_gas().await.unwrap(), 5 * GWEI);
+        let refused = ops.deploy_safe(HoprBalance::zero()).await.unwrap_err();
+        assert!(matches!(
+            refused.downcast_ref::<EdgliError>(),
+            Some(EdgliError::GasPriceAboveCap(_))
+        ));
+        assert!(
+            ops.withdraw_wxhopr([0x11u8; 20].into(), HoprBalance::zero())
+                .await
+                .is_err()
+        );
+        assert_eq!(submitted.load(Ordering::SeqCst), 0);
+
+        gas_price.store(GWEI as u64, Ordering::SeqCst);
+        ops.deploy_safe(HoprBalance::zero()).await.unwrap();
+        ops.withdraw_wxhopr([0x11u8; 20].into(), HoprBalance::zero())
+            .await
+            .unwrap();
+        assert_eq!(submitted.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn observed_gas_keeps_measurements_no_other_transaction_overlapped() {
+        let observed = Arc::new(ObservedGas::default());
+        let xdai = |wei: u128| XDaiBalance::from(wei);
+        assert_eq!(observed.median(), None);
+
+        observed
+            .start(xdai(10_000_000 * GWEI), GWEI)
+            .record(xdai(9_900_000 * GWEI));
+        assert_eq!(observed.median(), Some(100_000));
+
+        // Two transactions in flight at once cannot tell whose spend is whose.
+        let first = observed.start(xdai(10_000_000 * GWEI), GWEI);
+        let second = observed.start(xdai(10_000_000 * GWEI), GWEI);
+        first.record(xdai(9_000_000 * GWEI));
+        second.record(xdai(9_000_000 * GWEI));
+        // Neither is a spend that fits one transaction: none, or more than the gas limit.
+        observed.start(xdai(GWEI), GWEI).record(xdai(GWEI));
+        observed.start(xdai(u128::MAX), GWEI).record(xdai(0));
+        assert_eq!(observed.median(), Some(100_000));
+
+        for gas in [200_000, 300_000] {
+            observed
+                .start(xdai(10_000_000 * GWEI), 2 * GWEI)
+                .record(xdai(10_000_000 * GWEI - gas * 2 * GWEI));
+        }
+        assert_eq!(observed.median(), Some(200_000));
+    }
+}
@@ -0,0 +1,266 @@
+//! Liveness and readiness of an edge node, for orchestrators such as Kubernetes.
+//!
+//! A [`HealthMonitor`] is created before the node: it records every [`EdgliInitState`] the
+//! node passes through (wire [`HealthMonitor::record`] into [`crate::EdgliBuilder::visitor`])
+//! and is handed the node once it exists. [`HealthMonitor::report`] then combines the last init
+//! state with the node's [`HoprState`], Blokli connectivity, connected peers and funded open
+//! channels into a [`HealthReport`], which [`HealthReport::readiness`] checks against
+//! [`HealthThresholds`].
+
+use std::sync::{Arc, Mutex, OnceLock};
+
+use hopr_lib::api::{
+    node::HoprState,
+    types::{internal::channels::ChannelStatus, primitive::prelude::HoprBalance},
+};
+
+use crate::client::EdgliInitState;
+use crate::traits::EdgeNodeApi;
+
+/// What [`HealthMonitor::report`] needs from a node beyond [`EdgeNodeApi`].
+#[async_trait::async_trait]
+pub trait HealthProbe: EdgeNodeApi {
+    /// Whether Blokli answers a cheap query right now.
+    async fn blokli_reachable(&self) -> bool;
+}
+
+#[async_trait::async_trait]
+impl HealthProbe for crate::Edgli {
+    async fn blokli_reachable(&self) -> bool {
+        crate::Edgli::blokli_reachable(self).await
+    }
+}
+
+/// Minimums a node must meet to be reported ready.
+#[derive(Clone, Debug, smart_default::SmartDefault)]
+pub struct HealthThresholds {
+    /// Connected peers required. Default: 1.
+    #[default(1)]
+    pub min_connected_peers: usize,
+
+    /// Open outgoing channels with a non-zero balance required. Default: 0, since a node
+    /// without a strategy never opens any.
+    #[default(0)]
+    pub min_funded_channels: usize,
+
+    /// Whether Blokli must be reachable. Default: `true`.
+    #[default(true)]
+    pub require_blokli: bool,
+}
+
+/// Point-in-time health of an edge node. `None` fields could not be determined, either
+/// because the node is not built yet or because the query failed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HealthReport {
+    /// The last initialization stage reached.
+    pub init_state: Option<EdgliInitState>,
+    /// The node's lifecycle state.
+    pub node_state: Option<HoprState>,
+    /// Whether Blokli answered.
+    pub blokli_reachable: Option<bool>,
+    /// Currently connected peers.
+    pub connected_peers: Option<usize>,
+    /// Open outgoing channels with a non-zero balance.
+    pub funded_open_channels: Option<usize>,
+}
+
+/// Outcome of [`HealthReport::readiness`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Readiness {
+    pub ready: bool,
+    /// Human-readable reasons the node is not ready; empty when ready.
+    pub unmet: Vec<String>,
+}
+
+impl HealthReport {
+    /// Checks the report against `thresholds`, listing every unmet condition.
+    pub fn readiness(&self, thresholds: &HealthThresholds) -> Readiness {
+        let mut unmet = Vec::new();
+
+        if self.init_state != Some(EdgliInitState::Ready) {
+            unmet.push(match self.init_state {
+                Some(state) => format!("initialization incomplete: {state}"),
+                None => "initialization not started".into(),
+            });
+        }
+        if self.node_state != Some(HoprState::Running) {
+            unmet.push(format!("node is not running: {:?}", self.node_state));
+        }
+        if thresholds.require_blokli && self.blokli_reachable != Some(true) {
+            unmet.push("blokli is unreachable".into());
+        }
+        match self.connected_peers {
+            Some(peers) if peers >= thresholds.min_connected_peers => {}
+            peers => unmet.push(format!(
+                "connected peers {} below {}",
+                peers.map_or("unknown".into(), |p| p.to_string()),
+                thresholds.min_connected_peers
+            )),
+        }
+        match self.funded_open_channels {
+            Some(channels) if channels >= thresholds.min_funded_channels => {}
+            channels => unmet.push(format!(
+                "funded open channels {} below {}",
+                channels.map_or("unknown".into(), |c| c.to_string()),
+                thresholds.min_funded_channels
+            )),
+        }
+
+        Readiness {
+            ready: unmet.is_empty(),
+            unmet,
+        }
+    }
+}
+
+/// Collects [`HealthReport`]s across a node's whole life, initialization included.
+#[derive(Clone, Default)]
+pub struct HealthMonitor {
+    init_state: Arc<Mutex<Option<EdgliInitState>>>,
+    node: Arc<OnceLock<Arc<dyn HealthProbe>>>,
+    thresholds: HealthThresholds,
+}
+
+impl HealthMonitor {
+    pub fn new(thresholds: HealthThresholds) -> Self {
+        Self {
+            thresholds,
+            ..Default::default()
+        }
+    }
+
+    /// Records an initialization stage; call from [`crate::EdgliBuilder::visitor`].
+    pub fn record(&self, state: EdgliInitState) {
+        *self.init_state.lock().unwrap_or_else(|e| e.into_inner()) = Some(state);
+    }
+
+    /// Hands over the built node. Only the first call has an effect.
+    pub fn attach(&self, node: Arc<dyn HealthProbe>) {
+        let _ = self.node.set(node);
+    }
+
+    /// The last initialization stage recorded.
+    pub fn init_state(&self) -> Option<EdgliInitState> {
+        *self.init_state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn thresholds(&self) -> &HealthThresholds {
+        &self.thresholds
+    }
+
+    /// Queries the node, if attached, for a fresh report.
+    pub async fn report(&self) -> HealthReport {
+        let init_state = self.init_state();
+        let Some(node) = self.node.get() else {
+            return HealthReport {
+                init_state,
+                node_state: None,
+                blokli_reachable: None,
+                connected_peers: None,
+                funded_open_channels: None,
+            };
+        };
+
+        let (blokli_reachable, peers, channels) = futures::join!(
+            node.blokli_reachable(),
+            node.connected_peer_addresses(),
+            node.my_outgoing_channels()
+        );
+
+        HealthReport {
+            init_state,
+            node_state: Some(node.status()),
+            blokli_reachable: Some(blokli_reachable),
+            connected_peers: peers.ok().map(|peers| peers.len()),
+            funded_open_channels: channels.ok().map(|channels| {
+                channels
+                    .iter()
+                    .filter(|c| c.status == ChannelStatus::Open && c.balance > HoprBalance::zero())
+                    .count()
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn healthy() -> HealthReport {
+        HealthReport {
+            init_state: Some(EdgliInitState::Ready),
+            node_state: Some(HoprState::Running),
+            blokli_reachable: Some(true),
+            connected_peers: Some(3),
+            funded_open_channels: Some(2),
+        }
+    }
+
+    #[test]
+    fn healthy_node_is_ready() {
+        let readiness = healthy().readiness(&HealthThresholds::default());
+        assert!(readiness.ready, "{readiness:?}");
+        assert!(readiness.unmet.is_empty());
+    }
+
+    #[test]
+    fn incomplete_initialization_is_not_ready() {
+        let report = HealthReport {
+            init_state: Some(EdgliInitState::ConnectingBlockchain),
+            ..healthy()
+        };
+        let readiness = report.readiness(&HealthThresholds::default());
+        assert!(!readiness.ready);
+        assert_eq!(readiness.unmet.len(), 1);
+        assert!(readiness.unmet[0].contains("Establishing blockchain connection"));
+    }
+
+    #[test]
+    fn thresholds_are_applied() {
+        let thresholds = HealthThresholds {
+            min_connected_peers: 5,
+            min_funded_channels: 3,
+            require_blokli: true,
+        };
+        let readiness = healthy().readiness(&thresholds);
+        assert!(!readiness.ready);
+        assert_eq!(readiness.unmet.len(), 2, "{readiness:?}");
+    }
+
+    #[test]
+    fn blokli_requirement_can_be_waived() {
+        let report = HealthReport {
+            blokli_reachable: Some(false),
+            ..healthy()
+        };
+        assert!(!report.readiness(&HealthThresholds::default()).ready);
+        let thresholds = HealthThresholds {
+            require_blokli: false,
+            ..Default::default()
+        };
+        assert!(report.readiness(&thresholds).ready);
+    }
+
+    #[test]
+    fn unknown_values_are_not_ready() {
+        let report = HealthReport {
+            connected_peers: None,
+            ..healthy()
+        };
+        let readiness = report.readiness(&HealthThresholds::default());
+        assert!(!readiness.ready);
+        assert!(readiness.unmet[0].contains("unknown"));
+    }
+
+    #[tokio::test]
+    async fn monitor_without_a_node_reports_the_init_stage() {
+        let monitor = HealthMonitor::default();
+        assert_eq!(monitor.report().await.init_state, None);
+
+        monitor.record(EdgliInitState::CreatingNode);
+        let report = monitor.report().await;
+        assert_eq!(report.init_state, Some(EdgliInitState::CreatingNode));
+        assert_eq!(report.node_state, None);
+        assert!(!report.readiness(monitor.thresholds()).ready);
+    }
+}
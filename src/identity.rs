@@ -0,0 +1,227 @@
+//! Identity file management for the `edgli identity` subcommands.
+//!
+//! An identity file is an encrypted Ethereum keystore holding both the chain keypair
+//! (on-chain address) and the packet keypair (peer ID). Reading goes through
+//! [`IdentityRetrievalModes::FromFile`], the same path the node uses at startup, so a file
+//! accepted here is guaranteed to start a node.
+//!
+//! No function in this module returns or logs private key material; callers only ever see
+//! the public identifiers in [`IdentitySummary`].
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use hopr_lib::{
+    HoprKeys, IdentityRetrievalModes, api::types::primitive::prelude::Address, builder::Keypair,
+};
+
+use crate::errors::{EdgliError, Result};
+
+/// Public identifiers of an identity file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IdentitySummary {
+    /// On-chain address derived from the chain key; fund this address with xDAI.
+    pub chain_address: Address,
+    /// libp2p peer ID derived from the packet key.
+    pub peer_id: String,
+}
+
+impl IdentitySummary {
+    fn from_keys(keys: &HoprKeys) -> Self {
+        Self {
+            chain_address: keys.chain_key.public().to_address(),
+            peer_id: keys.packet_key.public().to_peerid_str(),
+        }
+    }
+
+    /// Renders the public identifiers as a YAML mapping, suitable for sharing the identity
+    /// with tooling that funds or allowlists the node.
+    pub fn to_yaml(&self) -> Result<String> {
+        let mut mapping = serde_yaml::Mapping::new();
+        mapping.insert(
+            "chain_address".into(),
+            self.chain_address.to_string().into(),
+        );
+        mapping.insert("peer_id".into(), self.peer_id.clone().into());
+        serde_yaml::to_string(&mapping).map_err(|e| EdgliError::SerializationError(e.to_string()))
+    }
+}
+
+impl fmt::Display for IdentitySummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "chain address: {}", self.chain_address)?;
+        write!(f, "peer id:       {}", self.peer_id)
+    }
+}
+
+fn path_str(path: &Path) -> Result<&str> {
+    path.to_str().ok_or_else(|| {
+        EdgliError::FileError(format!(
+            "identity file path '{}' is not valid UTF-8",
+            path.display()
+        ))
+    })
+}
+
+/// Reads and decrypts an existing identity file.
+///
+/// Fails if the file does not exist: [`IdentityRetrievalModes::FromFile`] would otherwise
+/// silently generate a fresh identity in its place.
+pub fn read_identity(path: &Path, password: &str) -> Result<HoprKeys> {
+    if !path.exists() {
+        return Err(EdgliError::FileError(format!(
+            "the identity file '{}' does not exist",
+            path.display()
+        )));
+    }
+
+    let keys: HoprKeys = IdentityRetrievalModes::FromFile {
+        password,
+        id_path: path_str(path)?,
+    }
+    .try_into()
+    .map_err(|e| EdgliError::IdentityError(format!("{e}")))?;
+    Ok(keys)
+}
+
+/// Generates a fresh chain and packet keypair and writes them to a new identity file
+/// encrypted with `password`.
+///
+/// Refuses to overwrite an existing file.
+pub fn create_identity(path: &Path, password: &str) -> Result<IdentitySummary> {
+    if password.is_empty() {
+        return Err(EdgliError::ValidationError(
+            "the identity password must not be empty".into(),
+        ));
+    }
+    if path.exists() {
+        return Err(EdgliError::FileError(format!(
+            "the identity file '{}' already exists",
+            path.display()
+        )));
+    }
+
+    let keys = HoprKeys::random();
+    keys.write_eth_keystore(path_str(path)?, password)
+        .map_err(|e| EdgliError::IdentityError(e.to_string()))?;
+
+    Ok(IdentitySummary::from_keys(&keys))
+}
+
+/// Decrypts an existing identity file and returns its public identifiers.
+pub fn show_identity(path: &Path, password: &str) -> Result<IdentitySummary> {
+    read_identity(path, password).map(|keys| IdentitySummary::from_keys(&keys))
+}
+
+/// Re-encrypts an existing identity file under `new_password`.
+///
+/// The keys are written to a sibling temporary file and read back with the new password
+/// before it replaces the original, so a failure at any step leaves the original file
+/// untouched and readable with the old password.
+pub fn change_identity_password(
+    path: &Path,
+    password: &str,
+    new_password: &str,
+) -> Result<IdentitySummary> {
+    if new_password.is_empty() {
+        return Err(EdgliError::ValidationError(
+            "the new identity password must not be empty".into(),
+        ));
+    }
+
+    let keys = read_identity(path, password)?;
+    let summary = IdentitySummary::from_keys(&keys);
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path: PathBuf = path.with_file_name(tmp_name);
+
+    let rotated = keys
+        .write_eth_keystore(path_str(&tmp_path)?, new_password)
+        .map_err(|e| EdgliError::IdentityError(e.to_string()))
+        .and_then(|_| show_identity(&tmp_path, new_password))
+        .and_then(|reread| {
+            if reread == summary {
+                Ok(())
+            } else {
+                Err(EdgliError::IdentityError(
+                    "re-encrypted identity does not match the original".into(),
+                ))
+            }
+        })
+        .and_then(|_| {
+            std::fs::rename(&tmp_path, path).map_err(|e| {
+                EdgliError::FileError(format!("failed to replace '{}': {e}", path.display()))
+            })
+        });
+
+    if rotated.is_err() {
+        let _ = std::fs::remove_file(&tmp_path);
+    }
+    rotated.map(|_| summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("edgli-identity-{}-{name}.id", std::process::id()))
+    }
+
+    #[test]
+    fn read_identity_rejects_missing_file() {
+        let path = scratch_path("missing");
+        let err = read_identity(&path, "secret").unwrap_err();
+        assert!(matches!(err, EdgliError::FileError(_)), "got {err}");
+        // Reading must never fall back to generating a new identity.
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn create_identity_rejects_empty_password() {
+        let path = scratch_path("empty-password");
+        let err = create_identity(&path, "").unwrap_err();
+        assert!(matches!(err, EdgliError::ValidationError(_)), "got {err}");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn create_identity_refuses_to_overwrite() {
+        let path = scratch_path("existing");
+        std::fs::write(&path, b"not a keystore").unwrap();
+        let err = create_identity(&path, "secret").unwrap_err();
+        assert!(matches!(err, EdgliError::FileError(_)), "got {err}");
+        assert_eq!(std::fs::read(&path).unwrap(), b"not a keystore");
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn create_show_and_change_password_round_trip() {
+        let path = scratch_path("round-trip");
+        let created = create_identity(&path, "old").unwrap();
+        assert_eq!(show_identity(&path, "old").unwrap(), created);
+
+        let rotated = change_identity_password(&path, "old", "new").unwrap();
+        assert_eq!(rotated, created);
+        assert_eq!(show_identity(&path, "new").unwrap(), created);
+        assert!(show_identity(&path, "old").is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn summary_yaml_contains_only_public_identifiers() {
+        let summary = IdentitySummary {
+            chain_address: Address::default(),
+            peer_id: "12D3KooWExample".into(),
+        };
+        let yaml = summary.to_yaml().unwrap();
+        let parsed: serde_yaml::Mapping = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(
+            parsed.get("peer_id").and_then(|v| v.as_str()),
+            Some("12D3KooWExample")
+        );
+    }
+}
@@ -2,15 +2,67 @@
 // `blokli` is currently the only supported connector.
 #[cfg(all(feature = "runtime-tokio", feature = "blokli"))]
 pub mod client;
+
+#[cfg(all(feature = "runtime-tokio", feature = "blokli"))]
+pub mod builder;
+
+pub mod config;
 pub mod errors;
+pub mod events;
+pub mod identity;
+pub mod metrics;
+
+#[cfg(all(feature = "runtime-tokio", feature = "blokli"))]
+pub mod monitor;
+
+// Local admin HTTP API for the `edgli` binary; implies `runtime-tokio` and `blokli`.
+#[cfg(feature = "api")]
+pub mod api;
 
 #[cfg(feature = "blokli")]
 pub mod blokli;
 
+#[cfg(feature = "blokli")]
+pub mod cache;
+
 #[cfg(feature = "blokli")]
 pub mod endpoint;
 
+#[cfg(feature = "blokli")]
+pub mod gas;
+
+#[cfg(all(feature = "runtime-tokio", feature = "blokli"))]
+pub mod health;
+
+#[cfg(feature = "blokli")]
+pub mod onboarding;
+
+// Health probe server for the `edgli` binary; implies `runtime-tokio` and `blokli`.
+#[cfg(feature = "probes")]
+pub mod probes;
+
+#[cfg(feature = "blokli")]
+pub mod proxy;
+
+pub mod reload;
+
+#[cfg(feature = "blokli")]
+pub mod retry;
+
+#[cfg(all(feature = "runtime-tokio", feature = "blokli"))]
+pub mod shutdown;
+
+pub mod session;
 pub mod strategy;
+pub mod sweep;
+
+// Scriptable `EdgeNodeApi` and `IncentiveOperations` mocks for consumers' tests.
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "blokli")]
+pub mod tls;
+
 pub mod traits;
 
 pub use hopr_lib;
@@ -18,13 +70,25 @@ pub use hopr_lib;
 #[cfg(feature = "blokli")]
 pub use blokli::*;
 #[cfg(feature = "blokli")]
+pub use cache::{CacheTtls, CachedIncentiveOperations};
+#[cfg(feature = "blokli")]
 pub use endpoint::*;
 #[cfg(feature = "blokli")]
+pub use gas::{GasCap, GasCappedIncentiveOperations};
+#[cfg(feature = "blokli")]
 pub use hopr_chain_connector::{BlockchainConnectorConfig, DEFAULT_REQUEST_TIMEOUT};
 pub use hopr_lib::exports::transport::path::PathPlannerConfig;
+#[cfg(feature = "blokli")]
+pub use proxy::{BlokliProxy, ParseBlokliProxyError, ProxyProtocol};
+#[cfg(feature = "blokli")]
+pub use retry::{ErrorClass, RetryPolicy, classify};
+#[cfg(feature = "blokli")]
+pub use tls::{CaBundle, ParseSpkiPinError, SpkiPin};
 // Re-exported so consumers can set per-session flow control (the `flow_control`
 // field of `HoprSessionClientConfig`) without reaching into `hopr_lib` internals.
 pub use hopr_lib::exports::transport::FlowControlConfig;
+// `EdgeNodeApi::open_session` takes these.
+pub use hopr_lib::exports::transport::{HoprSessionClientConfig, SessionTarget};
 // Re-exported so consumers constructing a `BlokliEndpoint` do not need their own
 // `url` dependency, which would have to match this crate's version to unify.
 #[cfg(feature = "blokli")]
@@ -54,21 +118,40 @@ pub fn latency_path_planner_config(min_ack_rate: f64) -> PathPlannerConfig {
     }
 }
 
+#[cfg(all(feature = "runtime-tokio", feature = "blokli"))]
+pub use builder::{
+    CoverTrafficPolicy, DEFAULT_EVENT_POLL_INTERVAL, DEFAULT_MIX_PROTOCOL, EdgliBuilder,
+    TicketFactorySource,
+};
 #[cfg(all(feature = "runtime-tokio", feature = "blokli"))]
 pub use client::*;
-pub use traits::{EdgeNodeApi, NodeBalances};
+pub use events::{EdgeEvent, EdgeEventChannel};
+#[cfg(all(feature = "runtime-tokio", feature = "blokli"))]
+pub use monitor::{
+    BalanceAlert, BalanceEvent, BalanceMonitor, BalanceMonitorConfig, BalanceSnapshot,
+    BalanceThresholds,
+};
+pub use session::{EdgeSession, EdgeSessionId, SessionInfo, SessionRegistry};
+pub use traits::{EdgeNodeApi, NodeBalances, TransactionHandle};
 
 // Re-export types that appear in EdgeNodeApi method signatures so consumers
 // do not need to dig into hopr_lib internal module paths.
-pub use hopr_lib::api::types::{
-    internal::channels::ChannelEntry,
-    primitive::prelude::{Balance, XDai},
+pub use hopr_lib::api::{
+    chain::ChainReceipt,
+    types::{
+        internal::channels::ChannelEntry,
+        primitive::prelude::{Balance, XDai},
+    },
 };
 
-pub use strategy::{BalanceRecommendation, Capacity, CapacityAllocations, StartupCosts};
+pub use strategy::{
+    BalanceRecommendation, Capacity, CapacityAllocations, FundingThresholds, StartupCosts,
+    XdaiBudget, XdaiBudgetConfig, compute_funding_thresholds, project_xdai_budget,
+};
 
 #[cfg(feature = "blokli")]
-pub use strategy::minimum_balance_recommendation;
+pub use strategy::{minimum_balance_recommendation, minimum_xdai_budget};
+pub use sweep::{SweepConfig, SweepOutcome};
 
 #[cfg(feature = "telemetry")]
 pub use hopr_lib::collect_hopr_metrics;
@@ -2,9 +2,10 @@ use std::path::PathBuf;
 use std::str::FromStr;
 
 use async_signal::{Signal, Signals};
-use clap::Parser;
-use futures::StreamExt;
-use hopr_lib::{HoprKeys, IdentityRetrievalModes, config::HoprLibConfig};
+use clap::{Args, Parser, Subcommand};
+use futures::{StreamExt, future::AbortHandle};
+use hopr_lib::builder::Keypair;
+use hopr_lib::config::{HoprLibConfig, HostConfig, HostType};
 use signal_hook::low_level;
 use tracing::{info, warn};
 use tracing_subscriber::prelude::*;
@@ -16,7 +17,23 @@ use {
     opentelemetry_sdk::trace::{RandomIdGenerator, Sampler},
 };
 
-use edgli::{BlokliDnsOverride, BlokliEndpoint, errors::EdgliError};
+#[cfg(feature = "probes")]
+use edgli::health::{HealthMonitor, HealthThresholds};
+use edgli::{
+    BalanceMonitor, BalanceMonitorConfig, BlokliDnsOverride, BlokliEndpointSet, BlokliProxy,
+    CaBundle, GasCap, RetryPolicy, SpkiPin,
+    config::{self, NodeConfig},
+    errors::EdgliError,
+    identity,
+    onboarding::{self, OnboardingEvent},
+    reload::ReloadPlan,
+    strategy::{ReactorBackoff, default_strategy_cfg},
+};
+
+/// The tracer provider installed by [`init_logger`], flushed when the node shuts down.
+#[cfg(feature = "telemetry")]
+static TRACER_PROVIDER: std::sync::OnceLock<opentelemetry_sdk::trace::SdkTracerProvider> =
+    std::sync::OnceLock::new();
 
 // Avoid musl's default allocator due to degraded performance
 // https://nickb.dev/blog/default-musl-allocator-considered-harmful-to-performance
@@ -27,9 +44,188 @@ static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 /// Takes all CLI arguments whose structure is known at compile-time.
 /// Arguments whose structure, e.g. their default values depend on
 /// file contents need be specified using `clap`s builder API
+///
+/// Without a subcommand the binary runs the node, as it always has.
 #[derive(Clone, Parser)]
-#[command(author, version, about, long_about = None)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 pub struct CliArgs {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+#[derive(Clone, Subcommand)]
+pub enum Command {
+    /// Create, inspect and re-encrypt identity files
+    #[command(subcommand)]
+    Identity(IdentityCommand),
+
+    /// Scaffold node configuration files
+    #[command(subcommand)]
+    Config(ConfigCommand),
+
+    /// Fund the node, deploy its Safe and record the Safe in the configuration file
+    Onboard(OnboardArgs),
+}
+
+#[derive(Clone, Args)]
+pub struct OnboardArgs {
+    #[command(flatten)]
+    pub identity: IdentityArgs,
+
+    /// HOPR configuration file path
+    #[arg(
+        long,
+        env = "HOPR_EDGE_CONFIG_FILE_PATH",
+        help = "The configuration file to record the Safe in; created with edge defaults if missing",
+        required = true
+    )]
+    pub config: PathBuf,
+
+    /// Update an existing configuration file
+    #[arg(
+        long,
+        help = "Record the Safe in an existing configuration file, keeping the original as <config>.bak"
+    )]
+    pub force: bool,
+
+    #[command(flatten)]
+    pub blokli: BlokliArgs,
+
+    /// Funding poll interval
+    #[arg(
+        long,
+        help = "Seconds between balance checks while waiting for funds",
+        default_value_t = 10
+    )]
+    pub poll_interval_secs: u64,
+
+    /// Funding timeout
+    #[arg(
+        long,
+        help = "Give up waiting for funds after this many seconds (default: wait indefinitely)",
+        required = false
+    )]
+    pub funding_timeout_secs: Option<u64>,
+}
+
+#[derive(Clone, Subcommand)]
+pub enum ConfigCommand {
+    /// Write a commented, edge-oriented configuration file
+    Init(ConfigInitArgs),
+}
+
+#[derive(Clone, Args)]
+pub struct ConfigInitArgs {
+    /// HOPR configuration file path
+    #[arg(
+        long,
+        env = "HOPR_EDGE_CONFIG_FILE_PATH",
+        help = "The path to write the configuration file to",
+        required = true
+    )]
+    pub config: PathBuf,
+
+    /// Overwrite an existing configuration file
+    #[arg(long, help = "Replace the configuration file if it already exists")]
+    pub force: bool,
+
+    /// Announced host address
+    #[arg(
+        long,
+        help = "IPv4 address or domain name the node listens on and announces (default: the hopr-lib default)",
+        required = false
+    )]
+    pub host: Option<String>,
+
+    /// Announced port
+    #[arg(
+        long,
+        help = "Port the node listens on and announces (default: the hopr-lib default)",
+        required = false
+    )]
+    pub port: Option<u16>,
+
+    /// Prefill the Safe and module addresses from chain
+    #[arg(
+        long,
+        help = "Look up the Safe and module deployed for the identity and prefill their addresses",
+        requires_all = ["identity_file_path", "identity_password"]
+    )]
+    pub discover_safe: bool,
+
+    /// Identity file path, only needed with --discover-safe
+    #[arg(
+        long,
+        env = "HOPR_EDGE_IDENTITY_FILE_PATH",
+        help = "The path to the identity file whose Safe to discover",
+        required = false
+    )]
+    pub identity_file_path: Option<PathBuf>,
+
+    /// Identity file password, only needed with --discover-safe
+    #[arg(
+        long,
+        env = "HOPR_EDGE_IDENTITY_FILE_PASSWORD",
+        help = "Password for the identity file provided",
+        required = false
+    )]
+    pub identity_password: Option<String>,
+
+    #[command(flatten)]
+    pub blokli: BlokliArgs,
+}
+
+#[derive(Clone, Subcommand)]
+pub enum IdentityCommand {
+    /// Generate a fresh chain and packet keypair into a new identity file
+    Create(IdentityArgs),
+
+    /// Print the on-chain address and peer ID of an identity file
+    Show(IdentityArgs),
+
+    /// Re-encrypt an identity file under a new password
+    ChangePassword {
+        #[command(flatten)]
+        identity: IdentityArgs,
+
+        /// New identity file password
+        #[arg(
+            long,
+            env = "HOPR_EDGE_IDENTITY_FILE_NEW_PASSWORD",
+            help = "New password to re-encrypt the identity file with",
+            required = true
+        )]
+        new_password: String,
+    },
+
+    /// Write the public identifiers of an identity file as YAML
+    ExportPublic {
+        #[command(flatten)]
+        identity: IdentityArgs,
+
+        /// Output file path
+        #[arg(
+            long,
+            help = "Write the public identifiers to this file instead of stdout",
+            required = false
+        )]
+        output: Option<PathBuf>,
+    },
+}
+
+/// Identity file location and password, shared by the node and the `identity` subcommands.
+#[derive(Clone, Args)]
+pub struct IdentityArgs {
     /// Identity file password
     #[arg(
         long,
@@ -47,35 +243,135 @@ pub struct CliArgs {
         required = true
     )]
     pub identity_file_path: PathBuf,
+}
 
-    /// HOPR configuration file path
-    #[arg(
-        long,
-        env = "HOPR_EDGE_CONFIG_FILE_PATH",
-        help = "The path to the configuration path for the HOPR client",
-        required = true
-    )]
-    pub config: PathBuf,
-
-    /// Blokli URL
+/// Blokli endpoint selection, shared by every command that talks to the chain.
+#[derive(Clone, Args)]
+pub struct BlokliArgs {
+    /// Blokli URLs
     #[arg(
         long,
         env = "HOPR_EDGE_BLOKLI_URL",
-        help = "The URL of the blokli provider to use",
+        value_delimiter = ',',
+        help = "The URL of a blokli provider to use; repeat it (or separate with commas) to fail over between providers in the given order (the node picks one at startup, on-boarding per query)",
         required = false
     )]
-    pub blokli_url: Option<String>,
+    pub blokli_url: Vec<String>,
 
     /// Blokli DNS override
     #[arg(
         long,
         env = "HOPR_EDGE_BLOKLI_DNS_OVERRIDE",
         value_parser = BlokliDnsOverride::from_str,
-        help = "The DNS override for the blokli provider; IPv6 addresses with a port must use brackets, for example [::1]:3002",
+        help = "The DNS override for the first blokli provider: one or more comma-separated addresses, raced for the fastest; IPv6 addresses with a port must use brackets, for example [::1]:3002",
         required = false
     )]
     pub blokli_dns_override: Option<BlokliDnsOverride>,
 
+    /// Blokli proxy
+    #[arg(
+        long,
+        env = "HOPR_EDGE_BLOKLI_PROXY",
+        value_parser = BlokliProxy::from_str,
+        help = "Route all blokli traffic through this proxy: socks5h://, socks5:// or http:// with optional user:password@, for example socks5h://127.0.0.1:9050",
+        required = false
+    )]
+    pub blokli_proxy: Option<BlokliProxy>,
+
+    /// Blokli SPKI pins
+    #[arg(
+        long,
+        env = "HOPR_EDGE_BLOKLI_SPKI_PIN",
+        value_delimiter = ',',
+        value_parser = SpkiPin::from_str,
+        help = "Require the blokli server certificate to carry one of these keys, as sha256/<BASE64> of the SubjectPublicKeyInfo; intermediate and root keys are not matched; repeat it (or separate with commas), listing keys for every provider",
+        required = false
+    )]
+    pub blokli_spki_pin: Vec<SpkiPin>,
+
+    /// Blokli CA bundle
+    #[arg(
+        long,
+        env = "HOPR_EDGE_BLOKLI_CA_BUNDLE",
+        help = "A PEM file of root certificates to validate blokli against instead of the system roots",
+        required = false
+    )]
+    pub blokli_ca_bundle: Option<PathBuf>,
+
+    /// Blokli request attempts
+    #[arg(
+        long,
+        env = "HOPR_EDGE_BLOKLI_MAX_ATTEMPTS",
+        default_value_t = RetryPolicy::default().max_attempts,
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "How many times a blokli read that failed transiently (connection, timeout, 5xx or 429) is attempted in total, with exponential backoff in between; 1 disables retrying"
+    )]
+    pub blokli_max_attempts: u32,
+
+    /// Gas price cap
+    #[arg(
+        long,
+        env = "HOPR_EDGE_MAX_FEE_PER_GAS_CAP",
+        help = "Refuse to submit transactions while the chain's max fee per gas is above this many wei, ",
+        required = false
+    )]
+    pub max_fee_per_gas_cap: Option<u128>,
+}
+
+impl BlokliArgs {
+    /// The configured providers; the DNS override pins the first provider's host only, the
+    /// proxy and TLS settings apply to all of them.
+    fn endpoints(&self) -> Result<BlokliEndpointSet, EdgliError> {
+        let mut endpoints = BlokliEndpointSet::from_urls(&self.blokli_url)?
+            .endpoints()
+            .to_vec();
+        if let Some(dns_override) = self.blokli_dns_override.clone() {
+            endpoints[0] = endpoints[0].clone().with_dns_override(dns_override);
+        }
+        let ca_bundle = self
+            .blokli_ca_bundle
+            .as_ref()
+            .map(CaBundle::from_pem_file)
+            .transpose()?;
+        for endpoint in &mut endpoints {
+            endpoint.proxy = self.blokli_proxy.clone();
+            endpoint.spki_pins = self.blokli_spki_pin.clone();
+            endpoint.ca_bundle = ca_bundle.clone();
+        }
+        Ok(BlokliEndpointSet::new(endpoints)?.with_retry(RetryPolicy {
+            max_attempts: self.blokli_max_attempts,
+            ..Default::default()
+        }))
+    }
+
+    /// The gas price cap.
+    fn gas_cap(&self) -> GasCap {
+        self.max_fee_per_gas_cap
+            .map(GasCap::new)
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub identity: IdentityArgs,
+
+    /// HOPR configuration file path
+    #[arg(
+        long,
+        env = "HOPR_EDGE_CONFIG_FILE_PATH",
+        help = "The path to the configuration path for the HOPR client",
+        required = true
+    )]
+    pub config: PathBuf,
+
+    #[command(flatten)]
+    pub blokli: BlokliArgs,
+
+    #[command(flatten)]
+    pub monitor: MonitorArgs,
+
     /// Probe non-public (private, loopback, link-local) peer addresses from announcements
     #[arg(
         long,
@@ -84,12 +380,163 @@ pub struct CliArgs {
         default_value_t = false
     )]
     pub probe_local_addresses: bool,
+
+    /// Shutdown grace period
+    #[arg(
+        long,
+        env = "HOPR_EDGE_SHUTDOWN_TIMEOUT_SECS",
+        help = "Seconds to wait for leased sessions to close on SIGINT or SIGTERM",
+        default_value_t = 30
+    )]
+    pub shutdown_timeout_secs: u64,
+
+    #[cfg(feature = "api")]
+    #[command(flatten)]
+    pub api: ApiArgs,
+
+    #[cfg(feature = "probes")]
+    #[command(flatten)]
+    pub health: HealthArgs,
+}
+
+/// Balance monitor settings; the monitor runs alongside the strategy reactor.
+#[derive(Clone, Args)]
+pub struct MonitorArgs {
+    /// Low xDAI threshold in transactions
+    #[arg(
+        long,
+        env = "HOPR_EDGE_MIN_XDAI_TRANSACTIONS",
+        help = "Alert when the node's xDAI covers fewer transactions than this at the current gas price",
+        value_parser = clap::value_parser!(u64).range(1..),
+        default_value_t = BalanceMonitorConfig::default().min_xdai_transactions
+    )]
+    pub min_xdai_transactions: u64,
+
+    /// Pause the reactor on low xDAI
+    #[arg(
+        long,
+        env = "HOPR_EDGE_PAUSE_REACTOR_ON_LOW_XDAI",
+        help = "Stop opening and funding channels while the node's xDAI is low; resumes once it recovers",
+        default_value_t = false
+    )]
+    pub pause_reactor_on_low_xdai: bool,
+
+    /// Balance alert webhook
+    #[cfg(feature = "webhook")]
+    #[arg(
+        long,
+        env = "HOPR_EDGE_BALANCE_WEBHOOK",
+        help = "POST balance alerts as JSON to this URL",
+        required = false
+    )]
+    pub balance_webhook: Option<edgli::Url>,
 }
 
-fn init_logger() -> anyhow::Result<()> {
-    let env_filter = match tracing_subscriber::EnvFilter::try_from_default_env() {
-        Ok(filter) => filter,
-        Err(_) => tracing_subscriber::filter::EnvFilter::new("info")
+impl MonitorArgs {
+    fn config(&self) -> BalanceMonitorConfig {
+        BalanceMonitorConfig {
+            min_xdai_transactions: self.min_xdai_transactions,
+            pause_reactor_on_low_xdai: self.pause_reactor_on_low_xdai,
+            #[cfg(feature = "webhook")]
+            webhook: self.balance_webhook.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Admin HTTP API settings; the API is only served when `--api-listen` is given.
+#[cfg(feature = "api")]
+#[derive(Clone, Args)]
+pub struct ApiArgs {
+    /// Admin API listen address
+    #[arg(
+        long,
+        env = "HOPR_EDGE_API_LISTEN",
+        value_parser = edgli::api::ApiListen::from_str,
+        help = "Serve the admin API on a loopback address (127.0.0.1:3001) or a Unix socket (unix:/run/edgli.sock)",
+        requires = "api_token",
+        required = false
+    )]
+    pub api_listen: Option<edgli::api::ApiListen>,
+
+    /// Admin API bearer token
+    #[arg(
+        long,
+        env = "HOPR_EDGE_API_TOKEN",
+        hide_env_values = true,
+        help = "The bearer token admin API requests must present",
+        required = false
+    )]
+    pub api_token: Option<String>,
+}
+
+/// Health probe settings; the probes are only served when `--health-listen` is given.
+#[cfg(feature = "probes")]
+#[derive(Clone, Args)]
+pub struct HealthArgs {
+    /// Health probe listen address
+    #[arg(
+        long,
+        env = "HOPR_EDGE_HEALTH_LISTEN",
+        help = "Serve the unauthenticated /healthz and /readyz probes on this address, e.g. 0.0.0.0:8080",
+        required = false
+    )]
+    pub health_listen: Option<std::net::SocketAddr>,
+
+    /// Readiness: minimum connected peers
+    #[arg(
+        long,
+        env = "HOPR_EDGE_READY_MIN_PEERS",
+        help = "Connected peers required before /readyz reports ready",
+        default_value_t = HealthThresholds::default().min_connected_peers
+    )]
+    pub ready_min_peers: usize,
+
+    /// Readiness: minimum funded channels
+    #[arg(
+        long,
+        env = "HOPR_EDGE_READY_MIN_FUNDED_CHANNELS",
+        help = "Open outgoing channels with a non-zero balance required before /readyz reports ready",
+        default_value_t = HealthThresholds::default().min_funded_channels
+    )]
+    pub ready_min_funded_channels: usize,
+
+    /// Readiness: ignore Blokli
+    #[arg(
+        long,
+        env = "HOPR_EDGE_READY_IGNORE_BLOKLI",
+        help = "Report ready even while Blokli is unreachable",
+        default_value_t = false
+    )]
+    pub ready_ignore_blokli: bool,
+}
+
+#[cfg(feature = "probes")]
+impl HealthArgs {
+    fn thresholds(&self) -> HealthThresholds {
+        HealthThresholds {
+            min_connected_peers: self.ready_min_peers,
+            min_funded_channels: self.ready_min_funded_channels,
+            require_blokli: !self.ready_ignore_blokli,
+        }
+    }
+}
+
+/// Handle through which the log filter is swapped on SIGHUP.
+type LogFilterHandle =
+    tracing_subscriber::reload::Handle<tracing_subscriber::EnvFilter, tracing_subscriber::Registry>;
+
+/// Builds the log filter: `RUST_LOG` when set, else the configured `edge.log_filter`, else the
+/// built-in defaults.
+fn log_filter(configured: Option<&str>) -> anyhow::Result<tracing_subscriber::EnvFilter> {
+    let env_filter = match (
+        tracing_subscriber::EnvFilter::try_from_default_env(),
+        configured,
+    ) {
+        (Ok(filter), _) => filter,
+        (Err(_), Some(directives)) => tracing_subscriber::EnvFilter::try_new(directives)
+            .map_err(|e| EdgliError::ConfigError(format!("invalid log filter: {e}")))?,
+        (Err(_), None) => tracing_subscriber::filter::EnvFilter::new("info")
             .add_directive("libp2p_swarm=info".parse()?)
             .add_directive("libp2p_mplex=info".parse()?)
             .add_directive("libp2p_tcp=info".parse()?)
@@ -98,13 +545,20 @@ fn init_logger() -> anyhow::Result<()> {
             .add_directive("isahc=error".parse()?),
     };
 
+    #[cfg(feature = "prof")]
+    let env_filter = env_filter
+        .add_directive("tokio=trace".parse()?)
+        .add_directive("runtime=trace".parse()?);
+
+    Ok(env_filter)
+}
+
+fn init_logger() -> anyhow::Result<LogFilterHandle> {
+    let (env_filter, log_filter_handle) = tracing_subscriber::reload::Layer::new(log_filter(None)?);
+
     #[cfg(feature = "prof")]
     let registry = tracing_subscriber::Registry::default()
-        .with(
-            env_filter
-                .add_directive("tokio=trace".parse()?)
-                .add_directive("runtime=trace".parse()?),
-        )
+        .with(env_filter)
         .with(console_subscriber::spawn());
 
     #[cfg(not(feature = "prof"))]
@@ -151,8 +605,9 @@ fn init_logger() -> anyhow::Result<()> {
                             )
                             .build(),
                     )
-                    .build()
-                    .tracer(env!("CARGO_PKG_NAME"));
+                    .build();
+                let _ = TRACER_PROVIDER.set(tracer.clone());
+                let tracer = tracer.tracer(env!("CARGO_PKG_NAME"));
 
                 tracing::subscriber::set_global_default(
                     registry.with(tracing_opentelemetry::layer().with_tracer(tracer))
@@ -166,12 +621,12 @@ fn init_logger() -> anyhow::Result<()> {
         }
     }
 
-    Ok(())
+    Ok(log_filter_handle)
 }
 
 #[cfg_attr(feature = "runtime-tokio", tokio::main)]
 async fn main() -> anyhow::Result<()> {
-    init_logger()?;
+    let log_filter_handle = init_logger()?;
 
     if cfg!(debug_assertions) {
         warn!("Executable was built using the DEBUG profile.");
@@ -180,74 +635,501 @@ async fn main() -> anyhow::Result<()> {
     }
 
     let args = <CliArgs as clap::Parser>::parse();
-    if !args.identity_file_path.exists() {
-        return Err(EdgliError::ConfigError(format!(
-            "The identity file '{}' does not exist",
-            args.identity_file_path.display()
-        ))
-        .into());
+    match args.command {
+        Some(Command::Identity(command)) => run_identity_command(command),
+        Some(Command::Config(ConfigCommand::Init(init))) => run_config_init(init).await,
+        Some(Command::Onboard(onboard)) => run_onboard(onboard).await,
+        None => run_node(args.run, log_filter_handle).await,
+    }
+}
+
+fn run_identity_command(command: IdentityCommand) -> anyhow::Result<()> {
+    match command {
+        IdentityCommand::Create(id) => {
+            let summary = identity::create_identity(&id.identity_file_path, &id.identity_password)?;
+            info!(path = %id.identity_file_path.display(), "Created identity file");
+            println!("{summary}");
+        }
+        IdentityCommand::Show(id) => {
+            println!(
+                "{}",
+                identity::show_identity(&id.identity_file_path, &id.identity_password)?
+            );
+        }
+        IdentityCommand::ChangePassword {
+            identity: id,
+            new_password,
+        } => {
+            identity::change_identity_password(
+                &id.identity_file_path,
+                &id.identity_password,
+                &new_password,
+            )?;
+            info!(path = %id.identity_file_path.display(), "Re-encrypted identity file");
+        }
+        IdentityCommand::ExportPublic {
+            identity: id,
+            output,
+        } => {
+            let yaml = identity::show_identity(&id.identity_file_path, &id.identity_password)?
+                .to_yaml()?;
+            match output {
+                Some(path) => std::fs::write(&path, yaml).map_err(|e| {
+                    EdgliError::FileError(format!("failed to write '{}': {e}", path.display()))
+                })?,
+                None => print!("{yaml}"),
+            }
+        }
     }
 
-    if !args.config.exists() {
-        return Err(EdgliError::ConfigError(format!(
-            "The configuration file '{}' does not exist",
-            args.config.display()
-        ))
-        .into());
+    Ok(())
+}
+
+async fn run_config_init(args: ConfigInitArgs) -> anyhow::Result<()> {
+    let mut host = HostConfig::default();
+    if let Some(address) = args.host {
+        host.address = if address.parse::<std::net::Ipv4Addr>().is_ok() {
+            HostType::IPv4(address)
+        } else {
+            HostType::Domain(address)
+        };
+    }
+    if let Some(port) = args.port {
+        host.port = port;
+    }
+
+    let mut cfg = config::edge_config(host);
+
+    if args.discover_safe {
+        let (Some(id_path), Some(password)) = (args.identity_file_path, args.identity_password)
+        else {
+            return Err(EdgliError::ConfigError(
+                "--discover-safe needs --identity-file-path and --identity-password".into(),
+            )
+            .into());
+        };
+        let hopr_keys = identity::read_identity(&id_path, &password)?;
+        let incentive_ops =
+            edgli::make_incentive_operations(args.blokli.endpoints()?, &hopr_keys.chain_key, None)
+                .await?;
+        match incentive_ops.retrieve_safe().await? {
+            Some(safe) => {
+                info!(
+                    safe_address = %safe.safe_address,
+                    module_address = %safe.module_address,
+                    "Discovered Safe deployment"
+                );
+                cfg.hopr.safe_module.safe_address = safe.safe_address;
+                cfg.hopr.safe_module.module_address = safe.module_address;
+            }
+            None => warn!("No Safe is deployed for this identity yet; leaving placeholders"),
+        }
+    }
+
+    config::write_config(&args.config, &config::render_config(&cfg)?, args.force)?;
+    info!(path = %args.config.display(), "Wrote configuration file");
+    if config::has_placeholder_safe_module(&cfg.hopr) {
+        warn!(
+            "The Safe and module addresses are placeholders; fill them in before running the node"
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_onboard(args: OnboardArgs) -> anyhow::Result<()> {
+    let hopr_keys = identity::read_identity(
+        &args.identity.identity_file_path,
+        &args.identity.identity_password,
+    )?;
+    // Checked and read up front so neither a file that may not be touched nor a broken one is
+    // discovered after transactions were sent.
+    let existing = if args.config.exists() {
+        if !args.force {
+            anyhow::bail!(
+                "the configuration file '{}' already exists; pass --force to record the Safe in it",
+                args.config.display()
+            );
+        }
+        Some(std::fs::read_to_string(&args.config)?)
+    } else {
+        None
+    };
+    let cfg = match &existing {
+        Some(contents) => {
+            let cfg = config::parse_config(contents)?;
+            // Its safe_module is rewritten line by line once the Safe exists; refuse a layout
+            // that cannot be before sending anything.
+            config::with_safe_module(
+                contents,
+                cfg.hopr.safe_module.safe_address,
+                cfg.hopr.safe_module.module_address,
+            )?;
+            cfg
+        }
+        None => config::edge_config(HostConfig::default()),
+    };
+    // Size the funding for the reactor the node will run, when one is configured.
+    let incentives = cfg.edge.incentive_configuration()?;
+
+    let node_address = hopr_keys.chain_key.public().to_address();
+    // Each funding poll reads balances, ticket pricing and gas; the cache keeps the slower
+    // moving of those from being re-queried on every poll. The gas cap is checked beneath the
+    // cache, against the price the chain reports at submission.
+    let incentive_ops =
+        edgli::CachedIncentiveOperations::new(Box::new(edgli::GasCappedIncentiveOperations::new(
+            edgli::make_incentive_operations(args.blokli.endpoints()?, &hopr_keys.chain_key, None)
+                .await?,
+            args.blokli.gas_cap(),
+        )));
+
+    let options = onboarding::OnboardingOptions {
+        poll_interval: std::time::Duration::from_secs(args.poll_interval_secs),
+        funding_timeout: args
+            .funding_timeout_secs
+            .map(std::time::Duration::from_secs),
+    };
+    let safe = onboarding::onboard(
+        &incentive_ops,
+        node_address,
+        &incentives,
+        options,
+        |event| match event {
+            OnboardingEvent::NodeAddress(address) => {
+                info!(%address, "Fund this node address with xDAI and wxHOPR")
+            }
+            OnboardingEvent::AwaitingFunds {
+                node_xdai,
+                node_wxhopr,
+                required_xdai,
+                required_wxhopr,
+            } => info!(
+                %node_xdai,
+                %node_wxhopr,
+                %required_xdai,
+                %required_wxhopr,
+                "Waiting for funds"
+            ),
+            other => info!(?other, "On-boarding"),
+        },
+    )
+    .await?;
+
+    match existing {
+        Some(contents) => {
+            let updated =
+                config::with_safe_module(&contents, safe.safe_address, safe.module_address)?;
+            let backup = config::backup_config(&args.config)?;
+            info!(path = %backup.display(), "Kept the previous configuration file");
+            config::write_config(&args.config, &updated, true)?;
+        }
+        None => {
+            let mut cfg = cfg;
+            cfg.hopr.safe_module.safe_address = safe.safe_address;
+            cfg.hopr.safe_module.module_address = safe.module_address;
+            config::write_config(&args.config, &config::render_config(&cfg)?, false)?;
+        }
     }
+    info!(
+        path = %args.config.display(),
+        safe_address = %safe.safe_address,
+        module_address = %safe.module_address,
+        "Recorded the Safe in the configuration file"
+    );
 
-    let mut cfg: HoprLibConfig = serde_yaml::from_str(&std::fs::read_to_string(args.config)?)?;
-    // Edge-client specific mixer defaults (0 ms min, 1 ms range); env vars override.
-    let read_ms = |var: &str, default_ms: u64| -> std::time::Duration {
+    Ok(())
+}
+
+/// Applies the `HOPR_INTERNAL_MIXER_*` env vars, where set, over the configured mixer delays.
+///
+/// The delays [`config::parse_config`] settled on, the edge defaults unless
+/// `edge.custom_protocol` is set, are kept otherwise.
+fn apply_env_overrides(cfg: &mut HoprLibConfig) {
+    let read_ms = |var: &str| -> Option<std::time::Duration> {
         std::env::var(var)
             .ok()
             .and_then(|v| v.parse::<u64>().ok())
             .map(std::time::Duration::from_millis)
-            .unwrap_or_else(|| std::time::Duration::from_millis(default_ms))
     };
-    cfg.protocol.mixer.min_delay = read_ms("HOPR_INTERNAL_MIXER_MINIMUM_DELAY_IN_MS", 0);
-    cfg.protocol.mixer.delay_range = read_ms("HOPR_INTERNAL_MIXER_DELAY_RANGE_IN_MS", 1);
-    cfg.protocol.path_planner = edgli::latency_path_planner_config(0.1);
-
-    // Find or create an identity
-    let hopr_keys: HoprKeys = IdentityRetrievalModes::FromFile {
-        password: &args.identity_password,
-        id_path: &args.identity_file_path.display().to_string(),
+    if let Some(min_delay) = read_ms("HOPR_INTERNAL_MIXER_MINIMUM_DELAY_IN_MS") {
+        cfg.protocol.mixer.min_delay = min_delay;
+    }
+    if let Some(delay_range) = read_ms("HOPR_INTERNAL_MIXER_DELAY_RANGE_IN_MS") {
+        cfg.protocol.mixer.delay_range = delay_range;
+    }
+}
+
+/// The strategy reactor and the balance monitor watching the funds it spends.
+struct StrategyTasks {
+    reactor: AbortHandle,
+    monitor: BalanceMonitor,
+}
+
+impl StrategyTasks {
+    fn stop(self) {
+        self.reactor.abort();
+        self.monitor.stop();
+    }
+}
+
+/// Starts the supervised channel-lifecycle reactor for the configured `edge.strategy`, if
+/// any, along with a balance monitor for it. Without the section the node opens and funds no
+/// channels of its own.
+fn start_reactor(
+    edgli: &edgli::Edgli,
+    strategy: Option<&config::StrategySection>,
+    monitor: &MonitorArgs,
+) -> anyhow::Result<Option<StrategyTasks>> {
+    let Some(strategy) = strategy else {
+        info!("No edge.strategy section configured; the strategy reactor is not started");
+        return Ok(None);
+    };
+    let incentives = strategy.to_incentive_configuration()?;
+    info!(?incentives, "Starting the strategy reactor");
+    let reactor = edgli.run_supervised_reactor(incentives.clone(), ReactorBackoff::default())?;
+    let monitor = edgli.spawn_balance_monitor(incentives, monitor.config())?;
+    Ok(Some(StrategyTasks { reactor, monitor }))
+}
+
+/// How often the balance, capacity and gas price gauges are refreshed.
+#[cfg(feature = "telemetry")]
+const METRICS_SAMPLE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Periodically queries the values behind the sampled gauges; querying them records them.
+#[cfg(feature = "telemetry")]
+fn start_metrics_sampler(edgli: &edgli::Edgli) -> AbortHandle {
+    use edgli::EdgeNodeApi as _;
+
+    let edgli = edgli.clone();
+    let (sampler, handle) = futures::future::abortable(async move {
+        loop {
+            if let Err(error) = edgli.balances().await {
+                tracing::debug!(%error, "failed to sample balances");
+            }
+            if let Err(error) = edgli.describe_current_capacity_allocations().await {
+                tracing::debug!(%error, "failed to sample capacity allocations");
+            }
+            if let Err(error) = edgli.max_fee_per_gas().await {
+                tracing::debug!(%error, "failed to sample the gas price");
+            }
+            tokio::time::sleep(METRICS_SAMPLE_INTERVAL).await;
+        }
+    });
+    tokio::spawn(sampler);
+    handle
+}
+
+/// Serves the health probes when `--health-listen` is given. The monitor is returned either
+/// way; it only records while nothing serves it.
+#[cfg(feature = "probes")]
+async fn start_health(args: &HealthArgs) -> anyhow::Result<(HealthMonitor, Option<AbortHandle>)> {
+    let monitor = HealthMonitor::new(args.thresholds());
+    let Some(address) = args.health_listen else {
+        return Ok((monitor, None));
+    };
+
+    let listen = edgli::probes::ApiListen::Tcp(address);
+    let server =
+        edgli::probes::bind(&listen, edgli::probes::health_router(monitor.clone())).await?;
+    let (server, handle) = futures::future::abortable(server);
+    tokio::spawn(async move {
+        if let Ok(Err(error)) = server.await {
+            tracing::error!(%error, "The health probe server stopped");
+        }
+    });
+    info!(%listen, thresholds = ?monitor.thresholds(), "Serving the health probes");
+
+    Ok((monitor, Some(handle)))
+}
+
+/// Serves the admin API when `--api-listen` is given, sized for the configured strategy.
+#[cfg(feature = "api")]
+async fn start_api(
+    args: &ApiArgs,
+    edgli: &edgli::Edgli,
+    cfg: &NodeConfig,
+) -> anyhow::Result<Option<(edgli::api::AdminState, AbortHandle)>> {
+    let Some(listen) = &args.api_listen else {
+        return Ok(None);
+    };
+    let token = args.api_token.clone().unwrap_or_default();
+    let state = edgli::api::AdminState::new(
+        std::sync::Arc::new(edgli.clone()),
+        token,
+        cfg.edge.incentive_configuration()?,
+    )?;
+
+    let server = edgli::api::bind(listen, edgli::api::router(state.clone())).await?;
+    let (server, handle) = futures::future::abortable(server);
+    tokio::spawn(async move {
+        if let Ok(Err(error)) = server.await {
+            tracing::error!(%error, "The admin API stopped");
+        }
+    });
+    info!(%listen, "Serving the admin API");
+
+    Ok(Some((state, handle)))
+}
+
+/// Re-reads the configuration file and applies the settings that can change on a running node.
+///
+/// Nothing is applied unless the whole file loads and its live settings are valid. A changed
+/// strategy is started before the running one is stopped, so a strategy that fails to start
+/// leaves the running one, and the rest of the running configuration, in place. Settings
+/// that need a restart are reported and keep their running value, so they are reported again
+/// on every reload until the node is restarted.
+///
+/// `running` holds the file's values as loaded at startup, before [`apply_env_overrides`], so
+/// an edit to a setting an env var overrides is still reported.
+fn reload_config(
+    path: &std::path::Path,
+    running: &mut NodeConfig,
+    log_filter_handle: &LogFilterHandle,
+    edgli: &edgli::Edgli,
+    monitor: &MonitorArgs,
+    reactor: &mut Option<StrategyTasks>,
+) -> anyhow::Result<()> {
+    let reloaded = config::load_config(path)?;
+
+    let plan = ReloadPlan::new(running, &reloaded)?;
+    if plan.is_empty() {
+        info!("The configuration is unchanged");
+        return Ok(());
+    }
+
+    // Validate every live setting before applying any of them.
+    let filter = log_filter(reloaded.edge.log_filter.as_deref())?;
+    if let Some(strategy) = &reloaded.edge.strategy {
+        default_strategy_cfg(&strategy.to_incentive_configuration()?)?;
+    }
+
+    for setting in &plan.restart_required {
+        warn!(%setting, "Changed setting takes effect only after a restart");
     }
-    .try_into()?;
+
+    if plan.changes("edge.strategy") {
+        let tasks = start_reactor(edgli, reloaded.edge.strategy.as_ref(), monitor)?;
+        if let Some(previous) = std::mem::replace(reactor, tasks) {
+            previous.stop();
+            info!("Stopped the previous strategy reactor");
+        }
+        running.edge.strategy = reloaded.edge.strategy;
+    }
+
+    if plan.changes("edge.log_filter") {
+        log_filter_handle.reload(filter)?;
+        info!(log_filter = ?reloaded.edge.log_filter, "Applied the log filter");
+        running.edge.log_filter = reloaded.edge.log_filter;
+    }
+
+    info!(applied = ?plan.live, "Configuration reloaded");
+    Ok(())
+}
+
+async fn run_node(args: RunArgs, log_filter_handle: LogFilterHandle) -> anyhow::Result<()> {
+    if !args.identity.identity_file_path.exists() {
+        return Err(EdgliError::ConfigError(format!(
+            "The identity file '{}' does not exist; create one with `edgli identity create`",
+            args.identity.identity_file_path.display()
+        ))
+        .into());
+    }
+
+    // Kept as the file has it, for reloads to diff against; the node runs with the env
+    // overrides applied on top.
+    let mut cfg: NodeConfig = config::load_config(&args.config)?;
+    let mut hopr_cfg = cfg.hopr.clone();
+    apply_env_overrides(&mut hopr_cfg);
+    log_filter_handle.reload(log_filter(cfg.edge.log_filter.as_deref())?)?;
+
+    let hopr_keys = identity::read_identity(
+        &args.identity.identity_file_path,
+        &args.identity.identity_password,
+    )?;
 
     info!(
         version = hopr_lib::constants::APP_VERSION,
-        ?cfg,
+        hopr = ?hopr_cfg,
+        edge = ?cfg.edge,
         "Starting Edgli"
     );
 
-    let mut blokli_endpoint = BlokliEndpoint::from_optional_url(args.blokli_url.as_deref())?;
-    if let Some(dns_override) = args.blokli_dns_override {
-        blokli_endpoint = blokli_endpoint.with_dns_override(dns_override);
+    let blokli_endpoints = args.blokli.endpoints()?;
+
+    // Served from the start, so orchestrators see the node alive while it initializes.
+    #[cfg(feature = "probes")]
+    let (health, health_server) = start_health(&args.health).await?;
+
+    let edgli = edgli::Edgli::builder(hopr_cfg, hopr_keys)
+        .blokli_endpoints(blokli_endpoints)
+        .gas_cap(args.blokli.gas_cap())
+        .probe_local_addresses(args.probe_local_addresses)
+        .visitor({
+            #[cfg(feature = "probes")]
+            let health = health.clone();
+            move |s| {
+                #[cfg(feature = "probes")]
+                health.record(s);
+                info!(?s, "Initialization stage");
+            }
+        })
+        .build()
+        .await?;
+    #[cfg(feature = "telemetry")]
+    if let Some(provider) = TRACER_PROVIDER.get() {
+        edgli.flush_on_shutdown(provider.clone());
     }
+    #[cfg(feature = "probes")]
+    health.attach(std::sync::Arc::new(edgli.clone()));
 
-    let edgli = edgli::Edgli::new(
-        cfg,
-        hopr_keys,
-        blokli_endpoint,
-        None,
-        args.probe_local_addresses,
-        |s| {
-            info!(?s, "Initialization stage");
-        },
-    )
-    .await?;
+    let mut reactor = start_reactor(&edgli, cfg.edge.strategy.as_ref(), &args.monitor)?;
+    #[cfg(feature = "telemetry")]
+    let metrics_sampler = start_metrics_sampler(&edgli);
+    #[cfg(feature = "api")]
+    let api = start_api(&args.api, &edgli, &cfg).await?;
 
-    let mut signals =
-        Signals::new([Signal::Hup, Signal::Int]).map_err(|e| EdgliError::OsError(e.to_string()))?;
+    let mut signals = Signals::new([Signal::Hup, Signal::Int, Signal::Term])
+        .map_err(|e| EdgliError::OsError(e.to_string()))?;
     while let Some(Ok(signal)) = signals.next().await {
         match signal {
             Signal::Hup => {
-                info!("Received the HUP signal... not doing anything");
+                info!("Received the HUP signal... reloading the configuration");
+                if let Err(error) = reload_config(
+                    &args.config,
+                    &mut cfg,
+                    &log_filter_handle,
+                    &edgli,
+                    &args.monitor,
+                    &mut reactor,
+                ) {
+                    warn!(%error, "Configuration reload failed; the running configuration is kept");
+                }
+                #[cfg(feature = "api")]
+                if let Some((state, _)) = &api
+                    && let Ok(incentives) = cfg.edge.incentive_configuration()
+                {
+                    state.set_incentives(incentives);
+                }
             }
-            Signal::Int => {
-                info!("Received the INT signal... tearing down the node");
+            Signal::Int | Signal::Term => {
+                info!(
+                    ?signal,
+                    "Received a termination signal... shutting the node down"
+                );
+                let summary = edgli
+                    .shutdown(std::time::Duration::from_secs(args.shutdown_timeout_secs))
+                    .await;
+                info!(%summary, "Node shut down");
+                #[cfg(feature = "telemetry")]
+                metrics_sampler.abort();
+                #[cfg(feature = "api")]
+                for (_, handle) in &api {
+                    handle.abort();
+                }
+                #[cfg(feature = "probes")]
+                if let Some(handle) = &health_server {
+                    handle.abort();
+                }
                 // Dropping Edgli aborts all background tasks via AbortableList.
                 drop(edgli);
                 info!("All processes stopped... emulating the default handler...");
@@ -0,0 +1,287 @@
+//! Metrics owned by edgli, as opposed to the hopr-lib metrics behind
+//! [`hopr_lib::collect_hopr_metrics`]. See `METRICS.md` for names and labels.
+//!
+//! Every function here is a no-op unless the `telemetry` feature is enabled, so call sites do
+//! not need their own `cfg`. Instruments are recorded through the global OpenTelemetry meter
+//! provider and therefore end up in the same export as hopr-lib's.
+//!
+//! Instruments are looked up on every call instead of being cached in statics: an instrument
+//! created before hopr-lib installs the global meter provider would stay bound to the no-op
+//! provider forever. All metrics here are recorded at most a few times per minute, so the
+//! lookup cost does not matter.
+
+use std::collections::BTreeSet;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use hopr_lib::api::types::primitive::prelude::{HoprBalance, XDaiBalance};
+
+use crate::strategy::CapacityAllocations;
+
+#[cfg(feature = "telemetry")]
+use {
+    crate::strategy::Capacity,
+    opentelemetry::{
+        KeyValue,
+        metrics::{Gauge, Meter},
+    },
+};
+
+#[cfg(feature = "telemetry")]
+fn meter() -> Meter {
+    opentelemetry::global::meter("edgli")
+}
+
+/// Converts a balance in base units (wei) to tokens, as a lossy `f64` for gauges.
+#[cfg_attr(not(feature = "telemetry"), allow(dead_code))]
+fn token_units(base_units: impl std::fmt::Display) -> f64 {
+    base_units.to_string().parse::<f64>().unwrap_or(f64::NAN) / 1e18
+}
+
+/// Records how long the node spent in an initialization `stage`.
+pub fn record_init_stage(stage: &str, duration: Duration) {
+    #[cfg(feature = "telemetry")]
+    meter()
+        .f64_gauge("edgli_init_stage_duration_seconds")
+        .with_description("Time spent in each initialization stage of the last start")
+        .with_unit("s")
+        .build()
+        .record(
+            duration.as_secs_f64(),
+            &[KeyValue::new("stage", stage.to_string())],
+        );
+    #[cfg(not(feature = "telemetry"))]
+    let _ = (stage, duration);
+}
+
+/// Records the latency and outcome of one Blokli request made to `endpoint`.
+pub fn record_blokli_request(
+    operation: &'static str,
+    endpoint: &str,
+    elapsed: Duration,
+    succeeded: bool,
+) {
+    #[cfg(feature = "telemetry")]
+    {
+        let meter = meter();
+        let labels = [
+            KeyValue::new("operation", operation),
+            KeyValue::new("endpoint", endpoint.to_string()),
+        ];
+        meter
+            .f64_histogram("edgli_blokli_request_duration_seconds")
+            .with_description("Latency of Blokli requests made by edgli")
+            .with_unit("s")
+            .build()
+            .record(elapsed.as_secs_f64(), &labels);
+        if !succeeded {
+            meter
+                .u64_counter("edgli_blokli_request_errors_total")
+                .with_description("Failed Blokli requests made by edgli")
+                .build()
+                .add(1, &labels);
+        }
+    }
+    #[cfg(not(feature = "telemetry"))]
+    let _ = (operation, endpoint, elapsed, succeeded);
+}
+
+/// Awaits a Blokli request to `endpoint`, recording it under `operation`.
+pub async fn blokli_request<T, E>(
+    operation: &'static str,
+    endpoint: String,
+    request: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let started = Instant::now();
+    let result = request.await;
+    record_blokli_request(operation, &endpoint, started.elapsed(), result.is_ok());
+    result
+}
+
+/// Records the gas price Blokli reported, in wei per gas.
+pub fn record_max_fee_per_gas(max_fee_per_gas: u128) {
+    #[cfg(feature = "telemetry")]
+    meter()
+        .f64_gauge("edgli_max_fee_per_gas_wei")
+        .with_description("Chain max_fee_per_gas last reported by Blokli")
+        .build()
+        .record(max_fee_per_gas as f64, &[]);
+    #[cfg(not(feature = "telemetry"))]
+    let _ = max_fee_per_gas;
+}
+
+/// Counts a strategy reactor (re)start.
+pub fn record_reactor_start() {
+    #[cfg(feature = "telemetry")]
+    meter()
+        .u64_counter("edgli_reactor_starts_total")
+        .with_description("Strategy reactor starts, restarts included")
+        .build()
+        .add(1, &[]);
+}
+
+/// Counts a strategy reactor failure.
+pub fn record_reactor_failure() {
+    #[cfg(feature = "telemetry")]
+    meter()
+        .u64_counter("edgli_reactor_failures_total")
+        .with_description("Strategy reactor runs that ended in an error")
+        .build()
+        .add(1, &[]);
+}
+
+/// Records the node and Safe balances.
+pub fn record_balances(node_wxhopr: HoprBalance, safe_wxhopr: HoprBalance, node_xdai: XDaiBalance) {
+    #[cfg(feature = "telemetry")]
+    {
+        let gauge = meter()
+            .f64_gauge("edgli_balance")
+            .with_description("Last sampled balances, in tokens")
+            .build();
+        for (account, token, amount) in [
+            ("node", "wxhopr", token_units(node_wxhopr.amount())),
+            ("safe", "wxhopr", token_units(safe_wxhopr.amount())),
+            ("node", "xdai", token_units(node_xdai.amount())),
+        ] {
+            gauge.record(
+                amount,
+                &[
+                    KeyValue::new("account", account),
+                    KeyValue::new("token", token),
+                ],
+            );
+        }
+    }
+    #[cfg(not(feature = "telemetry"))]
+    let _ = (node_wxhopr, safe_wxhopr, node_xdai);
+}
+
+#[cfg(feature = "telemetry")]
+fn record_capacity(
+    stake: &Gauge<f64>,
+    messages: &Gauge<u64>,
+    capacity: &Capacity,
+    labels: &[KeyValue],
+) {
+    stake.record(token_units(capacity.stake.amount()), labels);
+    messages.record(capacity.expected_messages, labels);
+}
+
+/// Channel destinations [`record_capacity_allocations`] recorded last.
+#[cfg(feature = "telemetry")]
+static RECORDED_DESTINATIONS: std::sync::Mutex<BTreeSet<String>> =
+    std::sync::Mutex::new(BTreeSet::new());
+
+/// Replaces `recorded` with `current` and returns the destinations no longer in it.
+#[cfg_attr(not(feature = "telemetry"), allow(dead_code))]
+fn departed_destinations(
+    recorded: &mut BTreeSet<String>,
+    current: BTreeSet<String>,
+) -> Vec<String> {
+    let departed = recorded.difference(&current).cloned().collect();
+    *recorded = current;
+    departed
+}
+
+/// Records the open channel count and every stake's [`Capacity`].
+///
+/// Gauges cannot drop a series, so the capacities of channels closed since the last call are
+/// recorded as zero.
+pub fn record_capacity_allocations(allocations: &CapacityAllocations) {
+    #[cfg(feature = "telemetry")]
+    {
+        let meter = meter();
+        meter
+            .u64_gauge("edgli_open_channels")
+            .with_description("Open outgoing payment channels")
+            .build()
+            .record(allocations.peer_allocations.len() as u64, &[]);
+
+        let stake = meter
+            .f64_gauge("edgli_capacity_stake_wxhopr")
+            .with_description("wxHOPR stake behind each capacity")
+            .build();
+        let messages = meter
+            .u64_gauge("edgli_capacity_expected_messages")
+            .with_description("Expected session frames each stake can fund")
+            .build();
+        let channel_labels = |destination: String| {
+            [
+                KeyValue::new("kind", "channel"),
+                KeyValue::new("destination", destination),
+            ]
+        };
+        for (destination, capacity) in &allocations.peer_allocations {
+            record_capacity(
+                &stake,
+                &messages,
+                capacity,
+                &channel_labels(destination.to_string()),
+            );
+        }
+        let current = allocations
+            .peer_allocations
+            .keys()
+            .map(ToString::to_string)
+            .collect();
+        let departed = departed_destinations(
+            &mut RECORDED_DESTINATIONS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+            current,
+        );
+        for destination in departed {
+            let labels = channel_labels(destination);
+            stake.record(0.0, &labels);
+            messages.record(0, &labels);
+        }
+        record_capacity(
+            &stake,
+            &messages,
+            &allocations.node,
+            &[KeyValue::new("kind", "node")],
+        );
+        record_capacity(
+            &stake,
+            &messages,
+            &allocations.safe,
+            &[KeyValue::new("kind", "safe")],
+        );
+    }
+    #[cfg(not(feature = "telemetry"))]
+    let _ = allocations;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_units_scales_base_units() {
+        assert_eq!(token_units(1_500_000_000_000_000_000u128), 1.5);
+        assert_eq!(token_units(0u8), 0.0);
+        assert!(token_units("not a number").is_nan());
+    }
+
+    #[tokio::test]
+    async fn blokli_request_passes_the_result_through() {
+        let endpoint = || "https://blokli.example.org".to_string();
+        let ok: Result<u8, &str> = blokli_request("test", endpoint(), async { Ok(7) }).await;
+        assert_eq!(ok, Ok(7));
+        let err: Result<u8, &str> = blokli_request("test", endpoint(), async { Err("down") }).await;
+        assert_eq!(err, Err("down"));
+    }
+
+    #[test]
+    fn departed_destinations_are_those_no_longer_allocated() {
+        let set = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<BTreeSet<_>>();
+        let mut recorded = set(&["a", "b"]);
+
+        assert_eq!(
+            departed_destinations(&mut recorded, set(&["b", "c"])),
+            vec!["a".to_string()]
+        );
+        assert_eq!(recorded, set(&["b", "c"]));
+        assert!(departed_destinations(&mut recorded, set(&["b", "c"])).is_empty());
+    }
+}
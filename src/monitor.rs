@@ -0,0 +1,663 @@
+//! Watching a running node's balances for the point where it can no longer operate.
+//!
+//! The strategy stops funding channels silently once the Safe drops below
+//! `min_safe_balance_required`, and transactions start failing once the node wallet runs out
+//! of xDAI. A [`BalanceMonitor`], started with [`Edgli::spawn_balance_monitor`], polls the
+//! balances and emits a [`BalanceEvent`] when one of them crosses its threshold: through
+//! [`BalanceMonitor::subscribe`], the log and, with the `webhook` feature, an HTTP POST.
+//!
+//! An alert is raised when a balance drops below its threshold and cleared only once it
+//! climbs [`BalanceMonitorConfig::hysteresis_percent`] above it, so a balance hovering at
+//! the threshold does not flap.
+//!
+//! [`Edgli::spawn_balance_monitor`]: crate::Edgli::spawn_balance_monitor
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::Stream;
+use futures::future::AbortHandle;
+use hopr_lib::api::types::primitive::prelude::{Address, Balance, HoprBalance, XDaiBalance};
+use tokio::sync::broadcast;
+
+use crate::shutdown::{Lifecycle, PauseReason};
+
+/// Events kept for a subscriber that falls behind; older ones are skipped.
+const EVENT_BUFFER: usize = 64;
+
+/// Events waiting for the webhook; further ones are dropped while it is this far behind.
+#[cfg(feature = "webhook")]
+const WEBHOOK_QUEUE: usize = 16;
+
+/// How long one webhook POST may take before it is abandoned.
+#[cfg(feature = "webhook")]
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How a [`BalanceMonitor`] polls and when it alerts.
+#[derive(Clone, Debug, smart_default::SmartDefault)]
+pub struct BalanceMonitorConfig {
+    /// Time between balance checks. Default: 1 min.
+    #[default(Duration::from_secs(60))]
+    pub interval: Duration,
+
+    /// Transactions' worth of gas the node wallet must hold before xDAI counts as low.
+    /// Default: 10.
+    #[default(10)]
+    pub min_xdai_transactions: u64,
+
+    /// Gas one transaction is assumed to use when pricing `min_xdai_transactions`.
+    /// Default: 150 000, as [`crate::strategy::XdaiBudgetConfig::gas_per_channel_tx`].
+    #[default(150_000)]
+    pub gas_per_tx: u64,
+
+    /// How far, in percent of the threshold, a balance must climb above it before its alert
+    /// clears. Default: 10.
+    #[default(10)]
+    pub hysteresis_percent: u64,
+
+    /// Whether the node's strategy reactors are paused while xDAI is low, so they stop
+    /// attempting transactions the node cannot pay for. Default: `false`.
+    #[default(false)]
+    pub pause_reactor_on_low_xdai: bool,
+
+    /// URL each event is POSTed to as JSON. Default: none.
+    ///
+    /// Posts are sent from a task of their own, so a slow endpoint does not delay the
+    /// balance checks. Each gives up after 10 s, and events are dropped while 16 are already
+    /// waiting to be sent.
+    #[cfg(feature = "webhook")]
+    #[default(None)]
+    pub webhook: Option<url::Url>,
+}
+
+/// A balance below the level the node needs to keep operating.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BalanceAlert {
+    /// The node wallet holds less xDAI than [`BalanceMonitorConfig::min_xdai_transactions`]
+    /// cost at the current gas price.
+    LowXdai {
+        balance: XDaiBalance,
+        threshold: XDaiBalance,
+    },
+    /// The Safe holds less wxHOPR than the strategy requires to open or fund any channel.
+    LowSafe {
+        balance: HoprBalance,
+        threshold: HoprBalance,
+    },
+    /// An open outgoing channel is below the balance at which the strategy tops it up.
+    LowChannel {
+        destination: Address,
+        balance: HoprBalance,
+        threshold: HoprBalance,
+    },
+}
+
+/// Identifies an alert across polls, whatever the balances it carries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum AlertKey {
+    Xdai,
+    Safe,
+    Channel(Address),
+}
+
+impl BalanceAlert {
+    fn key(&self) -> AlertKey {
+        match self {
+            Self::LowXdai { .. } => AlertKey::Xdai,
+            Self::LowSafe { .. } => AlertKey::Safe,
+            Self::LowChannel { destination, .. } => AlertKey::Channel(*destination),
+        }
+    }
+
+    /// A short, stable name for the kind of alert.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::LowXdai { .. } => "low_xdai",
+            Self::LowSafe { .. } => "low_safe",
+            Self::LowChannel { .. } => "low_channel",
+        }
+    }
+}
+
+impl std::fmt::Display for BalanceAlert {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LowXdai { balance, threshold } => {
+                write!(f, "node xDAI {balance} below {threshold}")
+            }
+            Self::LowSafe { balance, threshold } => {
+                write!(f, "Safe wxHOPR {balance} below {threshold}")
+            }
+            Self::LowChannel {
+                destination,
+                balance,
+                threshold,
+            } => write!(
+                f,
+                "channel to {destination} at {balance}, below {threshold}"
+            ),
+        }
+    }
+}
+
+/// A change in the alerts a [`BalanceMonitor`] has raised.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BalanceEvent {
+    /// The balance dropped below its threshold.
+    Raised(BalanceAlert),
+    /// The balance recovered; carries the values it recovered to. A channel alert also
+    /// clears when the channel is no longer open.
+    Cleared(BalanceAlert),
+}
+
+impl BalanceEvent {
+    /// The alert the event is about.
+    pub fn alert(&self) -> &BalanceAlert {
+        match self {
+            Self::Raised(alert) | Self::Cleared(alert) => alert,
+        }
+    }
+
+    #[cfg(feature = "webhook")]
+    fn webhook_payload(&self) -> serde_json::Value {
+        let (event, alert) = match self {
+            Self::Raised(alert) => ("raised", alert),
+            Self::Cleared(alert) => ("cleared", alert),
+        };
+        let (balance, threshold, destination) = match alert {
+            BalanceAlert::LowXdai { balance, threshold } => {
+                (balance.to_string(), threshold.to_string(), None)
+            }
+            BalanceAlert::LowSafe { balance, threshold } => {
+                (balance.to_string(), threshold.to_string(), None)
+            }
+            BalanceAlert::LowChannel {
+                destination,
+                balance,
+                threshold,
+            } => (
+                balance.to_string(),
+                threshold.to_string(),
+                Some(destination.to_string()),
+            ),
+        };
+        serde_json::json!({
+            "event": event,
+            "alert": alert.kind(),
+            "balance": balance,
+            "threshold": threshold,
+            "destination": destination,
+            "message": alert.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for BalanceEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Raised(alert) => write!(f, "raised: {alert}"),
+            Self::Cleared(alert) => write!(f, "cleared: {alert}"),
+        }
+    }
+}
+
+/// The levels the balances are checked against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BalanceThresholds {
+    pub xdai: XDaiBalance,
+    pub safe: HoprBalance,
+    pub channel: HoprBalance,
+}
+
+/// The balances checked in one poll.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BalanceSnapshot {
+    pub node_xdai: XDaiBalance,
+    pub safe_wxhopr: HoprBalance,
+    /// Open outgoing channels by destination.
+    pub channels: Vec<(Address, HoprBalance)>,
+}
+
+/// The alerts currently raised, and the hysteresis that clears them.
+#[derive(Debug)]
+pub(crate) struct AlertTracker {
+    hysteresis_percent: u64,
+    active: HashMap<AlertKey, BalanceAlert>,
+}
+
+impl AlertTracker {
+    pub(crate) fn new(hysteresis_percent: u64) -> Self {
+        Self {
+            hysteresis_percent,
+            active: HashMap::new(),
+        }
+    }
+
+    /// Whether an xDAI alert is raised.
+    pub(crate) fn xdai_low(&self) -> bool {
+        self.active.contains_key(&AlertKey::Xdai)
+    }
+
+    /// Compares `snapshot` against `thresholds` and returns the alerts raised and cleared.
+    pub(crate) fn update(
+        &mut self,
+        snapshot: &BalanceSnapshot,
+        thresholds: &BalanceThresholds,
+    ) -> Vec<BalanceEvent> {
+        let mut events = Vec::new();
+        let hysteresis = self.hysteresis_percent;
+        self.check(
+            &mut events,
+            level(snapshot.node_xdai, thresholds.xdai, hysteresis),
+            BalanceAlert::LowXdai {
+                balance: snapshot.node_xdai,
+                threshold: thresholds.xdai,
+            },
+        );
+        self.check(
+            &mut events,
+            level(snapshot.safe_wxhopr, thresholds.safe, hysteresis),
+            BalanceAlert::LowSafe {
+                balance: snapshot.safe_wxhopr,
+                threshold: thresholds.safe,
+            },
+        );
+        for &(destination, balance) in &snapshot.channels {
+            self.check(
+                &mut events,
+                level(balance, thresholds.channel, hysteresis),
+                BalanceAlert::LowChannel {
+                    destination,
+                    balance,
+                    threshold: thresholds.channel,
+                },
+            );
+        }
+
+        let closed: Vec<AlertKey> = self
+            .active
+            .keys()
+            .filter(|key| match key {
+                AlertKey::Channel(destination) => {
+                    !snapshot.channels.iter().any(|(d, _)| d == destination)
+                }
+                _ => false,
+            })
+            .copied()
+            .collect();
+        for key in closed {
+            if let Some(alert) = self.active.remove(&key) {
+                events.push(BalanceEvent::Cleared(alert));
+            }
+        }
+        events
+    }
+
+    /// Raises `alert` when `level` says the balance is low, clears it once recovered, and
+    /// keeps the current state in between.
+    fn check(&mut self, events: &mut Vec<BalanceEvent>, level: Level, alert: BalanceAlert) {
+        let key = alert.key();
+        match (level, self.active.contains_key(&key)) {
+            (Level::Below, false) => {
+                self.active.insert(key, alert);
+                events.push(BalanceEvent::Raised(alert));
+            }
+            (Level::Recovered, true) => {
+                self.active.remove(&key);
+                events.push(BalanceEvent::Cleared(alert));
+            }
+            (Level::Below | Level::Band, true) => {
+                self.active.insert(key, alert);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Where a balance stands relative to its threshold.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Level {
+    Below,
+    /// At or above the threshold, but within the hysteresis band above it.
+    Band,
+    Recovered,
+}
+
+fn level<C>(balance: Balance<C>, threshold: Balance<C>, percent: u64) -> Level
+where
+    Balance<C>: Copy + PartialOrd + std::ops::Mul<u64, Output = Balance<C>>,
+{
+    if balance < threshold {
+        Level::Below
+    } else if balance * 100u64 < threshold * (100 + percent) {
+        Level::Band
+    } else {
+        Level::Recovered
+    }
+}
+
+/// Handle to a running balance monitor; see [`crate::Edgli::spawn_balance_monitor`].
+///
+/// Dropping the handle leaves the monitor running; [`Self::stop`] or the node's shutdown
+/// stops it.
+pub struct BalanceMonitor {
+    pub(crate) events: broadcast::Sender<BalanceEvent>,
+    pub(crate) abort_handle: AbortHandle,
+    /// The node whose reactors the monitor pauses, when it does.
+    pub(crate) pauses: Option<Arc<Lifecycle>>,
+}
+
+impl BalanceMonitor {
+    /// The events raised from now on. A subscriber that falls more than a few dozen events
+    /// behind skips the oldest.
+    pub fn subscribe(&self) -> impl Stream<Item = BalanceEvent> + Send + 'static {
+        futures::stream::unfold(self.events.subscribe(), |mut events| async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => return Some((event, events)),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "balance event subscriber fell behind");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Stops the monitor, releasing any reactor it paused; subscriptions end once the handle
+    /// is dropped too.
+    pub fn stop(&self) {
+        self.abort_handle.abort();
+        if let Some(lifecycle) = &self.pauses {
+            lifecycle.pause_reactors(PauseReason::LowXdai, false);
+        }
+    }
+}
+
+/// Where a monitor's events go: the log, the subscribers and the webhook.
+pub(crate) struct Publisher {
+    events: broadcast::Sender<BalanceEvent>,
+    /// Queue of the task posting to the webhook, which ends once this is dropped.
+    #[cfg(feature = "webhook")]
+    webhook: Option<tokio::sync::mpsc::Sender<BalanceEvent>>,
+}
+
+impl Publisher {
+    /// Must be called from within a Tokio runtime when `cfg` has a webhook, whose posting
+    /// task starts here.
+    #[cfg_attr(not(feature = "webhook"), allow(unused_variables))]
+    pub(crate) fn new(cfg: &BalanceMonitorConfig) -> Self {
+        Self {
+            events: broadcast::Sender::new(EVENT_BUFFER),
+            #[cfg(feature = "webhook")]
+            webhook: cfg.webhook.clone().map(spawn_webhook),
+        }
+    }
+
+    pub(crate) fn sender(&self) -> broadcast::Sender<BalanceEvent> {
+        self.events.clone()
+    }
+
+    fn publish(&self, event: BalanceEvent) {
+        match event {
+            BalanceEvent::Raised(alert) => {
+                tracing::warn!(kind = alert.kind(), %alert, "balance low")
+            }
+            BalanceEvent::Cleared(alert) => {
+                tracing::info!(kind = alert.kind(), %alert, "balance recovered")
+            }
+        }
+        // No subscriber is not an error.
+        let _ = self.events.send(event);
+
+        #[cfg(feature = "webhook")]
+        if let Some(webhook) = &self.webhook
+            && let Err(tokio::sync::mpsc::error::TrySendError::Full(_)) = webhook.try_send(event)
+        {
+            tracing::warn!(%event, "balance webhook is behind; event not posted");
+        }
+    }
+}
+
+/// Starts the task posting each event queued on the returned sender to `url`, one at a time.
+#[cfg(feature = "webhook")]
+fn spawn_webhook(url: url::Url) -> tokio::sync::mpsc::Sender<BalanceEvent> {
+    let (queue, mut events) = tokio::sync::mpsc::channel::<BalanceEvent>(WEBHOOK_QUEUE);
+    tokio::spawn(async move {
+        let client = match reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(error) => {
+                tracing::warn!(%url, %error, "balance webhook disabled");
+                return;
+            }
+        };
+        while let Some(event) = events.recv().await {
+            let posted = client
+                .post(url.clone())
+                .json(&event.webhook_payload())
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+            if let Err(error) = posted {
+                tracing::warn!(%url, %error, "balance webhook failed");
+            }
+        }
+    });
+    queue
+}
+
+/// Polls every `cfg.interval` and publishes the alerts raised and cleared. `on_xdai_low`
+/// hears whether xDAI is low after each successful poll; a failed poll is logged and retried
+/// at the next interval.
+pub(crate) async fn run<F, Fut>(
+    cfg: BalanceMonitorConfig,
+    publisher: Publisher,
+    mut poll: F,
+    on_xdai_low: impl Fn(bool),
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<(BalanceSnapshot, BalanceThresholds)>>,
+{
+    let mut tracker = AlertTracker::new(cfg.hysteresis_percent);
+    let mut ticks = tokio::time::interval(cfg.interval);
+    ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticks.tick().await;
+        match poll().await {
+            Ok((snapshot, thresholds)) => {
+                for event in tracker.update(&snapshot, &thresholds) {
+                    publisher.publish(event);
+                }
+                on_xdai_low(tracker.xdai_low());
+            }
+            Err(error) => tracing::warn!(%error, "balance check failed"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wxhopr(amount: u32) -> HoprBalance {
+        HoprBalance::from(amount)
+    }
+
+    fn thresholds() -> BalanceThresholds {
+        BalanceThresholds {
+            xdai: XDaiBalance::from(1_000u64),
+            safe: wxhopr(100),
+            channel: wxhopr(10),
+        }
+    }
+
+    fn snapshot(xdai: u64, safe: u32, channels: &[(u8, u32)]) -> BalanceSnapshot {
+        BalanceSnapshot {
+            node_xdai: XDaiBalance::from(xdai),
+            safe_wxhopr: wxhopr(safe),
+            channels: channels
+                .iter()
+                .map(|&(peer, balance)| (Address::from([peer; 20]), wxhopr(balance)))
+                .collect(),
+        }
+    }
+
+    fn kinds(events: &[BalanceEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|e| match e {
+                BalanceEvent::Raised(a) => format!("+{}", a.kind()),
+                BalanceEvent::Cleared(a) => format!("-{}", a.kind()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn alerts_are_raised_once_when_balances_drop_below_their_thresholds() {
+        let mut tracker = AlertTracker::new(10);
+        assert!(
+            tracker
+                .update(&snapshot(5_000, 500, &[(1, 50)]), &thresholds())
+                .is_empty()
+        );
+
+        let events = tracker.update(&snapshot(999, 99, &[(1, 9), (2, 50)]), &thresholds());
+        assert_eq!(kinds(&events), ["+low_xdai", "+low_safe", "+low_channel"]);
+        assert!(tracker.xdai_low());
+        assert!(
+            tracker
+                .update(&snapshot(500, 50, &[(1, 5), (2, 50)]), &thresholds())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn hysteresis_keeps_alerts_raised_just_above_the_threshold() {
+        let mut tracker = AlertTracker::new(10);
+        tracker.update(&snapshot(999, 500, &[]), &thresholds());
+
+        // At the threshold and within 10% above it: still low.
+        assert!(
+            tracker
+                .update(&snapshot(1_000, 500, &[]), &thresholds())
+                .is_empty()
+        );
+        assert!(
+            tracker
+                .update(&snapshot(1_099, 500, &[]), &thresholds())
+                .is_empty()
+        );
+        assert!(tracker.xdai_low());
+
+        let events = tracker.update(&snapshot(1_100, 500, &[]), &thresholds());
+        assert_eq!(kinds(&events), ["-low_xdai"]);
+        assert!(!tracker.xdai_low());
+
+        // Dipping back into the band does not raise it again.
+        assert!(
+            tracker
+                .update(&snapshot(1_050, 500, &[]), &thresholds())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn closed_channels_clear_their_alerts() {
+        let mut tracker = AlertTracker::new(10);
+        tracker.update(&snapshot(5_000, 500, &[(1, 1)]), &thresholds());
+        let events = tracker.update(&snapshot(5_000, 500, &[]), &thresholds());
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            events[0],
+            BalanceEvent::Cleared(BalanceAlert::LowChannel { destination, .. })
+                if destination == Address::from([1u8; 20])
+        ));
+    }
+
+    #[cfg(feature = "webhook")]
+    #[tokio::test]
+    async fn a_stalled_webhook_does_not_hold_up_publishing() {
+        // Accepts connections but never answers them.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap())
+            .parse()
+            .unwrap();
+        let publisher = Publisher::new(&BalanceMonitorConfig {
+            webhook: Some(url),
+            ..Default::default()
+        });
+        let mut events = publisher.sender().subscribe();
+        let event = BalanceEvent::Raised(BalanceAlert::LowXdai {
+            balance: XDaiBalance::zero(),
+            threshold: thresholds().xdai,
+        });
+
+        // More than the queue holds: the overflow is dropped rather than waited for.
+        for _ in 0..2 * WEBHOOK_QUEUE {
+            publisher.publish(event);
+        }
+        assert_eq!(events.recv().await.unwrap(), event);
+        assert_eq!(events.len(), 2 * WEBHOOK_QUEUE - 1);
+    }
+
+    #[tokio::test]
+    async fn monitor_streams_events_and_reports_low_xdai() {
+        use std::sync::{Arc, Mutex};
+
+        use futures::StreamExt;
+
+        let cfg = BalanceMonitorConfig {
+            interval: Duration::from_millis(1),
+            ..Default::default()
+        };
+        let publisher = Publisher::new(&cfg);
+        let monitor = BalanceMonitor {
+            events: publisher.sender(),
+            abort_handle: AbortHandle::new_pair().0,
+            pauses: None,
+        };
+        let mut stream = Box::pin(monitor.subscribe());
+
+        let polls = Arc::new(Mutex::new(vec![
+            snapshot(5_000, 500, &[]),
+            snapshot(500, 500, &[]),
+            snapshot(5_000, 500, &[]),
+        ]));
+        let xdai_low = Arc::new(Mutex::new(Vec::new()));
+        let (running, abort_handle) = futures::future::abortable(run(
+            cfg,
+            publisher,
+            {
+                let polls = polls.clone();
+                move || {
+                    let next = polls.lock().unwrap().pop();
+                    async move {
+                        next.map(|s| (s, thresholds()))
+                            .ok_or_else(|| anyhow::anyhow!("no more balances"))
+                    }
+                }
+            },
+            {
+                let xdai_low = xdai_low.clone();
+                move |low| xdai_low.lock().unwrap().push(low)
+            },
+        ));
+        let task = tokio::spawn(running);
+
+        assert!(matches!(
+            stream.next().await,
+            Some(BalanceEvent::Raised(BalanceAlert::LowXdai { .. }))
+        ));
+        assert!(matches!(
+            stream.next().await,
+            Some(BalanceEvent::Cleared(BalanceAlert::LowXdai { .. }))
+        ));
+        abort_handle.abort();
+        let _ = task.await;
+        assert_eq!(*xdai_low.lock().unwrap(), [false, true, false]);
+
+        drop(monitor);
+        assert_eq!(stream.next().await, None);
+    }
+}
@@ -0,0 +1,342 @@
+//! The Safe on-boarding flow behind `edgli onboard`.
+//!
+//! [`onboard`] drives an [`IncentiveOperations`] handle from a freshly created identity to a
+//! deployed, funded Safe: it waits for the node address to be funded with the xDAI and
+//! wxHOPR the [`minimum_balance_recommendation`] asks for, deploys the Safe and module, and
+//! sweeps any wxHOPR left on the node EOA into the Safe. Progress is reported through a
+//! visitor callback, mirroring [`crate::EdgliInitState`] for node startup.
+//!
+//! Every step checks on-chain state first, so re-running the flow after an interruption
+//! resumes where it stopped instead of deploying a second Safe.
+
+use std::time::Duration;
+
+use hopr_lib::api::types::primitive::prelude::{Address, HoprBalance, XDaiBalance};
+
+use crate::blokli::{IncentiveOperations, SafeModuleDeploymentResult};
+use crate::strategy::{IncentiveConfiguration, minimum_balance_recommendation};
+
+/// Progress reported by [`onboard`], in the order the steps happen.
+#[derive(Clone, Debug)]
+pub enum OnboardingEvent {
+    /// The on-chain address that has to be funded.
+    NodeAddress(Address),
+    /// The node address does not hold enough yet; the flow polls again after
+    /// [`OnboardingOptions::poll_interval`].
+    AwaitingFunds {
+        node_xdai: XDaiBalance,
+        node_wxhopr: HoprBalance,
+        required_xdai: XDaiBalance,
+        required_wxhopr: HoprBalance,
+    },
+    /// The node address holds enough xDAI and wxHOPR to deploy the Safe.
+    Funded {
+        node_xdai: XDaiBalance,
+        node_wxhopr: HoprBalance,
+    },
+    /// A Safe was already deployed for this identity, so deployment is skipped.
+    SafeFound(SafeModuleDeploymentResult),
+    /// The Safe deployment transaction is being submitted, funded with `token_amount`.
+    DeployingSafe { token_amount: HoprBalance },
+    /// The Safe and module are deployed.
+    SafeDeployed(SafeModuleDeploymentResult),
+    /// wxHOPR left on the node EOA is being moved into the Safe.
+    SweepingWxHopr { amount: HoprBalance },
+    /// On-boarding finished; the addresses belong in the node configuration.
+    Complete(SafeModuleDeploymentResult),
+}
+
+/// Tuning for [`onboard`].
+#[derive(Clone, Debug, smart_default::SmartDefault)]
+pub struct OnboardingOptions {
+    /// How long to wait between balance checks while waiting for funds. Default: 10 s.
+    #[default(Duration::from_secs(10))]
+    pub poll_interval: Duration,
+
+    /// Give up waiting for funds after this long. Default: `None` (wait indefinitely).
+    #[default(None)]
+    pub funding_timeout: Option<Duration>,
+}
+
+/// Waits until the node address holds what [`minimum_balance_recommendation`] asks for.
+async fn await_funds(
+    incentive_ops: &dyn IncentiveOperations,
+    incentives: &IncentiveConfiguration,
+    options: &OnboardingOptions,
+    visitor: &(impl Fn(OnboardingEvent) + Send + Sync),
+) -> anyhow::Result<HoprBalance> {
+    let started = std::time::Instant::now();
+    loop {
+        let recommendation = minimum_balance_recommendation(incentive_ops, incentives).await?;
+        let (node_wxhopr, node_xdai) = incentive_ops.balances().await?;
+        let required_wxhopr = recommendation.total_wxhopr();
+        let required_xdai = recommendation.xdai_minimum;
+
+        if node_wxhopr >= required_wxhopr && node_xdai >= required_xdai {
+            visitor(OnboardingEvent::Funded {
+                node_xdai,
+                node_wxhopr,
+            });
+            return Ok(node_wxhopr);
+        }
+
+        visitor(OnboardingEvent::AwaitingFunds {
+            node_xdai,
+            node_wxhopr,
+            required_xdai,
+            required_wxhopr,
+        });
+
+        if let Some(timeout) = options.funding_timeout
+            && started.elapsed() + options.poll_interval > timeout
+        {
+            anyhow::bail!(
+                "node was not funded within {timeout:?}: holds {node_xdai} and {node_wxhopr}, \
+                 needs {required_xdai} and {required_wxhopr}"
+            );
+        }
+        tokio::time::sleep(options.poll_interval).await;
+    }
+}
+
+/// Runs the on-boarding flow for the identity behind `incentive_ops`.
+///
+/// `incentives` sizes the funding the flow waits for, exactly as the reactor will later
+/// size channels. Returns the Safe and module addresses to put into the node configuration.
+pub async fn onboard(
+    incentive_ops: &dyn IncentiveOperations,
+    node_address: Address,
+    incentives: &IncentiveConfiguration,
+    options: OnboardingOptions,
+    visitor: impl Fn(OnboardingEvent) + Send + Sync,
+) -> anyhow::Result<SafeModuleDeploymentResult> {
+    incentives.validate()?;
+    visitor(OnboardingEvent::NodeAddress(node_address));
+
+    let safe = match incentive_ops.retrieve_safe().await? {
+        Some(safe) => {
+            visitor(OnboardingEvent::SafeFound(safe.clone()));
+            safe
+        }
+        None => {
+            let token_amount = await_funds(incentive_ops, incentives, &options, &visitor).await?;
+            visitor(OnboardingEvent::DeployingSafe { token_amount });
+            let safe = incentive_ops.deploy_safe(token_amount).await?;
+            visitor(OnboardingEvent::SafeDeployed(safe.clone()));
+            safe
+        }
+    };
+
+    // Anything deposited after the deployment snapshot, or before a resumed run, is still
+    // on the node EOA where the reactor cannot use it.
+    let (node_wxhopr, _) = incentive_ops.balances().await?;
+    if node_wxhopr > HoprBalance::zero() {
+        visitor(OnboardingEvent::SweepingWxHopr {
+            amount: node_wxhopr,
+        });
+        incentive_ops
+            .withdraw_wxhopr(safe.safe_address, node_wxhopr)
+            .await?;
+    }
+
+    visitor(OnboardingEvent::Complete(safe.clone()));
+    Ok(safe)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use hopr_lib::api::types::internal::prelude::WinningProbability;
+
+    use super::*;
+    use crate::blokli::TicketStats;
+    use crate::strategy::StartupCosts;
+
+    /// In-test chain: balances are returned from a script, one entry per `balances` call
+    /// (the last entry repeats), and operations are recorded.
+    struct ScriptedChain {
+        safe: Mutex<Option<SafeModuleDeploymentResult>>,
+        balances: Mutex<Vec<(HoprBalance, XDaiBalance)>>,
+        deployed_with: Mutex<Vec<HoprBalance>>,
+        withdrawn: Mutex<Vec<HoprBalance>>,
+    }
+
+    impl ScriptedChain {
+        fn new(balances: Vec<(HoprBalance, XDaiBalance)>) -> Self {
+            Self {
+                safe: Mutex::new(None),
+                balances: Mutex::new(balances),
+                deployed_with: Mutex::new(vec![]),
+                withdrawn: Mutex::new(vec![]),
+            }
+        }
+
+        fn with_safe(self) -> Self {
+            *self.safe.lock().unwrap() = Some(deployment());
+            self
+        }
+    }
+
+    fn deployment() -> SafeModuleDeploymentResult {
+        SafeModuleDeploymentResult {
+            safe_address: [0x11u8; 20].into(),
+            module_address: [0x22u8; 20].into(),
+        }
+    }
+
+    fn rich() -> (HoprBalance, XDaiBalance) {
+        (HoprBalance::new_base(1_000), XDaiBalance::new_base(100))
+    }
+
+    fn broke() -> (HoprBalance, XDaiBalance) {
+        (HoprBalance::zero(), XDaiBalance::zero())
+    }
+
+    #[async_trait::async_trait]
+    impl IncentiveOperations for ScriptedChain {
+        async fn retrieve_safe(&self) -> anyhow::Result<Option<SafeModuleDeploymentResult>> {
+            Ok(self.safe.lock().unwrap().clone())
+        }
+
+        async fn deploy_safe(
+            &self,
+            token_amount: HoprBalance,
+        ) -> anyhow::Result<SafeModuleDeploymentResult> {
+            self.deployed_with.lock().unwrap().push(token_amount);
+            *self.safe.lock().unwrap() = Some(deployment());
+            // The deployment moves the node's wxHOPR into the Safe.
+            let mut balances = self.balances.lock().unwrap();
+            let xdai = balances.last().map_or(XDaiBalance::zero(), |(_, x)| *x);
+            *balances = vec![(HoprBalance::zero(), xdai)];
+            Ok(deployment())
+        }
+
+        async fn ticket_stats(&self) -> anyhow::Result<TicketStats> {
+            Ok(TicketStats {
+                ticket_price: "0.000001 wxHOPR".parse().unwrap(),
+                winning_probability: WinningProbability::default(),
+            })
+        }
+
+        async fn max_fee_per_gas(&self) -> anyhow::Result<u128> {
+            Ok(1_000_000_000)
+        }
+
+        async fn compute_costs_to_start(&self) -> anyhow::Result<StartupCosts> {
+            Ok(StartupCosts {
+                fee_to_start: HoprBalance::zero(),
+                txs_to_start: 3,
+            })
+        }
+
+        async fn balances(&self) -> anyhow::Result<(HoprBalance, XDaiBalance)> {
+            let mut balances = self.balances.lock().unwrap();
+            if balances.len() > 1 {
+                Ok(balances.remove(0))
+            } else {
+                Ok(balances[0])
+            }
+        }
+
+        async fn withdraw_wxhopr(
+            &self,
+            _safe_address: Address,
+            amount: HoprBalance,
+        ) -> anyhow::Result<()> {
+            self.withdrawn.lock().unwrap().push(amount);
+            Ok(())
+        }
+    }
+
+    fn fast() -> OnboardingOptions {
+        OnboardingOptions {
+            poll_interval: Duration::from_millis(1),
+            funding_timeout: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn funded_node_deploys_safe_with_its_wxhopr() -> anyhow::Result<()> {
+        let chain = ScriptedChain::new(vec![rich()]);
+        let safe = onboard(
+            &chain,
+            Address::default(),
+            &IncentiveConfiguration::default(),
+            fast(),
+            |_| {},
+        )
+        .await?;
+
+        assert_eq!(safe.safe_address, deployment().safe_address);
+        assert_eq!(*chain.deployed_with.lock().unwrap(), vec![rich().0]);
+        assert!(chain.withdrawn.lock().unwrap().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn waits_for_funding_before_deploying() -> anyhow::Result<()> {
+        let chain = ScriptedChain::new(vec![broke(), broke(), rich()]);
+        let awaited = Mutex::new(0usize);
+        onboard(
+            &chain,
+            Address::default(),
+            &IncentiveConfiguration::default(),
+            fast(),
+            |event| {
+                if matches!(event, OnboardingEvent::AwaitingFunds { .. }) {
+                    *awaited.lock().unwrap() += 1;
+                }
+            },
+        )
+        .await?;
+
+        assert_eq!(*awaited.lock().unwrap(), 2);
+        assert_eq!(chain.deployed_with.lock().unwrap().len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_the_funding_timeout() {
+        let chain = ScriptedChain::new(vec![broke()]);
+        let result = onboard(
+            &chain,
+            Address::default(),
+            &IncentiveConfiguration::default(),
+            OnboardingOptions {
+                poll_interval: Duration::from_millis(1),
+                funding_timeout: Some(Duration::from_millis(5)),
+            },
+            |_| {},
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(chain.deployed_with.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn existing_safe_is_reused_and_node_wxhopr_swept() -> anyhow::Result<()> {
+        let chain = ScriptedChain::new(vec![rich()]).with_safe();
+        let events = Mutex::new(vec![]);
+        onboard(
+            &chain,
+            Address::default(),
+            &IncentiveConfiguration::default(),
+            fast(),
+            |event| events.lock().unwrap().push(event),
+        )
+        .await?;
+
+        assert!(chain.deployed_with.lock().unwrap().is_empty());
+        assert_eq!(*chain.withdrawn.lock().unwrap(), vec![rich().0]);
+        let events = events.lock().unwrap();
+        assert!(matches!(
+            events.first(),
+            Some(OnboardingEvent::NodeAddress(_))
+        ));
+        assert!(matches!(events.get(1), Some(OnboardingEvent::SafeFound(_))));
+        assert!(matches!(events.last(), Some(OnboardingEvent::Complete(_))));
+        Ok(())
+    }
+}
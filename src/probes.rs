@@ -0,0 +1,323 @@
+//! Unauthenticated health probes for the `edgli` binary, and the listener it serves HTTP on.
+//!
+//! [`health_router`] serves the `/healthz` and `/readyz` probes from a [`HealthMonitor`], plus
+//! the `/metrics` scrape under the `telemetry` feature. It is built in by default, apart from
+//! the admin API in [`crate::api`], so orchestrators can probe any node; it is kept separate
+//! from the node so it can be served before the node is built and on an address an
+//! orchestrator can reach. [`bind`] and [`ApiListen`] serve both routers.
+
+use std::fmt;
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::str::FromStr;
+
+#[cfg(feature = "telemetry")]
+use axum::response::{IntoResponse, Response};
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use futures::{FutureExt as _, future::BoxFuture};
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{EdgliError, Result};
+use crate::health::{HealthMonitor, HealthReport, Readiness};
+
+/// Where [`bind`] listens.
+///
+/// Parsed from `unix:<path>` for a Unix socket (Unix only), or from a socket address, which
+/// must be a loopback address. Constructing [`ApiListen::Tcp`] directly skips that check; only
+/// do so for routers without privileged routes, such as [`health_router`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ApiListen {
+    Tcp(SocketAddr),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl FromStr for ApiListen {
+    type Err = EdgliError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err(EdgliError::ConfigError(
+                    "the admin API socket path must not be empty".into(),
+                ));
+            }
+            #[cfg(unix)]
+            return Ok(Self::Unix(path.into()));
+            #[cfg(not(unix))]
+            return Err(EdgliError::ConfigError(
+                "Unix sockets are not supported on this platform".into(),
+            ));
+        }
+
+        let address: SocketAddr = s.parse().map_err(|e| {
+            EdgliError::ConfigError(format!(
+                "invalid admin API address '{s}' (expected `unix:<path>` or `<ip>:<port>`): {e}"
+            ))
+        })?;
+        if !address.ip().is_loopback() {
+            return Err(EdgliError::ConfigError(format!(
+                "the admin API only listens on loopback addresses, not '{address}'"
+            )));
+        }
+        Ok(Self::Tcp(address))
+    }
+}
+
+impl fmt::Display for ApiListen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(address) => write!(f, "{address}"),
+            #[cfg(unix)]
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// Binds `listen` and returns the future that serves `router` on it.
+///
+/// Binding happens before this returns, so an unusable address fails at startup rather than
+/// in a background task. A Unix socket is bound inside a fresh owner-only (0700) directory and
+/// restricted to its owner (0600) before it is moved into place, so no other local user can
+/// connect to it in between; a stale socket file left behind by a previous run is replaced.
+pub async fn bind(listen: &ApiListen, router: Router) -> Result<BoxFuture<'static, Result<()>>> {
+    match listen {
+        ApiListen::Tcp(address) => {
+            let listener = tokio::net::TcpListener::bind(address)
+                .await
+                .map_err(|e| EdgliError::OsError(format!("failed to bind '{address}': {e}")))?;
+            Ok(async move {
+                axum::serve(listener, router)
+                    .await
+                    .map_err(|e| EdgliError::OsError(e.to_string()))
+            }
+            .boxed())
+        }
+        #[cfg(unix)]
+        ApiListen::Unix(path) => {
+            use std::os::unix::fs::FileTypeExt;
+
+            if let Ok(metadata) = std::fs::symlink_metadata(path)
+                && metadata.file_type().is_socket()
+            {
+                let _ = std::fs::remove_file(path);
+            }
+            let listener = bind_private_unix_socket(path)?;
+            Ok(async move {
+                axum::serve(listener, router)
+                    .await
+                    .map_err(|e| EdgliError::OsError(e.to_string()))
+            }
+            .boxed())
+        }
+    }
+}
+
+/// Binds a Unix socket at `path` that only its owner can connect to.
+///
+/// The socket is created in a 0700 staging directory next to `path`, restricted to 0600 and
+/// then renamed into place; a bound socket keeps listening under its new name.
+#[cfg(unix)]
+fn bind_private_unix_socket(path: &std::path::Path) -> Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    let os_error = |action: &str, path: &std::path::Path, e: std::io::Error| {
+        EdgliError::OsError(format!("failed to {action} '{}': {e}", path.display()))
+    };
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => std::path::Path::new("."),
+    };
+    let staging = parent.join(format!(".edgli-api-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&staging)
+        .map_err(|e| os_error("create", &staging, e))?;
+
+    let staged = staging.join("api.sock");
+    let bound = tokio::net::UnixListener::bind(&staged)
+        .map_err(|e| os_error("bind", path, e))
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))
+                .map_err(|e| os_error("restrict", &staged, e))?;
+            std::fs::rename(&staged, path).map_err(|e| os_error("move the socket to", path, e))?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_dir_all(&staging);
+    bound
+}
+
+/// Body of the `/readyz` response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HealthDto {
+    pub ready: bool,
+    /// Why the node is not ready; empty when ready.
+    pub unmet: Vec<String>,
+    pub init_state: Option<String>,
+    pub node_state: Option<String>,
+    pub blokli_reachable: Option<bool>,
+    pub connected_peers: Option<usize>,
+    pub funded_open_channels: Option<usize>,
+}
+
+impl HealthDto {
+    fn new(report: &HealthReport, readiness: Readiness) -> Self {
+        Self {
+            ready: readiness.ready,
+            unmet: readiness.unmet,
+            init_state: report.init_state.map(|state| state.as_ref().to_string()),
+            node_state: report.node_state.map(|state| format!("{state:?}")),
+            blokli_reachable: report.blokli_reachable,
+            connected_peers: report.connected_peers,
+            funded_open_channels: report.funded_open_channels,
+        }
+    }
+}
+
+/// Body of the `/healthz` response.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LivenessDto {
+    pub alive: bool,
+    pub init_state: Option<String>,
+}
+
+/// Liveness: answers as long as the process serves requests, without querying the node.
+async fn healthz(State(monitor): State<HealthMonitor>) -> Json<LivenessDto> {
+    Json(LivenessDto {
+        alive: true,
+        init_state: monitor.init_state().map(|state| state.as_ref().to_string()),
+    })
+}
+
+/// Readiness: `200` when every [`crate::health::HealthThresholds`] is met, `503` otherwise.
+async fn readyz(State(monitor): State<HealthMonitor>) -> (StatusCode, Json<HealthDto>) {
+    let report = monitor.report().await;
+    let readiness = report.readiness(monitor.thresholds());
+    let status = if readiness.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(HealthDto::new(&report, readiness)))
+}
+
+/// Prometheus scrape: hopr-lib's and edgli's metrics in the text exposition format.
+#[cfg(feature = "telemetry")]
+async fn metrics() -> Response {
+    match hopr_lib::collect_hopr_metrics() {
+        Ok(text) => (
+            [(
+                axum::http::header::CONTENT_TYPE,
+                "text/plain; version=0.0.4; charset=utf-8",
+            )],
+            text,
+        )
+            .into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+/// Builds the `/healthz` and `/readyz` router, plus `/metrics` under the `telemetry`
+/// feature. No route requires a token.
+pub fn health_router(monitor: HealthMonitor) -> Router {
+    let router = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz));
+    #[cfg(feature = "telemetry")]
+    let router = router.route("/metrics", get(metrics));
+    router.with_state(monitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, response::Response};
+    use tower::ServiceExt as _;
+
+    use super::*;
+
+    fn get_request(uri: &str) -> axum::extract::Request {
+        axum::http::Request::builder()
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    async fn json<T: serde::de::DeserializeOwned>(response: Response) -> T {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn api_listen_parses_loopback_addresses() {
+        assert_eq!(
+            "127.0.0.1:3001".parse::<ApiListen>().unwrap(),
+            ApiListen::Tcp("127.0.0.1:3001".parse().unwrap())
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn api_listen_parses_unix_sockets() {
+        assert_eq!(
+            "unix:/run/edgli.sock".parse::<ApiListen>().unwrap(),
+            ApiListen::Unix("/run/edgli.sock".into())
+        );
+        assert_eq!(
+            "unix:/run/edgli.sock"
+                .parse::<ApiListen>()
+                .unwrap()
+                .to_string(),
+            "unix:/run/edgli.sock"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_sockets_are_bound_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("edgli-api-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("api.sock");
+
+        let _serve = bind(&ApiListen::Unix(path.clone()), Router::new())
+            .await
+            .unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // Only the socket is left behind, not the staging directory.
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        tokio::net::UnixStream::connect(&path).await.unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn api_listen_rejects_non_loopback_addresses() {
+        assert!("0.0.0.0:3001".parse::<ApiListen>().is_err());
+        assert!("192.0.2.1:3001".parse::<ApiListen>().is_err());
+        assert!("unix:".parse::<ApiListen>().is_err());
+    }
+
+    #[tokio::test]
+    async fn health_probes_need_no_token() {
+        let monitor = HealthMonitor::default();
+        let app = health_router(monitor.clone());
+
+        let response = app.clone().oneshot(get_request("/healthz")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let liveness: LivenessDto = json(response).await;
+        assert!(liveness.alive);
+
+        let response = app.clone().oneshot(get_request("/readyz")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let health: HealthDto = json(response).await;
+        assert!(!health.ready);
+        assert_eq!(health.init_state, None);
+    }
+}
@@ -0,0 +1,201 @@
+//! Classifies the configuration changes the `edgli` binary picks up on SIGHUP.
+//!
+//! [`ReloadPlan::new`] compares the running [`NodeConfig`] with a freshly loaded one, field by
+//! field, and sorts every changed setting into those that can be applied to the running node
+//! and those that only take effect after a restart. Settings are named by their dotted path in
+//! the configuration file, e.g. `protocol.mixer.min_delay`, so the report points operators at
+//! the exact line they changed.
+
+use std::collections::BTreeMap;
+
+use serde_yaml::Value;
+
+use crate::config::{EDGE_SECTION_KEY, NodeConfig};
+use crate::errors::{EdgliError, Result};
+
+/// Settings, by dotted path prefix, that a running node applies without a restart.
+///
+/// Everything hopr-lib reads is consumed once when the node is built, so only the `edge`
+/// section qualifies. That includes `protocol.mixer`: the mixer delays are handed to the
+/// transport as the node starts, and edgli keeps no handle through which to change them on
+/// the running transport, so a changed delay is reported as needing a restart.
+pub const LIVE_SETTINGS: &[&str] = &["edge.log_filter", "edge.strategy"];
+
+/// The changed settings between two configurations, split by how they can be applied.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReloadPlan {
+    /// Changed settings covered by [`LIVE_SETTINGS`], sorted by path.
+    pub live: Vec<String>,
+    /// Changed settings that keep their running value until the node restarts, sorted by
+    /// path.
+    pub restart_required: Vec<String>,
+}
+
+impl ReloadPlan {
+    /// Diffs `running` against `reloaded`.
+    ///
+    /// Both should hold the file's values; a setting overwritten in both after loading, e.g. from
+    /// an env var, never shows up as changed.
+    pub fn new(running: &NodeConfig, reloaded: &NodeConfig) -> Result<Self> {
+        let before = leaves(running)?;
+        let after = leaves(reloaded)?;
+
+        let mut plan = Self::default();
+        let paths = before
+            .keys()
+            .chain(after.keys().filter(|path| !before.contains_key(*path)));
+        for path in paths {
+            if before.get(path) == after.get(path) {
+                continue;
+            }
+            if is_live(path) {
+                plan.live.push(path.clone());
+            } else {
+                plan.restart_required.push(path.clone());
+            }
+        }
+        plan.live.sort();
+        plan.restart_required.sort();
+
+        Ok(plan)
+    }
+
+    /// Whether the configurations are identical.
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty() && self.restart_required.is_empty()
+    }
+
+    /// Whether any live setting at or below `prefix` changed.
+    pub fn changes(&self, prefix: &str) -> bool {
+        self.live.iter().any(|path| has_prefix(path, prefix))
+    }
+}
+
+fn has_prefix(path: &str, prefix: &str) -> bool {
+    path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+fn is_live(path: &str) -> bool {
+    LIVE_SETTINGS.iter().any(|prefix| has_prefix(path, prefix))
+}
+
+/// Flattens `cfg` into its leaf settings keyed by dotted path.
+///
+/// Sequences are leaves: a changed allowlist is one setting, not one per entry.
+fn leaves(cfg: &NodeConfig) -> Result<BTreeMap<String, Value>> {
+    let mut root = serde_yaml::to_value(&cfg.hopr)
+        .map_err(|e| EdgliError::SerializationError(e.to_string()))?;
+    // An empty section would otherwise surface as a leaf `edge: {}` that disappears as soon as
+    // any edge setting is added.
+    if !cfg.edge.is_empty() {
+        let edge = serde_yaml::to_value(&cfg.edge)
+            .map_err(|e| EdgliError::SerializationError(e.to_string()))?;
+        if let Some(mapping) = root.as_mapping_mut() {
+            mapping.insert(EDGE_SECTION_KEY.into(), edge);
+        }
+    }
+
+    let mut out = BTreeMap::new();
+    flatten(String::new(), root, &mut out);
+    Ok(out)
+}
+
+fn flatten(path: String, value: Value, out: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Mapping(mapping) if !mapping.is_empty() => {
+            for (key, value) in mapping {
+                let key = match &key {
+                    Value::String(key) => key.clone(),
+                    other => serde_yaml::to_string(other)
+                        .unwrap_or_default()
+                        .trim_end()
+                        .to_string(),
+                };
+                let child = if path.is_empty() {
+                    key
+                } else {
+                    format!("{path}.{key}")
+                };
+                flatten(child, value, out);
+            }
+        }
+        Value::Tagged(tagged) => flatten(path, tagged.value, out),
+        leaf => {
+            out.insert(path, leaf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use hopr_lib::config::HostConfig;
+
+    use super::*;
+    use crate::config::{StrategySection, edge_config};
+
+    fn running() -> NodeConfig {
+        edge_config(HostConfig::default())
+    }
+
+    #[test]
+    fn identical_configurations_produce_an_empty_plan() {
+        let plan = ReloadPlan::new(&running(), &running()).unwrap();
+        assert!(plan.is_empty(), "{plan:?}");
+    }
+
+    #[test]
+    fn edge_section_changes_are_live() {
+        let mut reloaded = running();
+        reloaded.edge.log_filter = Some("debug".into());
+        reloaded.edge.strategy = Some(StrategySection {
+            target_open_channels: Some(3),
+            ..Default::default()
+        });
+
+        let plan = ReloadPlan::new(&running(), &reloaded).unwrap();
+        assert_eq!(
+            plan.live,
+            vec!["edge.log_filter", "edge.strategy.target_open_channels"]
+        );
+        assert!(plan.restart_required.is_empty());
+        assert!(plan.changes("edge.log_filter"));
+        assert!(plan.changes("edge.strategy"));
+    }
+
+    #[test]
+    fn hopr_settings_require_a_restart() {
+        let mut reloaded = running();
+        reloaded.hopr.host.port += 1;
+        reloaded.hopr.protocol.mixer.min_delay = Duration::from_millis(5);
+
+        let plan = ReloadPlan::new(&running(), &reloaded).unwrap();
+        assert!(plan.live.is_empty());
+        assert_eq!(
+            plan.restart_required,
+            vec!["host.port", "protocol.mixer.min_delay"]
+        );
+        assert!(!plan.changes("edge.strategy"));
+    }
+
+    #[test]
+    fn removed_settings_are_reported() {
+        let mut before = running();
+        before.edge.log_filter = Some("debug".into());
+
+        let plan = ReloadPlan::new(&before, &running()).unwrap();
+        assert_eq!(plan.live, vec!["edge.log_filter"]);
+    }
+
+    #[test]
+    fn live_prefixes_match_whole_path_segments() {
+        assert!(is_live("edge.strategy"));
+        assert!(is_live("edge.strategy.channel_allowlist"));
+        assert!(!is_live("edge.strategy_extra"));
+        assert!(!is_live("protocol.mixer"));
+    }
+}
@@ -0,0 +1,355 @@
+//! Retrying Blokli requests that failed transiently.
+//!
+//! A [`RetryPolicy`] travels with the [`BlokliEndpointSet`](crate::BlokliEndpointSet) it was
+//! set on, so the node's startup and chain reads and the on-boarding interactor all retry the
+//! same way. Only reads are retried: a transaction whose submission failed may still have
+//! been broadcast, so resubmitting it could execute it twice. [`classify`] decides which
+//! failures are worth another attempt.
+
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// The kind of failure a Blokli request ended in, as far as it can be told from the error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum ErrorClass {
+    /// The connection could not be established or broke off.
+    Connection,
+    /// The request or connection timed out.
+    Timeout,
+    /// Blokli or a gateway in front of it answered with a 5xx status.
+    ServerError,
+    /// Blokli answered 429 Too Many Requests.
+    RateLimited,
+    /// Anything else, such as a rejected query or a chain-level error.
+    Other,
+}
+
+/// Classifies `error` by the first typed cause in its chain that tells: an I/O error or
+/// timeout by its kind, an HTTP failure by what the HTTP client reports and the status Blokli
+/// answered with. The Blokli client keeps the HTTP client's error as the source of its own.
+///
+/// Anything else, such as a rejected query, a reverted transaction or an error that is only
+/// described by its message, is [`ErrorClass::Other`] and not retried by default.
+pub fn classify(error: &anyhow::Error) -> ErrorClass {
+    error
+        .chain()
+        .find_map(classify_cause)
+        .unwrap_or(ErrorClass::Other)
+}
+
+fn classify_cause(cause: &(dyn std::error::Error + 'static)) -> Option<ErrorClass> {
+    #[cfg(feature = "blokli")]
+    if let Some(http) = cause.downcast_ref::<reqwest::Error>() {
+        return classify_http(http);
+    }
+    if let Some(io) = cause.downcast_ref::<std::io::Error>() {
+        use std::io::ErrorKind::*;
+        return match io.kind() {
+            TimedOut => Some(ErrorClass::Timeout),
+            ConnectionRefused | ConnectionReset | ConnectionAborted | NotConnected | BrokenPipe
+            | UnexpectedEof | HostUnreachable | NetworkUnreachable => Some(ErrorClass::Connection),
+            _ => None,
+        };
+    }
+    cause
+        .is::<tokio::time::error::Elapsed>()
+        .then_some(ErrorClass::Timeout)
+}
+
+/// An HTTP failure by the stage it failed at, or the status Blokli answered with. `None`
+/// leaves it to the error's own causes.
+#[cfg(feature = "blokli")]
+fn classify_http(error: &reqwest::Error) -> Option<ErrorClass> {
+    if error.is_timeout() {
+        return Some(ErrorClass::Timeout);
+    }
+    if error.is_connect() {
+        return Some(ErrorClass::Connection);
+    }
+    error.status().map(|status| {
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            ErrorClass::RateLimited
+        } else if status.is_server_error() {
+            ErrorClass::ServerError
+        } else {
+            ErrorClass::Other
+        }
+    })
+}
+
+/// How often and how patiently a failed Blokli read is retried.
+///
+/// The delay before retry `n` is `initial_delay * 2^(n-1)`, capped at `max_delay`, minus a
+/// random share of up to `jitter` of it so that clients failing together do not retry in
+/// lockstep.
+#[derive(Clone, Debug, PartialEq, smart_default::SmartDefault)]
+pub struct RetryPolicy {
+    /// Attempts in total, the first included; `1` disables retrying. Default: 3.
+    #[default(3)]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry. Default: 500 ms.
+    #[default(Duration::from_millis(500))]
+    pub initial_delay: Duration,
+
+    /// Upper bound on the delay between attempts. Default: 10 s.
+    #[default(Duration::from_secs(10))]
+    pub max_delay: Duration,
+
+    /// Share of each delay, between 0 and 1, that is randomly taken off. Default: 0.5.
+    #[default(0.5)]
+    pub jitter: f64,
+
+    /// The failures that are retried. Default: all but [`ErrorClass::Other`].
+    #[default(vec![ErrorClass::Connection, ErrorClass::Timeout, ErrorClass::ServerError, ErrorClass::RateLimited])]
+    pub retry_on: Vec<ErrorClass>,
+}
+
+impl RetryPolicy {
+    /// A policy making a single attempt.
+    pub fn never() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Whether `error` is worth another attempt under this policy.
+    pub fn is_retryable(&self, error: &anyhow::Error) -> bool {
+        self.retry_on.contains(&classify(error))
+    }
+
+    /// The longest delay before retry `retry` (at least 1), before jitter is taken off.
+    pub fn max_delay_before(&self, retry: u32) -> Duration {
+        let doublings = retry.saturating_sub(1).min(31);
+        self.initial_delay
+            .saturating_mul(1u32 << doublings)
+            .min(self.max_delay)
+    }
+
+    fn delay_before(&self, retry: u32) -> Duration {
+        let delay = self.max_delay_before(retry);
+        let random = std::collections::hash_map::RandomState::new()
+            .build_hasher()
+            .finish() as f64
+            / u64::MAX as f64;
+        delay.mul_f64(1.0 - self.jitter.clamp(0.0, 1.0) * random)
+    }
+
+    /// Runs `request` until it succeeds, fails with an error that is not retryable, or
+    /// `max_attempts` are used up. Returns the last error.
+    pub async fn retry<T, F, Fut>(
+        &self,
+        operation: &'static str,
+        mut request: F,
+    ) -> anyhow::Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = anyhow::Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match request().await {
+                Ok(value) => return Ok(value),
+                Err(error) if attempt < self.max_attempts && self.is_retryable(&error) => {
+                    let delay = self.delay_before(attempt);
+                    tracing::warn!(
+                        operation,
+                        attempt,
+                        class = %classify(&error),
+                        ?delay,
+                        error = format!("{error:#}"),
+                        "Blokli request failed, retrying"
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    fn fast(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            initial_delay: Duration::from_millis(1),
+            ..Default::default()
+        }
+    }
+
+    fn io(kind: std::io::ErrorKind) -> anyhow::Error {
+        anyhow::Error::from(std::io::Error::from(kind))
+    }
+
+    #[tokio::test]
+    async fn classify_recognises_transient_failures() {
+        assert_eq!(
+            classify(&io(std::io::ErrorKind::ConnectionReset)),
+            ErrorClass::Connection
+        );
+        assert_eq!(
+            classify(&io(std::io::ErrorKind::TimedOut).context("querying chain info")),
+            ErrorClass::Timeout
+        );
+        let elapsed = tokio::time::timeout(Duration::ZERO, std::future::pending::<()>())
+            .await
+            .unwrap_err();
+        assert_eq!(classify(&elapsed.into()), ErrorClass::Timeout);
+    }
+
+    #[test]
+    fn classify_does_not_guess_from_messages() {
+        for message in [
+            "HTTP status server error (502 Bad Gateway)",
+            "429 Too Many Requests",
+            "connection refused",
+            "request timed out",
+            "safe not found",
+        ] {
+            assert_eq!(classify(&anyhow::anyhow!(message)), ErrorClass::Other);
+        }
+        assert_eq!(
+            classify(&io(std::io::ErrorKind::InvalidData)),
+            ErrorClass::Other
+        );
+    }
+
+    /// Serves one connection on a local port with `response`, or leaves it unanswered.
+    #[cfg(feature = "blokli")]
+    async fn serve_once(response: Option<&'static str>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).await;
+            match response {
+                Some(response) => stream.write_all(response.as_bytes()).await.unwrap(),
+                None => std::future::pending().await,
+            }
+        });
+        url
+    }
+
+    #[cfg(feature = "blokli")]
+    async fn http_error(response: Option<&'static str>) -> anyhow::Error {
+        let url = serve_once(response).await;
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(200))
+            .build()
+            .unwrap();
+        let error = match client.get(url).send().await {
+            Ok(response) => response.error_for_status().unwrap_err(),
+            Err(error) => error,
+        };
+        anyhow::Error::from(error).context("querying Blokli")
+    }
+
+    #[cfg(feature = "blokli")]
+    #[tokio::test]
+    async fn classify_http_failures_by_status_and_stage() {
+        assert_eq!(
+            classify(
+                &http_error(Some(
+                    "HTTP/1.1 502 Bad Gateway\r\ncontent-length: 0\r\n\r\n"
+                ))
+                .await
+            ),
+            ErrorClass::ServerError
+        );
+        assert_eq!(
+            classify(
+                &http_error(Some(
+                    "HTTP/1.1 429 Too Many Requests\r\ncontent-length: 0\r\n\r\n"
+                ))
+                .await
+            ),
+            ErrorClass::RateLimited
+        );
+        assert_eq!(
+            classify(
+                &http_error(Some(
+                    "HTTP/1.1 400 Bad Request\r\ncontent-length: 0\r\n\r\n"
+                ))
+                .await
+            ),
+            ErrorClass::Other
+        );
+        assert_eq!(classify(&http_error(None).await), ErrorClass::Timeout);
+
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", closed.local_addr().unwrap());
+        drop(closed);
+        let refused = reqwest::get(url).await.unwrap_err();
+        assert_eq!(classify(&refused.into()), ErrorClass::Connection);
+    }
+
+    #[test]
+    fn delays_double_up_to_the_cap_and_jitter_only_shortens_them() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.max_delay_before(1), Duration::from_millis(500));
+        assert_eq!(policy.max_delay_before(3), Duration::from_secs(2));
+        assert_eq!(policy.max_delay_before(10), Duration::from_secs(10));
+        for retry in 1..6 {
+            let delay = policy.delay_before(retry);
+            assert!(delay <= policy.max_delay_before(retry));
+            assert!(delay >= policy.max_delay_before(retry).mul_f64(0.5));
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_transient_failures_until_success() {
+        let calls = AtomicU32::new(0);
+        let value = fast(3)
+            .retry("test", || async {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    return Err(io(std::io::ErrorKind::ConnectionReset));
+                }
+                Ok(7)
+            })
+            .await
+            .unwrap();
+        assert_eq!(value, 7);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let result: anyhow::Result<()> = fast(2)
+            .retry("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(io(std::io::ErrorKind::ConnectionRefused))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_permanent_failures() {
+        let calls = AtomicU32::new(0);
+        let result: anyhow::Result<()> = fast(5)
+            .retry("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                anyhow::bail!("invalid query")
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let never = RetryPolicy::never();
+        assert!(never.is_retryable(&io(std::io::ErrorKind::ConnectionRefused)));
+        assert_eq!(never.max_attempts, 1);
+    }
+}
@@ -0,0 +1,472 @@
+//! Bookkeeping for the sessions opened through [`crate::EdgeNodeApi::open_session`].
+//!
+//! hopr-lib does not report which sessions are open, so [`SessionRegistry`] wraps every
+//! session it hands out in a [`TrackedSession`] that counts its traffic and unregisters
+//! itself when dropped. [`SessionRegistry::close`] takes the underlying session away from
+//! its handle and closes it right away, whether or not the handle is used again; the handle
+//! then reports end-of-stream and a broken pipe. Stub [`crate::EdgeNodeApi`]
+//! implementations can track in-memory streams the same way.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, ready};
+use std::time::Instant;
+
+use futures::future::BoxFuture;
+use futures::io::{AsyncRead, AsyncWrite, AsyncWriteExt as _};
+use futures::task::AtomicWaker;
+use hopr_lib::api::types::primitive::prelude::Address;
+
+/// Identifies a session among those a node opened.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeSessionId(pub u64);
+
+impl std::fmt::Display for EdgeSessionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "session-{}", self.0)
+    }
+}
+
+/// An open session: a byte stream to the destination through the mixnet.
+pub trait EdgeSession: AsyncRead + AsyncWrite + Send + Unpin {
+    /// The id [`crate::EdgeNodeApi::sessions`] lists the session under.
+    fn id(&self) -> EdgeSessionId;
+}
+
+/// An open session as [`crate::EdgeNodeApi::sessions`] lists it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionInfo {
+    pub id: EdgeSessionId,
+    /// The on-chain address of the session's exit node.
+    pub destination: Address,
+    pub opened_at: Instant,
+    /// Bytes written into the session.
+    pub bytes_sent: u64,
+    /// Bytes read from the session.
+    pub bytes_received: u64,
+}
+
+/// Takes a session away from its handle and releases its guard, returning the future that
+/// closes it.
+struct Closer(Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>);
+
+impl std::fmt::Debug for Closer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Closer")
+    }
+}
+
+#[derive(Debug)]
+struct SessionEntry {
+    id: EdgeSessionId,
+    destination: Address,
+    opened_at: Instant,
+    sent: AtomicU64,
+    received: AtomicU64,
+    /// Taken by the first of [`SessionRegistry::close`] and the handle's drop.
+    closer: Mutex<Option<Closer>>,
+    /// Wakes a read left pending on a session that was taken away.
+    read_waker: AtomicWaker,
+    /// Wakes a write, flush or close left pending on a session that was taken away; apart
+    /// from the reader's, as the two halves may be polled from different tasks.
+    write_waker: AtomicWaker,
+}
+
+impl SessionEntry {
+    fn info(&self) -> SessionInfo {
+        SessionInfo {
+            id: self.id,
+            destination: self.destination,
+            opened_at: self.opened_at,
+            bytes_sent: self.sent.load(Ordering::Relaxed),
+            bytes_received: self.received.load(Ordering::Relaxed),
+        }
+    }
+
+    fn take_closer(&self) -> Option<Closer> {
+        self.closer.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    next_id: AtomicU64,
+    sessions: Mutex<HashMap<EdgeSessionId, Arc<SessionEntry>>>,
+}
+
+impl Registry {
+    fn sessions(&self) -> std::sync::MutexGuard<'_, HashMap<EdgeSessionId, Arc<SessionEntry>>> {
+        self.sessions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The open sessions of a node. Clones share them.
+#[derive(Clone, Debug, Default)]
+pub struct SessionRegistry {
+    inner: Arc<Registry>,
+}
+
+impl SessionRegistry {
+    /// Tracks `session` to `destination` until the returned handle is dropped.
+    ///
+    /// `guard` is dropped with the handle or when [`Self::close`] closes the session,
+    /// whichever comes first; pass a [`crate::shutdown::SessionLease`] to keep the node's
+    /// shutdown waiting for the session.
+    pub fn register<S>(
+        &self,
+        destination: Address,
+        session: S,
+        guard: Option<Box<dyn Send>>,
+    ) -> TrackedSession<S>
+    where
+        S: AsyncWrite + Send + Unpin + 'static,
+    {
+        let inner = Arc::new(Mutex::new(Some(session)));
+        let closer = Closer(Box::new({
+            let inner = inner.clone();
+            move || {
+                drop(guard);
+                let session = inner.lock().unwrap_or_else(|e| e.into_inner()).take();
+                Box::pin(async move {
+                    if let Some(mut session) = session
+                        && let Err(error) = session.close().await
+                    {
+                        tracing::debug!(%error, "closing the session failed");
+                    }
+                })
+            }
+        }));
+        let entry = Arc::new(SessionEntry {
+            id: EdgeSessionId(self.inner.next_id.fetch_add(1, Ordering::Relaxed)),
+            destination,
+            opened_at: Instant::now(),
+            sent: AtomicU64::new(0),
+            received: AtomicU64::new(0),
+            closer: Mutex::new(Some(closer)),
+            read_waker: AtomicWaker::new(),
+            write_waker: AtomicWaker::new(),
+        });
+        self.inner.sessions().insert(entry.id, entry.clone());
+        TrackedSession {
+            inner,
+            entry,
+            registry: self.inner.clone(),
+        }
+    }
+
+    /// The open sessions, oldest first.
+    pub fn list(&self) -> Vec<SessionInfo> {
+        let mut sessions: Vec<_> = self.inner.sessions().values().map(|e| e.info()).collect();
+        sessions.sort_by_key(|s| s.id);
+        sessions
+    }
+
+    /// Closes every open session and returns how many there were.
+    pub fn close_all(&self) -> usize {
+        let ids: Vec<_> = self.inner.sessions().keys().copied().collect();
+        ids.into_iter().filter(|&id| self.close(id)).count()
+    }
+
+    /// Closes the session `id` and releases its guard; `false` if no such session is open.
+    ///
+    /// The session is closed from a task of its own on the current Tokio runtime. Without
+    /// one it is dropped instead, which ends it without waiting for the close to complete.
+    pub fn close(&self, id: EdgeSessionId) -> bool {
+        let Some(entry) = self.inner.sessions().remove(&id) else {
+            return false;
+        };
+        if let Some(Closer(close)) = entry.take_closer() {
+            spawn_close(close());
+        }
+        entry.read_waker.wake();
+        entry.write_waker.wake();
+        true
+    }
+}
+
+/// Runs `closing` on the current Tokio runtime; without one it is dropped, and the session
+/// with it.
+#[cfg_attr(not(feature = "runtime-tokio"), allow(unused_variables))]
+fn spawn_close(closing: BoxFuture<'static, ()>) {
+    #[cfg(feature = "runtime-tokio")]
+    if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+        runtime.spawn(closing);
+    }
+}
+
+/// A session tracked by a [`SessionRegistry`].
+pub struct TrackedSession<S> {
+    /// `None` once [`SessionRegistry::close`] took the session away to close it.
+    inner: Arc<Mutex<Option<S>>>,
+    entry: Arc<SessionEntry>,
+    registry: Arc<Registry>,
+}
+
+impl<S> TrackedSession<S> {
+    /// The inner session, registering the task with `waker` to be woken when it is taken
+    /// away.
+    fn session(&self, waker: &AtomicWaker, cx: &Context<'_>) -> MutexGuard<'_, Option<S>> {
+        waker.register(cx.waker());
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<S> Drop for TrackedSession<S> {
+    fn drop(&mut self) {
+        self.registry.sessions().remove(&self.entry.id);
+        // Releases the guard; the session itself is dropped with `inner`.
+        drop(self.entry.take_closer());
+    }
+}
+
+impl<S> AsyncRead for TrackedSession<S>
+where
+    S: AsyncRead + Unpin,
+{
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let mut session = self.session(&self.entry.read_waker, cx);
+        let Some(inner) = session.as_mut() else {
+            return Poll::Ready(Ok(0));
+        };
+        let read = ready!(Pin::new(inner).poll_read(cx, buf))?;
+        self.entry
+            .received
+            .fetch_add(read as u64, Ordering::Relaxed);
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl<S> AsyncWrite for TrackedSession<S>
+where
+    S: AsyncWrite + Unpin,
+{
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let mut session = self.session(&self.entry.write_waker, cx);
+        let Some(inner) = session.as_mut() else {
+            return Poll::Ready(Err(std::io::ErrorKind::BrokenPipe.into()));
+        };
+        let written = ready!(Pin::new(inner).poll_write(cx, buf))?;
+        self.entry.sent.fetch_add(written as u64, Ordering::Relaxed);
+        Poll::Ready(Ok(written))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.session(&self.entry.write_waker, cx).as_mut() {
+            Some(inner) => Pin::new(inner).poll_flush(cx),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+
+    /// Already closing once [`SessionRegistry::close`] took the session away.
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        match self.session(&self.entry.write_waker, cx).as_mut() {
+            Some(inner) => Pin::new(inner).poll_close(cx),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+impl<S> EdgeSession for TrackedSession<S>
+where
+    S: AsyncRead + AsyncWrite + Send + Unpin,
+{
+    fn id(&self) -> EdgeSessionId {
+        self.entry.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::io::{AsyncReadExt as _, AsyncWriteExt as _, Cursor};
+
+    use super::*;
+
+    const EXIT: [u8; 20] = [0x66; 20];
+
+    #[tokio::test]
+    async fn sessions_count_their_traffic_until_dropped() {
+        let registry = SessionRegistry::default();
+        let mut session = registry.register(EXIT.into(), Cursor::new(vec![0u8; 4]), None);
+
+        let mut buf = [0u8; 3];
+        assert_eq!(session.read(&mut buf).await.unwrap(), 3);
+        session.write_all(b"hello").await.unwrap();
+
+        let listed = registry.list();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, session.id());
+        assert_eq!(listed[0].destination, EXIT.into());
+        assert_eq!(listed[0].bytes_received, 3);
+        assert_eq!(listed[0].bytes_sent, 5);
+
+        drop(session);
+        assert!(registry.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn closed_sessions_end_and_refuse_writes() {
+        let registry = SessionRegistry::default();
+        let mut first = registry.register(EXIT.into(), Cursor::new(vec![1u8; 8]), None);
+        let second = registry.register(EXIT.into(), Cursor::new(vec![]), None);
+        assert_ne!(first.id(), second.id());
+
+        assert!(registry.close(first.id()));
+        assert!(!registry.close(first.id()));
+        assert_eq!(
+            registry.list().iter().map(|s| s.id).collect::<Vec<_>>(),
+            [second.id()]
+        );
+
+        let mut buf = [0u8; 8];
+        assert_eq!(first.read(&mut buf).await.unwrap(), 0);
+        assert_eq!(
+            first.write(b"late").await.unwrap_err().kind(),
+            std::io::ErrorKind::BrokenPipe
+        );
+    }
+
+    /// Never has anything to read, and records when it is closed.
+    struct Idle(Arc<std::sync::atomic::AtomicBool>);
+
+    impl AsyncRead for Idle {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Pending
+        }
+    }
+
+    impl AsyncWrite for Idle {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            self.0.store(true, Ordering::SeqCst);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[cfg(feature = "runtime-tokio")]
+    #[tokio::test]
+    async fn closing_a_session_closes_it_and_releases_its_guard_at_once() {
+        let registry = SessionRegistry::default();
+        let closed = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let guard = Arc::new(());
+        let mut session = registry.register(
+            EXIT.into(),
+            Idle(closed.clone()),
+            Some(Box::new(guard.clone())),
+        );
+        let id = session.id();
+        let reader = tokio::spawn(async move {
+            let mut buf = [0u8; 1];
+            let read = session.read(&mut buf).await.unwrap();
+            (read, session)
+        });
+        tokio::task::yield_now().await;
+
+        assert!(registry.close(id));
+        assert_eq!(Arc::strong_count(&guard), 1);
+        // The pending read ends, though the handle is still alive.
+        let (read, _session) = reader.await.unwrap();
+        assert_eq!(read, 0);
+        tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            while !closed.load(Ordering::SeqCst) {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("the session is closed without its handle being used");
+    }
+
+    /// Neither reads nor writes anything.
+    struct Stalled;
+
+    impl AsyncRead for Stalled {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Pending
+        }
+    }
+
+    impl AsyncWrite for Stalled {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            _buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            Poll::Pending
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Pending
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+            Poll::Pending
+        }
+    }
+
+    #[tokio::test]
+    async fn closing_wakes_a_pending_read_and_write_from_different_tasks() {
+        let registry = SessionRegistry::default();
+        let session = registry.register(EXIT.into(), Stalled, None);
+        let id = session.id();
+        let (mut reading, mut writing) = session.split();
+        let reader = tokio::spawn(async move {
+            let mut buf = [0u8; 1];
+            reading.read(&mut buf).await.unwrap()
+        });
+        let writer = tokio::spawn(async move { writing.write(b"late").await.unwrap_err().kind() });
+        for _ in 0..10 {
+            tokio::task::yield_now().await;
+        }
+
+        assert!(registry.close(id));
+        let (read, written) = tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            (reader.await.unwrap(), writer.await.unwrap())
+        })
+        .await
+        .expect("both halves are woken");
+        assert_eq!(read, 0);
+        assert_eq!(written, std::io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn the_guard_lives_as_long_as_the_session() {
+        let guard = Arc::new(());
+        let registry = SessionRegistry::default();
+        let session = registry.register(
+            EXIT.into(),
+            Cursor::new(vec![]),
+            Some(Box::new(guard.clone())),
+        );
+        assert_eq!(Arc::strong_count(&guard), 2);
+        drop(session);
+        assert_eq!(Arc::strong_count(&guard), 1);
+    }
+}
@@ -0,0 +1,367 @@
+//! Orderly teardown of an [`Edgli`](crate::Edgli) node.
+//!
+//! Dropping the node aborts all of its background tasks at once, which cuts sessions
+//! mid-transfer. [`Edgli::shutdown`](crate::Edgli::shutdown) instead goes through the stages
+//! below and reports what each achieved in a [`ShutdownSummary`]:
+//!
+//! 1. new [`SessionLease`]s are refused,
+//! 2. leased sessions get until the timeout to close,
+//! 3. the sessions opened through [`crate::EdgeNodeApi::open_session`] that are still open
+//!    are closed,
+//! 4. the strategy reactors and balance monitors started from the node are aborted,
+//! 5. the registered telemetry exporters are flushed.
+//!
+//! hopr-lib does not report which sessions are open, so only sessions that hold a
+//! [`SessionLease`] are waited for, and only those in the node's [`SessionRegistry`] are
+//! closed. Any other session ends when the last clone of the node is dropped.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use futures::future::AbortHandle;
+use tokio::sync::watch;
+
+use crate::errors::{EdgliError, Result};
+use crate::session::SessionRegistry;
+
+/// Keeps the node's shutdown waiting for a session until dropped.
+///
+/// Obtain one from [`Edgli::session_lease`](crate::Edgli::session_lease) before opening a
+/// session and drop it once the session is closed.
+#[derive(Debug)]
+pub struct SessionLease {
+    sessions: watch::Sender<usize>,
+}
+
+impl Drop for SessionLease {
+    fn drop(&mut self) {
+        self.sessions.send_modify(|n| *n = n.saturating_sub(1));
+    }
+}
+
+/// What [`Edgli::shutdown`](crate::Edgli::shutdown) did.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShutdownSummary {
+    /// Leased sessions that closed before the timeout.
+    pub sessions_drained: usize,
+    /// Leased sessions still open when the timeout expired.
+    pub sessions_abandoned: usize,
+    /// Sessions opened through [`crate::EdgeNodeApi::open_session`] that were still open
+    /// when the timeout expired and got closed.
+    pub sessions_closed: usize,
+    /// Strategy reactors that were still running and got aborted.
+    pub reactors_stopped: usize,
+    /// Whether every registered telemetry exporter flushed successfully; `true` when none
+    /// is registered.
+    pub telemetry_flushed: bool,
+    /// Time the whole shutdown took.
+    pub elapsed: Duration,
+}
+
+impl std::fmt::Display for ShutdownSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} session(s) drained, {} abandoned, {} closed, {} reactor(s) stopped, telemetry {} \
+             in {:?}",
+            self.sessions_drained,
+            self.sessions_abandoned,
+            self.sessions_closed,
+            self.reactors_stopped,
+            if self.telemetry_flushed {
+                "flushed"
+            } else {
+                "not flushed"
+            },
+            self.elapsed
+        )
+    }
+}
+
+/// Why strategy reactors are held paused; they run while no reason holds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ReactorPause {
+    /// A balance monitor found the node's xDAI low.
+    pub(crate) low_xdai: bool,
+    /// The chain's gas price is above the node's cap.
+    pub(crate) gas_above_cap: bool,
+}
+
+impl ReactorPause {
+    /// Whether any reason holds.
+    pub(crate) fn any(&self) -> bool {
+        self.low_xdai || self.gas_above_cap
+    }
+}
+
+/// A reason for holding reactors paused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PauseReason {
+    LowXdai,
+    GasAboveCap,
+}
+
+/// Shutdown bookkeeping shared by all clones of a node.
+#[derive(Debug)]
+pub(crate) struct Lifecycle {
+    shutting_down: AtomicBool,
+    sessions: watch::Sender<usize>,
+    reactors: Mutex<Vec<AbortHandle>>,
+    /// Background tasks other than reactors, such as balance monitors.
+    tasks: Mutex<Vec<AbortHandle>>,
+    /// Why reactors are held paused, set by a balance monitor and the gas cap watch.
+    reactors_paused: watch::Sender<ReactorPause>,
+    #[cfg(feature = "telemetry")]
+    tracer_providers: Mutex<Vec<opentelemetry_sdk::trace::SdkTracerProvider>>,
+}
+
+impl Default for Lifecycle {
+    fn default() -> Self {
+        Self {
+            shutting_down: AtomicBool::new(false),
+            sessions: watch::Sender::new(0),
+            reactors: Mutex::default(),
+            tasks: Mutex::default(),
+            reactors_paused: watch::Sender::default(),
+            #[cfg(feature = "telemetry")]
+            tracer_providers: Mutex::default(),
+        }
+    }
+}
+
+impl Lifecycle {
+    pub(crate) fn session_lease(&self) -> Result<SessionLease> {
+        if self.shutting_down.load(Ordering::Acquire) {
+            return Err(EdgliError::ShuttingDown);
+        }
+        self.sessions.send_modify(|n| *n += 1);
+        Ok(SessionLease {
+            sessions: self.sessions.clone(),
+        })
+    }
+
+    pub(crate) fn open_sessions(&self) -> usize {
+        *self.sessions.borrow()
+    }
+
+    /// Registers a reactor so that shutdown aborts it; handles already aborted are pruned.
+    pub(crate) fn register_reactor(&self, handle: AbortHandle) {
+        let mut reactors = self.reactors.lock().unwrap_or_else(|e| e.into_inner());
+        reactors.retain(|h| !h.is_aborted());
+        reactors.push(handle);
+    }
+
+    /// Registers a background task so that shutdown aborts it.
+    pub(crate) fn register_task(&self, handle: AbortHandle) {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks.retain(|h| !h.is_aborted());
+        tasks.push(handle);
+    }
+
+    /// Holds reactors paused for `reason`, or lifts that reason; they resume once no reason
+    /// holds.
+    pub(crate) fn pause_reactors(&self, reason: PauseReason, paused: bool) {
+        self.reactors_paused.send_if_modified(|p| {
+            let held = match reason {
+                PauseReason::LowXdai => &mut p.low_xdai,
+                PauseReason::GasAboveCap => &mut p.gas_above_cap,
+            };
+            std::mem::replace(held, paused) != paused
+        });
+    }
+
+    /// Follows why reactors are held paused.
+    pub(crate) fn reactors_paused(&self) -> watch::Receiver<ReactorPause> {
+        self.reactors_paused.subscribe()
+    }
+
+    #[cfg(feature = "telemetry")]
+    pub(crate) fn register_tracer_provider(
+        &self,
+        provider: opentelemetry_sdk::trace::SdkTracerProvider,
+    ) {
+        self.tracer_providers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(provider);
+    }
+
+    /// Shuts down the node whose sessions `registry` tracks.
+    pub(crate) async fn shutdown(
+        &self,
+        timeout: Duration,
+        registry: &SessionRegistry,
+    ) -> ShutdownSummary {
+        let started = Instant::now();
+        self.shutting_down.store(true, Ordering::Release);
+
+        let open = self.open_sessions();
+        let mut sessions = self.sessions.subscribe();
+        // The sender lives in `self`, so `wait_for` can only end by the count reaching zero.
+        let _ = tokio::time::timeout(timeout, sessions.wait_for(|n| *n == 0)).await;
+        let abandoned = self.open_sessions();
+        let sessions_closed = registry.close_all();
+
+        let reactors =
+            std::mem::take(&mut *self.reactors.lock().unwrap_or_else(|e| e.into_inner()));
+        let reactors_stopped = reactors.iter().filter(|h| !h.is_aborted()).count();
+        reactors.iter().for_each(AbortHandle::abort);
+        std::mem::take(&mut *self.tasks.lock().unwrap_or_else(|e| e.into_inner()))
+            .iter()
+            .for_each(AbortHandle::abort);
+
+        ShutdownSummary {
+            sessions_drained: open.saturating_sub(abandoned),
+            sessions_abandoned: abandoned,
+            sessions_closed,
+            reactors_stopped,
+            telemetry_flushed: self.flush_telemetry().await,
+            elapsed: started.elapsed(),
+        }
+    }
+
+    #[cfg(feature = "telemetry")]
+    async fn flush_telemetry(&self) -> bool {
+        let providers = std::mem::take(
+            &mut *self
+                .tracer_providers
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()),
+        );
+        // Flushing blocks on the exporter, so keep it off the async workers.
+        tokio::task::spawn_blocking(move || {
+            providers
+                .iter()
+                .fold(true, |flushed, provider| match provider.force_flush() {
+                    Ok(()) => flushed,
+                    Err(error) => {
+                        tracing::warn!(%error, "failed to flush the tracer provider");
+                        false
+                    }
+                })
+        })
+        .await
+        .unwrap_or(false)
+    }
+
+    #[cfg(not(feature = "telemetry"))]
+    async fn flush_telemetry(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reactors_resume_once_every_pause_reason_is_lifted() {
+        let lifecycle = Lifecycle::default();
+        let paused = lifecycle.reactors_paused();
+        assert!(!paused.borrow().any());
+
+        lifecycle.pause_reactors(PauseReason::LowXdai, true);
+        lifecycle.pause_reactors(PauseReason::GasAboveCap, true);
+        lifecycle.pause_reactors(PauseReason::LowXdai, false);
+        assert!(paused.borrow().any());
+        assert!(paused.borrow().gas_above_cap);
+
+        lifecycle.pause_reactors(PauseReason::GasAboveCap, false);
+        assert!(!paused.borrow().any());
+    }
+
+    #[tokio::test]
+    async fn shutdown_waits_for_leased_sessions() {
+        let lifecycle = Lifecycle::default();
+        let lease = lifecycle.session_lease().unwrap();
+        assert_eq!(lifecycle.open_sessions(), 1);
+
+        let closer = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(lease);
+        });
+        let summary = lifecycle
+            .shutdown(Duration::from_secs(5), &SessionRegistry::default())
+            .await;
+        closer.await.unwrap();
+
+        assert_eq!(summary.sessions_drained, 1);
+        assert_eq!(summary.sessions_abandoned, 0);
+        assert!(summary.telemetry_flushed);
+    }
+
+    #[tokio::test]
+    async fn shutdown_gives_up_on_sessions_after_the_timeout() {
+        let lifecycle = Lifecycle::default();
+        let _lease = lifecycle.session_lease().unwrap();
+
+        let summary = lifecycle
+            .shutdown(Duration::from_millis(10), &SessionRegistry::default())
+            .await;
+        assert_eq!(summary.sessions_drained, 0);
+        assert_eq!(summary.sessions_abandoned, 1);
+    }
+
+    #[tokio::test]
+    async fn shutdown_closes_the_sessions_still_open_after_the_timeout() {
+        let lifecycle = Lifecycle::default();
+        let registry = SessionRegistry::default();
+        let _session = registry.register(
+            [0x66u8; 20].into(),
+            futures::io::Cursor::new(vec![]),
+            Some(Box::new(lifecycle.session_lease().unwrap())),
+        );
+
+        let summary = lifecycle
+            .shutdown(Duration::from_millis(10), &registry)
+            .await;
+        assert_eq!(summary.sessions_abandoned, 1);
+        assert_eq!(summary.sessions_closed, 1);
+        assert!(registry.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn shutdown_refuses_new_sessions() {
+        let lifecycle = Lifecycle::default();
+        lifecycle
+            .shutdown(Duration::ZERO, &SessionRegistry::default())
+            .await;
+        assert!(matches!(
+            lifecycle.session_lease(),
+            Err(EdgliError::ShuttingDown)
+        ));
+    }
+
+    #[tokio::test]
+    async fn shutdown_aborts_running_reactors() {
+        let lifecycle = Lifecycle::default();
+        let (running, handle) = futures::future::abortable(futures::future::pending::<()>());
+        let task = tokio::spawn(running);
+        lifecycle.register_reactor(handle);
+
+        let (_, stopped) = futures::future::abortable(futures::future::pending::<()>());
+        stopped.abort();
+        lifecycle.register_reactor(stopped);
+
+        let summary = lifecycle
+            .shutdown(Duration::ZERO, &SessionRegistry::default())
+            .await;
+        assert_eq!(summary.reactors_stopped, 1);
+        assert!(task.await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn shutdown_aborts_background_tasks_without_counting_them_as_reactors() {
+        let lifecycle = Lifecycle::default();
+        let (running, handle) = futures::future::abortable(futures::future::pending::<()>());
+        let task = tokio::spawn(running);
+        lifecycle.register_task(handle);
+
+        let summary = lifecycle
+            .shutdown(Duration::ZERO, &SessionRegistry::default())
+            .await;
+        assert_eq!(summary.reactors_stopped, 0);
+        assert!(task.await.unwrap().is_err());
+    }
+}
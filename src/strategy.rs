@@ -1,4 +1,5 @@
 use std::collections::HashSet;
+use std::time::Duration;
 
 use bytesize::ByteSize;
 use hopr_lib::api::node::PacketTransport;
@@ -11,6 +12,8 @@ pub use hopr_strategy::channel_lifecycle::{
     ResolvedFunding, SelectorProfile,
 };
 
+use crate::sweep::SweepConfig;
+
 /// Paid downstream relay hops a ticket's face value covers.
 ///
 /// Fixed at the protocol's maximum path length rather than the single hop an edge node
@@ -67,6 +70,8 @@ use hopr_lib::api::chain::{AccountSelector, ChainReadAccountOperations, ChainVal
 /// Subset of strategies relevant to an edge node.
 pub enum EdgeStrategyKind {
     ChannelLifecycle(ChannelLifecycleConfig),
+    /// Moves wxHOPR deposited on the node EOA into the Safe; see [`crate::sweep`].
+    WxHoprSweep(SweepConfig),
 }
 
 /// Strategy configuration for an edge node reactor.
@@ -133,6 +138,11 @@ pub struct IncentiveConfiguration {
     /// Default: `None` — [`SIZING_MODE`].
     #[default(None)]
     pub sizing_mode: Option<CapacitySizingMode>,
+
+    /// When `Some`, the reactor also sweeps wxHOPR from the node EOA into the Safe, so a
+    /// top-up sent to the wrong address still funds channels. Default: `None` — no sweeping.
+    #[default(None)]
+    pub sweep: Option<SweepConfig>,
 }
 
 impl IncentiveConfiguration {
@@ -143,6 +153,9 @@ impl IncentiveConfiguration {
             self.target_open_channels,
             self.min_open_channels
         );
+        if let Some(sweep) = &self.sweep {
+            sweep.validate()?;
+        }
         Ok(())
     }
 }
@@ -230,6 +243,39 @@ fn channel_stakes(
     Ok(total.max(resolved.min_safe_balance_required))
 }
 
+/// wxHOPR balances the strategy acts on at the current ticket economics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FundingThresholds {
+    /// Safe balance below which the strategy opens and funds no channel
+    /// (`stop_when_unfunded`).
+    pub min_safe_balance: HoprBalance,
+    /// Channel balance below which the strategy tops a channel up.
+    pub channel_lower_threshold: HoprBalance,
+}
+
+/// The [`FundingThresholds`] the strategy resolves `cfg` to at `ticket_price` and `win_prob`.
+///
+/// The channel threshold is resolved like an initial capacity of
+/// `lower_capacity_threshold`, through the same [`resolve_funding`] the strategy sizes
+/// stakes with.
+pub fn compute_funding_thresholds(
+    cfg: &IncentiveConfiguration,
+    ticket_price: HoprBalance,
+    win_prob: f64,
+) -> anyhow::Result<FundingThresholds> {
+    let funding = compute_funding_config(cfg)?;
+    let min_safe_balance =
+        resolve_funding(&funding, ticket_price, win_prob).min_safe_balance_required;
+    let lower = FundingConfig {
+        initial_capacity: funding.lower_capacity_threshold,
+        ..funding
+    };
+    Ok(FundingThresholds {
+        min_safe_balance,
+        channel_lower_threshold: resolve_funding(&lower, ticket_price, win_prob).initial_balance,
+    })
+}
+
 /// One-time costs still owed before this node can be fully up and running,
 /// verified against on-chain state.
 #[derive(Clone, Copy, Debug)]
@@ -244,7 +290,7 @@ pub struct StartupCosts {
     pub txs_to_start: u64,
 }
 
-/// Recommended total xDai amount to fund the node with for gas: 0.005 xDai.
+/// A flat xDai amount to fund the node with for gas: 0.005 xDai.
 ///
 /// A hardcoded funding target covering all setup transactions plus headroom for
 /// the larger Safe + module deployment, distinct from
@@ -252,6 +298,9 @@ pub struct StartupCosts {
 /// depends on the chain's current gas price). The figure halves upstream's
 /// `hopr_lib::SUGGESTED_NATIVE_BALANCE` (0.01 xDai), which suggests roughly
 /// double what setup transactions need on Gnosis Chain.
+///
+/// [`BalanceRecommendation`] projects its amounts from the node's expected transactions
+/// instead; this remains for a figure that needs no chain reading.
 pub fn suggested_xdai_fund_amount() -> XDaiBalance {
     XDaiBalance::from(5_000_000_000_000_000_u64) // 0.005 xDai in wei
 }
@@ -264,7 +313,7 @@ pub fn suggested_xdai_fund_amount() -> XDaiBalance {
 /// sender authorises — *not* expected spend, which is `gas_used × effective
 /// price` and on Gnosis Chain is orders of magnitude lower. Saturates rather
 /// than overflowing on an absurd reported gas price. Obtain `max_fee_per_gas`
-/// from the chain via `crate::blokli::query_max_fee_per_gas`.
+/// from `Edgli::max_fee_per_gas` or `IncentiveOperations::max_fee_per_gas`.
 pub fn xdai_fee_per_tx(max_fee_per_gas: u128) -> XDaiBalance {
     let gas_limit = hopr_lib::api::types::chain::payload::GasEstimation::default().gas_limit;
     XDaiBalance::from(max_fee_per_gas.saturating_mul(gas_limit as u128))
@@ -289,8 +338,11 @@ pub struct BalanceRecommendation {
     /// [`xdai_fee_per_tx`]. Use [`xdai_fund_amount`](Self::xdai_fund_amount) for
     /// what to actually fund the node with.
     pub xdai_fee_per_tx: XDaiBalance,
-    /// Total xDAI to fund the node with for gas
-    /// (fixed at [`suggested_xdai_fund_amount`]).
+    /// xDAI the node's projected transactions cost: the [`XdaiBudget::minimum`] of
+    /// [`project_xdai_budget`].
+    pub xdai_minimum: XDaiBalance,
+    /// Total xDAI to fund the node with for gas: the [`XdaiBudget::comfortable`] of
+    /// [`project_xdai_budget`], the minimum with headroom.
     pub xdai_fund_amount: XDaiBalance,
 }
 
@@ -301,6 +353,124 @@ impl BalanceRecommendation {
     }
 }
 
+/// What [`project_xdai_budget`] assumes about the node's operation over the budgeted period.
+///
+/// The gas figures are typical gas *used*, not the gas limit transactions are signed with.
+/// A running node replaces [`Self::gas_per_channel_tx`] with what its own channel
+/// transactions used once it has measured one; see [`crate::client::Edgli::xdai_budget`].
+#[derive(Clone, Debug, smart_default::SmartDefault)]
+pub struct XdaiBudgetConfig {
+    /// Period the budget covers. Default: 30 days.
+    #[default(Duration::from_hours(30 * 24))]
+    pub horizon: Duration,
+
+    /// Data the node sends through its channels per day, which drives the top-ups.
+    /// Default: 1 GiB.
+    #[default(ByteSize::gib(1))]
+    pub daily_traffic: ByteSize,
+
+    /// How long a channel stays open before the strategy closes and replaces it.
+    /// Default: 7 days.
+    #[default(Duration::from_hours(7 * 24))]
+    pub channel_lifetime: Duration,
+
+    /// Gas one startup transaction (Safe deployment, Safe registration, announcement) uses.
+    /// Default: 500 000.
+    #[default(500_000)]
+    pub gas_per_startup_tx: u64,
+
+    /// Gas one channel transaction (open, top-up, close, finalize) uses, where the node has
+    /// not measured its own. Default: 150 000.
+    #[default(150_000)]
+    pub gas_per_channel_tx: u64,
+
+    /// Factor the comfortable amount exceeds the minimum by, to ride out gas price spikes
+    /// and traffic above the assumed. Default: 3.
+    #[default(3.0)]
+    pub headroom: f64,
+}
+
+/// Projected gas spend of a node over an [`XdaiBudgetConfig::horizon`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct XdaiBudget {
+    /// Startup transactions still to be made; see [`StartupCosts::txs_to_start`].
+    pub startup_txs: u64,
+    /// Channels opened: the missing ones, then the replacements for closed ones.
+    pub channel_opens: u64,
+    /// Channel top-ups the projected traffic needs.
+    pub channel_topups: u64,
+    /// Channels closed and replaced; each takes a closure and a finalization transaction.
+    pub channel_closes: u64,
+    /// xDAI the projected transactions cost at the given gas price.
+    pub minimum: XDaiBalance,
+    /// [`Self::minimum`] with [`XdaiBudgetConfig::headroom`]; what to fund the node with.
+    pub comfortable: XDaiBalance,
+}
+
+impl XdaiBudget {
+    /// Total number of projected transactions.
+    pub fn transactions(&self) -> u64 {
+        self.startup_txs + self.channel_opens + self.channel_topups + 2 * self.channel_closes
+    }
+}
+
+/// Projects the xDAI the node spends on gas over `budget.horizon`.
+///
+/// Counts the `txs_to_start` startup transactions, the opens of `missing_channels`, one
+/// replacement of each of the `incentives.target_open_channels` per
+/// [`XdaiBudgetConfig::channel_lifetime`], and a top-up per `topup_capacity` of the
+/// projected traffic. Each is priced at its typical gas use times `max_fee_per_gas`, so the
+/// figure tracks the reported price rather than the gas limit [`xdai_fee_per_tx`] uses.
+pub fn project_xdai_budget(
+    incentives: &IncentiveConfiguration,
+    budget: &XdaiBudgetConfig,
+    txs_to_start: u64,
+    missing_channels: usize,
+    max_fee_per_gas: u128,
+) -> anyhow::Result<XdaiBudget> {
+    anyhow::ensure!(
+        !budget.channel_lifetime.is_zero(),
+        "channel_lifetime must be non-zero"
+    );
+    anyhow::ensure!(
+        budget.headroom.is_finite() && budget.headroom >= 1.0,
+        "headroom must be at least 1; got {}",
+        budget.headroom
+    );
+    let topup_capacity = compute_funding_config(incentives)?.topup_capacity.as_u64();
+    anyhow::ensure!(topup_capacity > 0, "topup_capacity must be non-zero");
+
+    let replacement_rounds =
+        (budget.horizon.as_secs_f64() / budget.channel_lifetime.as_secs_f64()).floor() as u64;
+    let channel_closes =
+        (incentives.target_open_channels as u64).saturating_mul(replacement_rounds);
+    let days = budget.horizon.as_secs_f64() / Duration::from_hours(24).as_secs_f64();
+    let traffic = budget.daily_traffic.as_u64() as f64 * days;
+    let channel_topups = (traffic / topup_capacity as f64).ceil() as u64;
+
+    let projected = XdaiBudget {
+        startup_txs: txs_to_start,
+        channel_opens: (missing_channels as u64).saturating_add(channel_closes),
+        channel_topups,
+        channel_closes,
+        minimum: XDaiBalance::zero(),
+        comfortable: XDaiBalance::zero(),
+    };
+    let gas = u128::from(txs_to_start)
+        .saturating_mul(budget.gas_per_startup_tx.into())
+        .saturating_add(
+            u128::from(projected.transactions() - txs_to_start)
+                .saturating_mul(budget.gas_per_channel_tx.into()),
+        );
+    let minimum = gas.saturating_mul(max_fee_per_gas);
+    let comfortable = (minimum as f64 * budget.headroom).ceil() as u128;
+    Ok(XdaiBudget {
+        minimum: XDaiBalance::from(minimum),
+        comfortable: XDaiBalance::from(comfortable.max(minimum)),
+        ..projected
+    })
+}
+
 /// Data-throughput capacities of every wxHOPR stake the node can draw on,
 /// returned by [`crate::client::Edgli::describe_current_capacity_allocations`].
 #[derive(Clone, Debug)]
@@ -350,13 +520,15 @@ pub struct Capacity {
 /// it underfunds the node.
 ///
 /// `max_fee_per_gas` is the chain's current EIP-1559 gas price in wei per gas,
-/// from which the per-transaction xDai fee is derived; see [`xdai_fee_per_tx`].
+/// from which the per-transaction xDai fee is derived; see [`xdai_fee_per_tx`]. The
+/// xDAI amounts are the [`project_xdai_budget`] of the same transactions over `budget`.
 pub(crate) fn compute_balance_recommendation(
     ticket_price: HoprBalance,
     win_prob: f64,
     missing_channels: usize,
     costs: StartupCosts,
     sizing: &IncentiveConfiguration,
+    budget: &XdaiBudgetConfig,
     max_fee_per_gas: u128,
 ) -> anyhow::Result<BalanceRecommendation> {
     let stake = if missing_channels == 0 {
@@ -364,12 +536,20 @@ pub(crate) fn compute_balance_recommendation(
     } else {
         channel_stakes(ticket_price, win_prob, sizing, missing_channels)?
     };
+    let projected = project_xdai_budget(
+        sizing,
+        budget,
+        costs.txs_to_start,
+        missing_channels,
+        max_fee_per_gas,
+    )?;
     Ok(BalanceRecommendation {
         channel_stakes: stake,
         fee_to_start: costs.fee_to_start,
         txs_to_start: costs.txs_to_start,
         xdai_fee_per_tx: xdai_fee_per_tx(max_fee_per_gas),
-        xdai_fund_amount: suggested_xdai_fund_amount(),
+        xdai_minimum: projected.minimum,
+        xdai_fund_amount: projected.comfortable,
     })
 }
 
@@ -464,7 +644,9 @@ where
 /// Queries ticket pricing from the safeless chain interactor so this can be
 /// called before the full node is started (e.g. during onboarding). The
 /// one-time key-binding (announcement) fee is included on top of the channel
-/// stakes only when the node's key is not yet bound on-chain.
+/// stakes only when the node's key is not yet bound on-chain. The xDAI is projected
+/// over the default [`XdaiBudgetConfig`]: a node not yet started has no transactions of
+/// its own to measure gas from.
 #[cfg(feature = "blokli")]
 pub async fn minimum_balance_recommendation(
     incentive_ops: &dyn crate::blokli::IncentiveOperations,
@@ -480,6 +662,28 @@ pub async fn minimum_balance_recommendation(
         cfg.target_open_channels,
         costs,
         cfg,
+        &XdaiBudgetConfig::default(),
+        max_fee_per_gas,
+    )
+}
+
+/// Projects the xDAI a node started from scratch spends on gas over `budget.horizon`,
+/// opening `cfg.target_open_channels` channels; see [`project_xdai_budget`].
+///
+/// Like [`minimum_balance_recommendation`], this can be called before the node is started.
+#[cfg(feature = "blokli")]
+pub async fn minimum_xdai_budget(
+    incentive_ops: &dyn crate::blokli::IncentiveOperations,
+    cfg: &IncentiveConfiguration,
+    budget: &XdaiBudgetConfig,
+) -> anyhow::Result<XdaiBudget> {
+    let costs = incentive_ops.compute_costs_to_start().await?;
+    let max_fee_per_gas = incentive_ops.max_fee_per_gas().await?;
+    project_xdai_budget(
+        cfg,
+        budget,
+        costs.txs_to_start,
+        cfg.target_open_channels,
         max_fee_per_gas,
     )
 }
@@ -518,9 +722,41 @@ pub fn default_strategy_cfg(
         },
         ..Default::default()
     };
-    Ok(MultiStrategyConfig {
-        strategies: vec![EdgeStrategyKind::ChannelLifecycle(cfg)],
-    })
+    let mut strategies = vec![EdgeStrategyKind::ChannelLifecycle(cfg)];
+    if let Some(sweep) = &sizing.sweep {
+        strategies.push(EdgeStrategyKind::WxHoprSweep(sweep.clone()));
+    }
+    Ok(MultiStrategyConfig { strategies })
+}
+
+/// Restart pacing for a supervised strategy reactor.
+///
+/// The delay doubles with every consecutive failure, from `initial_delay` up to `max_delay`.
+/// A run that lasted at least `reset_after` before failing counts as the first failure again,
+/// so a reactor that fails once a day is not restarted as slowly as one stuck in a crash loop.
+#[derive(Clone, Debug, smart_default::SmartDefault)]
+pub struct ReactorBackoff {
+    /// Delay before the first restart. Default: 1 s.
+    #[default(Duration::from_secs(1))]
+    pub initial_delay: Duration,
+
+    /// Upper bound on the delay between restarts. Default: 5 min.
+    #[default(Duration::from_secs(300))]
+    pub max_delay: Duration,
+
+    /// A run at least this long resets the delay to `initial_delay`. Default: 10 min.
+    #[default(Duration::from_secs(600))]
+    pub reset_after: Duration,
+}
+
+impl ReactorBackoff {
+    /// Delay before restarting after `consecutive_failures` (at least 1) failures in a row.
+    pub fn delay(&self, consecutive_failures: u32) -> Duration {
+        let doublings = consecutive_failures.saturating_sub(1).min(31);
+        self.initial_delay
+            .saturating_mul(1u32 << doublings)
+            .min(self.max_delay)
+    }
 }
 
 #[cfg(test)]
@@ -761,6 +997,7 @@ mod tests {
                     1,
                     no_startup_costs(),
                     &IncentiveConfiguration::default(),
+                    &XdaiBudgetConfig::default(),
                     TEST_MAX_FEE_PER_GAS,
                 )
                 .unwrap();
@@ -775,6 +1012,7 @@ mod tests {
                     64,
                     no_startup_costs(),
                     &IncentiveConfiguration::default(),
+                    &XdaiBudgetConfig::default(),
                     TEST_MAX_FEE_PER_GAS,
                 )
                 .unwrap();
@@ -800,6 +1038,7 @@ mod tests {
                 1,
                 no_startup_costs(),
                 &IncentiveConfiguration::default(),
+                &XdaiBudgetConfig::default(),
                 TEST_MAX_FEE_PER_GAS,
             )
             .unwrap();
@@ -826,6 +1065,7 @@ mod tests {
                 1,
                 no_startup_costs(),
                 &requested_cfg,
+                &XdaiBudgetConfig::default(),
                 TEST_MAX_FEE_PER_GAS,
             )
             .unwrap();
@@ -837,6 +1077,7 @@ mod tests {
                 1,
                 no_startup_costs(),
                 &IncentiveConfiguration::default(),
+                &XdaiBudgetConfig::default(),
                 TEST_MAX_FEE_PER_GAS,
             )
             .unwrap();
@@ -891,7 +1132,10 @@ mod tests {
             ..Default::default()
         };
         let cfg = default_strategy_cfg(&sizing).unwrap();
-        let EdgeStrategyKind::ChannelLifecycle(lifecycle_cfg) = &cfg.strategies[0];
+        assert_eq!(cfg.strategies.len(), 1);
+        let EdgeStrategyKind::ChannelLifecycle(lifecycle_cfg) = &cfg.strategies[0] else {
+            panic!("the first strategy is the channel lifecycle");
+        };
         let funding = &lifecycle_cfg.funding;
         assert_eq!(funding.initial_capacity, ByteSize::mib(640));
         assert_eq!(funding.topup_capacity, ByteSize::mib(384));
@@ -900,6 +1144,32 @@ mod tests {
         assert_eq!(funding.sizing_mode, CapacitySizingMode::Deterministic);
     }
 
+    #[test]
+    fn default_strategy_cfg_adds_a_sweep_only_when_configured() {
+        let sweep = SweepConfig {
+            reserve: HoprBalance::new_base(3),
+            ..Default::default()
+        };
+        let cfg = default_strategy_cfg(&IncentiveConfiguration {
+            sweep: Some(sweep.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(cfg.strategies.len(), 2);
+        assert!(matches!(&cfg.strategies[1], EdgeStrategyKind::WxHoprSweep(s) if *s == sweep));
+
+        assert!(
+            default_strategy_cfg(&IncentiveConfiguration {
+                sweep: Some(SweepConfig {
+                    interval: Duration::ZERO,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
     #[test]
     fn channel_sizing_defaults_match_population_config_defaults() {
         let sizing = IncentiveConfiguration::default();
@@ -960,12 +1230,24 @@ mod tests {
             0,
             no_startup_costs(),
             &IncentiveConfiguration::default(),
+            &XdaiBudgetConfig::default(),
             TEST_MAX_FEE_PER_GAS,
         )
         .unwrap();
         assert_eq!(rec.total_wxhopr(), HoprBalance::zero());
         assert_eq!(rec.xdai_fee_per_tx, xdai_fee_per_tx(TEST_MAX_FEE_PER_GAS));
-        assert_eq!(rec.xdai_fund_amount, suggested_xdai_fund_amount());
+        // Nothing left to open, but channel churn and top-ups still cost gas.
+        let projected = project_xdai_budget(
+            &IncentiveConfiguration::default(),
+            &XdaiBudgetConfig::default(),
+            0,
+            0,
+            TEST_MAX_FEE_PER_GAS,
+        )
+        .unwrap();
+        assert!(projected.minimum > XDaiBalance::zero());
+        assert_eq!(rec.xdai_minimum, projected.minimum);
+        assert_eq!(rec.xdai_fund_amount, projected.comfortable);
     }
 
     #[test]
@@ -982,6 +1264,7 @@ mod tests {
                 txs_to_start: 3,
             },
             &IncentiveConfiguration::default(),
+            &XdaiBudgetConfig::default(),
             TEST_MAX_FEE_PER_GAS,
         )
         .unwrap();
@@ -1011,6 +1294,7 @@ mod tests {
                 txs_to_start: 2,
             },
             &IncentiveConfiguration::default(),
+            &XdaiBudgetConfig::default(),
             TEST_MAX_FEE_PER_GAS,
         )
         .unwrap();
@@ -1029,6 +1313,7 @@ mod tests {
             8,
             no_startup_costs(),
             &IncentiveConfiguration::default(),
+            &XdaiBudgetConfig::default(),
             TEST_MAX_FEE_PER_GAS,
         )
         .unwrap();
@@ -1043,7 +1328,39 @@ mod tests {
         assert_eq!(rec.txs_to_start, 0);
         assert_eq!(rec.total_wxhopr(), per_channel * 8u64);
         assert_eq!(rec.xdai_fee_per_tx, xdai_fee_per_tx(TEST_MAX_FEE_PER_GAS));
-        assert_eq!(rec.xdai_fund_amount, suggested_xdai_fund_amount());
+        let projected = project_xdai_budget(
+            &IncentiveConfiguration::default(),
+            &XdaiBudgetConfig::default(),
+            0,
+            8,
+            TEST_MAX_FEE_PER_GAS,
+        )
+        .unwrap();
+        assert_eq!(rec.xdai_minimum, projected.minimum);
+        assert_eq!(rec.xdai_fund_amount, projected.comfortable);
+    }
+
+    #[test]
+    fn compute_balance_recommendation_projects_xdai_from_the_budget_gas() {
+        let recommend = |gas_per_channel_tx| {
+            compute_balance_recommendation(
+                HoprBalance::new_base(10),
+                1.0,
+                8,
+                no_startup_costs(),
+                &IncentiveConfiguration::default(),
+                &XdaiBudgetConfig {
+                    gas_per_channel_tx,
+                    ..Default::default()
+                },
+                TEST_MAX_FEE_PER_GAS,
+            )
+            .unwrap()
+        };
+        let measured = recommend(75_000);
+        let assumed = recommend(150_000);
+        assert_eq!(assumed.xdai_minimum, measured.xdai_minimum * 2u64);
+        assert!(assumed.xdai_fund_amount > assumed.xdai_minimum);
     }
 
     #[test]
@@ -1057,6 +1374,7 @@ mod tests {
             1,
             no_startup_costs(),
             &IncentiveConfiguration::default(),
+            &XdaiBudgetConfig::default(),
             TEST_MAX_FEE_PER_GAS,
         )
         .unwrap();
@@ -1066,6 +1384,7 @@ mod tests {
             1,
             no_startup_costs(),
             &IncentiveConfiguration::default(),
+            &XdaiBudgetConfig::default(),
             TEST_MAX_FEE_PER_GAS,
         )
         .unwrap();
@@ -1085,6 +1404,7 @@ mod tests {
             0,
             no_startup_costs(),
             &IncentiveConfiguration::default(),
+            &XdaiBudgetConfig::default(),
             TEST_MAX_FEE_PER_GAS,
         )
         .unwrap();
@@ -1190,4 +1510,92 @@ mod tests {
         };
         assert_eq!(eligibility.allowlist, Some(allowlist));
     }
+
+    #[test]
+    fn xdai_budget_counts_startup_churn_and_topups() {
+        let incentives = IncentiveConfiguration {
+            target_open_channels: 4,
+            topup_capacity: Some(ByteSize::gib(1)),
+            ..Default::default()
+        };
+        let budget = XdaiBudgetConfig {
+            horizon: Duration::from_hours(10 * 24),
+            daily_traffic: ByteSize::mib(512),
+            channel_lifetime: Duration::from_hours(4 * 24),
+            gas_per_startup_tx: 1_000,
+            gas_per_channel_tx: 100,
+            headroom: 2.0,
+        };
+        let projected = project_xdai_budget(&incentives, &budget, 3, 4, 10).unwrap();
+        // Two full lifetimes fit into ten days: every channel is replaced twice.
+        assert_eq!(projected.channel_closes, 8);
+        assert_eq!(projected.channel_opens, 12);
+        // 5 GiB of traffic at 1 GiB per top-up.
+        assert_eq!(projected.channel_topups, 5);
+        assert_eq!(projected.transactions(), 3 + 12 + 5 + 16);
+        let gas = 3 * 1_000 + (12 + 5 + 16) * 100;
+        assert_eq!(projected.minimum, XDaiBalance::from(gas * 10u128));
+        assert_eq!(projected.comfortable, XDaiBalance::from(gas * 20u128));
+    }
+
+    #[test]
+    fn xdai_budget_tracks_gas_price_and_outstanding_work() {
+        let incentives = IncentiveConfiguration::default();
+        let budget = XdaiBudgetConfig::default();
+        let at = |price| project_xdai_budget(&incentives, &budget, 3, 8, price).unwrap();
+        assert_eq!(
+            at(TEST_MAX_FEE_PER_GAS * 2).minimum,
+            at(TEST_MAX_FEE_PER_GAS).minimum * 2u64
+        );
+        assert_eq!(at(0).comfortable, XDaiBalance::zero());
+
+        let set_up = project_xdai_budget(&incentives, &budget, 0, 0, TEST_MAX_FEE_PER_GAS).unwrap();
+        assert!(set_up.minimum < at(TEST_MAX_FEE_PER_GAS).minimum);
+    }
+
+    #[test]
+    fn xdai_budget_rejects_degenerate_assumptions() {
+        let incentives = IncentiveConfiguration::default();
+        for budget in [
+            XdaiBudgetConfig {
+                channel_lifetime: Duration::ZERO,
+                ..Default::default()
+            },
+            XdaiBudgetConfig {
+                headroom: 0.5,
+                ..Default::default()
+            },
+        ] {
+            assert!(project_xdai_budget(&incentives, &budget, 0, 0, 1).is_err());
+        }
+    }
+
+    #[test]
+    fn funding_thresholds_order_the_lower_threshold_below_the_initial_stake() {
+        let cfg = IncentiveConfiguration {
+            channel_capacity: Some(ByteSize::mib(512)),
+            lower_capacity_threshold: Some(ByteSize::mib(64)),
+            ..Default::default()
+        };
+        let price = HoprBalance::new_base(10);
+        let thresholds = compute_funding_thresholds(&cfg, price, 0.5).unwrap();
+        let resolved = resolve_funding(&compute_funding_config(&cfg).unwrap(), price, 0.5);
+        assert_eq!(
+            thresholds.min_safe_balance,
+            resolved.min_safe_balance_required
+        );
+        assert!(thresholds.channel_lower_threshold > HoprBalance::zero());
+        assert!(thresholds.channel_lower_threshold < resolved.initial_balance);
+    }
+
+    #[test]
+    fn reactor_backoff_doubles_up_to_the_cap() {
+        let backoff = ReactorBackoff::default();
+        assert_eq!(backoff.delay(1), Duration::from_secs(1));
+        assert_eq!(backoff.delay(2), Duration::from_secs(2));
+        assert_eq!(backoff.delay(4), Duration::from_secs(8));
+        assert_eq!(backoff.delay(9), Duration::from_secs(256));
+        assert_eq!(backoff.delay(10), backoff.max_delay);
+        assert_eq!(backoff.delay(u32::MAX), backoff.max_delay);
+    }
 }
@@ -0,0 +1,150 @@
+//! Sweeping wxHOPR deposited on the node EOA into the Safe.
+//!
+//! Channels are funded from the Safe, so wxHOPR sent to the node address instead sits idle
+//! until it is withdrawn into the Safe. A sweep moves everything above
+//! [`SweepConfig::reserve`] once that is at least [`SweepConfig::min_amount`] and gas is no
+//! dearer than [`SweepConfig::max_fee_per_gas`] and the node's gas cap.
+//!
+//! Sweeps run either inside the strategy reactor, as [`EdgeStrategyKind::WxHoprSweep`]
+//! (set [`IncentiveConfiguration::sweep`]), or as a task of their own started with
+//! `Edgli::spawn_wxhopr_sweeper`.
+//!
+//! [`EdgeStrategyKind::WxHoprSweep`]: crate::strategy::EdgeStrategyKind::WxHoprSweep
+//! [`IncentiveConfiguration::sweep`]: crate::strategy::IncentiveConfiguration::sweep
+
+use std::time::Duration;
+
+use hopr_lib::api::types::primitive::prelude::HoprBalance;
+
+/// When and how much node wxHOPR a sweeper moves into the Safe.
+#[derive(Clone, Debug, PartialEq, smart_default::SmartDefault)]
+pub struct SweepConfig {
+    /// wxHOPR left on the node EOA. Default: zero.
+    pub reserve: HoprBalance,
+
+    /// Smallest amount above [`Self::reserve`] worth a transaction. Default: 1 wxHOPR.
+    #[default(HoprBalance::new_base(1))]
+    pub min_amount: HoprBalance,
+
+    /// Highest chain-reported `max_fee_per_gas`, in wei, a sweep is submitted at; a dearer
+    /// sweep waits for the next check. Default: `None` — whatever the node's gas cap allows.
+    #[default(None)]
+    pub max_fee_per_gas: Option<u128>,
+
+    /// How often the node balance is checked. Default: 10 minutes.
+    #[default(Duration::from_secs(600))]
+    pub interval: Duration,
+}
+
+impl SweepConfig {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(!self.interval.is_zero(), "sweep interval must be non-zero");
+        Ok(())
+    }
+
+    /// The amount to sweep from a node holding `node_wxhopr`, if it is worth a transaction.
+    pub fn sweepable(&self, node_wxhopr: HoprBalance) -> Option<HoprBalance> {
+        if node_wxhopr <= self.reserve {
+            return None;
+        }
+        let amount = node_wxhopr - self.reserve;
+        (amount >= self.min_amount).then_some(amount)
+    }
+
+    /// The highest `max_fee_per_gas` a sweep goes ahead at, given the node's own gas cap.
+    pub fn gas_limit(&self, gas_cap: Option<u128>) -> Option<u128> {
+        match (self.max_fee_per_gas, gas_cap) {
+            (Some(limit), Some(cap)) => Some(limit.min(cap)),
+            (limit, cap) => limit.or(cap),
+        }
+    }
+}
+
+/// What a sweep did.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SweepOutcome {
+    /// Moved this much wxHOPR into the Safe.
+    Swept(HoprBalance),
+    /// The node balance was less than [`SweepConfig::min_amount`] above the reserve.
+    BelowMinimum { node_wxhopr: HoprBalance },
+    /// The chain's gas price was above the limit; the sweep waits for a cheaper one.
+    GasTooExpensive { max_fee_per_gas: u128, limit: u128 },
+}
+
+impl std::fmt::Display for SweepOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Swept(amount) => write!(f, "swept {amount} into the Safe"),
+            Self::BelowMinimum { node_wxhopr } => {
+                write!(f, "node holds {node_wxhopr}, too little to sweep")
+            }
+            Self::GasTooExpensive {
+                max_fee_per_gas,
+                limit,
+            } => write!(
+                f,
+                "gas at {max_fee_per_gas} wei is above the sweep limit of {limit} wei"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_amount_above_the_reserve_is_swept() {
+        let cfg = SweepConfig {
+            reserve: HoprBalance::new_base(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            cfg.sweepable(HoprBalance::new_base(5)),
+            Some(HoprBalance::new_base(3))
+        );
+        assert_eq!(cfg.sweepable(HoprBalance::new_base(2)), None);
+        assert_eq!(cfg.sweepable(HoprBalance::zero()), None);
+    }
+
+    #[test]
+    fn amounts_below_the_minimum_are_not_swept() {
+        let cfg = SweepConfig {
+            min_amount: HoprBalance::new_base(10),
+            ..Default::default()
+        };
+        assert_eq!(cfg.sweepable(HoprBalance::new_base(9)), None);
+        assert_eq!(
+            cfg.sweepable(HoprBalance::new_base(10)),
+            Some(HoprBalance::new_base(10))
+        );
+    }
+
+    #[test]
+    fn the_gas_limit_is_the_lower_of_the_sweep_limit_and_the_cap() {
+        let cfg = SweepConfig::default();
+        assert_eq!(cfg.gas_limit(None), None);
+        assert_eq!(cfg.gas_limit(Some(7)), Some(7));
+
+        let cfg = SweepConfig {
+            max_fee_per_gas: Some(5),
+            ..Default::default()
+        };
+        assert_eq!(cfg.gas_limit(None), Some(5));
+        assert_eq!(cfg.gas_limit(Some(7)), Some(5));
+        assert_eq!(cfg.gas_limit(Some(3)), Some(3));
+    }
+
+    #[test]
+    fn zero_interval_is_rejected() {
+        assert!(SweepConfig::default().validate().is_ok());
+        assert!(
+            SweepConfig {
+                interval: Duration::ZERO,
+                ..Default::default()
+            }
+            .validate()
+            .is_err()
+        );
+    }
+}
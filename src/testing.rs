@@ -0,0 +1,653 @@
+//! Scriptable stand-ins for consumers' tests: [`MockEdgeNode`] for [`EdgeNodeApi`] and,
+//! with the `blokli` feature, `MockIncentiveOperations` for on-boarding against
+//! [`crate::IncentiveOperations`].
+//!
+//! [`MockEdgeNode`] keeps the node's balances, channels, peers and sessions in memory and
+//! applies operations the way [`crate::Edgli`] does: opening or funding a channel takes
+//! the amount from the Safe, finalizing a closure returns the stake to it, withdrawals come
+//! out of the node wallet, and every change is reported through [`EdgeNodeApi::events`].
+//! Transactions confirm at once. Each call is recorded as a [`MockCall`], and any method can
+//! be made to fail with [`MockEdgeNode::fail_next`] or [`MockEdgeNode::fail_always`].
+//!
+//! Clones share the same node, so a test can keep one to script and inspect the node while
+//! the code under test owns another.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use futures::stream::BoxStream;
+use hopr_lib::{
+    api::{
+        chain::ChainReceipt,
+        node::HoprState,
+        types::{
+            internal::channels::{ChannelEntry, ChannelStatus},
+            primitive::prelude::{Address, Balance, HoprBalance},
+        },
+    },
+    errors::HoprLibError,
+    exports::transport::{HoprSessionClientConfig, SessionTarget},
+};
+
+use crate::events::{EdgeEvent, EdgeEventChannel};
+use crate::session::{EdgeSession, EdgeSessionId, SessionInfo, SessionRegistry};
+use crate::traits::{EdgeNodeApi, NodeBalances, TransactionHandle};
+
+#[cfg(feature = "blokli")]
+mod incentives;
+
+#[cfg(feature = "blokli")]
+pub use incentives::{
+    DEFAULT_MOCK_GAS_PER_TX, IncentiveFault, IncentiveMethod, MockIncentiveOperations,
+};
+
+/// An [`EdgeNodeApi`] method that can be scripted to fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MockMethod {
+    Balances,
+    MyOutgoingChannels,
+    OpenChannel,
+    FundChannel,
+    CloseChannel,
+    FinalizeClosure,
+    Withdraw,
+    ConnectedPeerAddresses,
+    OpenSession,
+    CloseSession,
+}
+
+/// A call made to a [`MockEdgeNode`], with its arguments.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MockCall {
+    Balances,
+    MyOutgoingChannels,
+    OpenChannel {
+        target: Address,
+        amount: HoprBalance,
+    },
+    FundChannel {
+        destination: Address,
+        amount: HoprBalance,
+    },
+    CloseChannel {
+        destination: Address,
+    },
+    FinalizeClosure {
+        destination: Address,
+    },
+    Withdraw {
+        recipient: Address,
+        amount: HoprBalance,
+    },
+    ConnectedPeerAddresses,
+    OpenSession {
+        destination: Address,
+    },
+    CloseSession {
+        id: EdgeSessionId,
+    },
+}
+
+impl MockCall {
+    /// The method this call was made to.
+    pub fn method(&self) -> MockMethod {
+        match self {
+            Self::Balances => MockMethod::Balances,
+            Self::MyOutgoingChannels => MockMethod::MyOutgoingChannels,
+            Self::OpenChannel { .. } => MockMethod::OpenChannel,
+            Self::FundChannel { .. } => MockMethod::FundChannel,
+            Self::CloseChannel { .. } => MockMethod::CloseChannel,
+            Self::FinalizeClosure { .. } => MockMethod::FinalizeClosure,
+            Self::Withdraw { .. } => MockMethod::Withdraw,
+            Self::ConnectedPeerAddresses => MockMethod::ConnectedPeerAddresses,
+            Self::OpenSession { .. } => MockMethod::OpenSession,
+            Self::CloseSession { .. } => MockMethod::CloseSession,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct MockState {
+    state: HoprState,
+    balances: NodeBalances,
+    channels: Vec<ChannelEntry>,
+    peers: Vec<Address>,
+    calls: Vec<MockCall>,
+    next_failures: HashMap<MockMethod, VecDeque<String>>,
+    failures: HashMap<MockMethod, String>,
+}
+
+impl MockState {
+    /// Records `call` and returns the failure scripted for it, if any.
+    fn call(&mut self, call: MockCall) -> Result<(), HoprLibError> {
+        let method = call.method();
+        self.calls.push(call);
+        let failure = self
+            .next_failures
+            .get_mut(&method)
+            .and_then(VecDeque::pop_front)
+            .or_else(|| self.failures.get(&method).cloned());
+        match failure {
+            Some(reason) => Err(HoprLibError::GeneralError(reason)),
+            None => Ok(()),
+        }
+    }
+
+    /// Refuses on-chain operations unless the node is running, as [`crate::Edgli`] does.
+    fn ensure_running(&self) -> Result<(), HoprLibError> {
+        if self.state == HoprState::Running {
+            Ok(())
+        } else {
+            Err(error(format!("the node is not running ({:?})", self.state)))
+        }
+    }
+
+    /// The channel to `destination` that is not yet closed.
+    fn channel(&mut self, destination: Address) -> Result<&mut ChannelEntry, HoprLibError> {
+        self.channels
+            .iter_mut()
+            .find(|c| c.destination == destination && c.status != ChannelStatus::Closed)
+            .ok_or_else(|| error(format!("no outgoing channel to {destination}")))
+    }
+
+    fn take_from_safe(&mut self, amount: HoprBalance) -> Result<(), HoprLibError> {
+        if amount > self.balances.safe_wxhopr {
+            return Err(error(format!(
+                "the Safe holds {}, less than {amount}",
+                self.balances.safe_wxhopr
+            )));
+        }
+        self.balances.safe_wxhopr = self.balances.safe_wxhopr - amount;
+        Ok(())
+    }
+}
+
+fn error(reason: impl Into<String>) -> HoprLibError {
+    HoprLibError::GeneralError(reason.into())
+}
+
+fn confirmed() -> TransactionHandle {
+    TransactionHandle::confirmed(ChainReceipt::default())
+}
+
+/// An in-memory [`EdgeNodeApi`] with scripted behaviour; see the [module docs](self).
+///
+/// Starts [`HoprState::Running`] with zero balances, no channels and no peers.
+#[derive(Clone, Debug)]
+pub struct MockEdgeNode {
+    address: Address,
+    safe: Address,
+    inner: Arc<Mutex<MockState>>,
+    events: EdgeEventChannel,
+    sessions: SessionRegistry,
+}
+
+impl Default for MockEdgeNode {
+    fn default() -> Self {
+        Self::new(Address::default(), Address::default())
+    }
+}
+
+impl MockEdgeNode {
+    /// A node at `address` whose Safe is `safe`.
+    pub fn new(address: Address, safe: Address) -> Self {
+        Self {
+            address,
+            safe,
+            inner: Arc::new(Mutex::new(MockState {
+                state: HoprState::Running,
+                balances: NodeBalances {
+                    node_wxhopr: HoprBalance::zero(),
+                    safe_wxhopr: HoprBalance::zero(),
+                    node_xdai: Balance::zero(),
+                },
+                channels: vec![],
+                peers: vec![],
+                calls: vec![],
+                next_failures: HashMap::new(),
+                failures: HashMap::new(),
+            })),
+            events: EdgeEventChannel::default(),
+            sessions: SessionRegistry::default(),
+        }
+    }
+
+    /// Sets the node and Safe balances.
+    pub fn with_balances(self, balances: NodeBalances) -> Self {
+        self.lock().balances = balances;
+        self
+    }
+
+    /// Adds an open channel to `destination` holding `balance`, without taking it from the
+    /// Safe.
+    pub fn with_channel(self, destination: Address, balance: HoprBalance) -> Self {
+        let channel = self.open_channel_entry(destination, balance);
+        self.lock().channels.push(channel);
+        self
+    }
+
+    /// Sets the connected peers.
+    pub fn with_peers(self, peers: Vec<Address>) -> Self {
+        self.lock().peers = peers;
+        self
+    }
+
+    /// Starts the node in `state`.
+    pub fn with_state(self, state: HoprState) -> Self {
+        self.lock().state = state;
+        self
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn open_channel_entry(&self, destination: Address, balance: HoprBalance) -> ChannelEntry {
+        ChannelEntry::new(
+            self.address,
+            destination,
+            balance,
+            0u32.into(),
+            ChannelStatus::Open,
+            1u32.into(),
+        )
+    }
+
+    /// Moves the node to `state`, reporting [`EdgeEvent::StateChanged`].
+    ///
+    /// Channel, withdrawal and session operations fail unless the node is
+    /// [`HoprState::Running`].
+    pub fn set_state(&self, state: HoprState) {
+        let mut inner = self.lock();
+        if inner.state != state {
+            inner.state = state;
+            self.events.send(EdgeEvent::StateChanged(state));
+        }
+    }
+
+    /// Replaces the node and Safe balances, reporting [`EdgeEvent::BalancesChanged`].
+    pub fn set_balances(&self, balances: NodeBalances) {
+        let mut inner = self.lock();
+        if inner.balances != balances {
+            inner.balances = balances.clone();
+            self.events.send(EdgeEvent::BalancesChanged(balances));
+        }
+    }
+
+    /// Replaces the connected peers, reporting who connected and who disconnected.
+    pub fn set_peers(&self, peers: Vec<Address>) {
+        let mut inner = self.lock();
+        for peer in inner.peers.iter().filter(|p| !peers.contains(p)) {
+            self.events.send(EdgeEvent::PeerDisconnected(*peer));
+        }
+        for peer in peers.iter().filter(|p| !inner.peers.contains(p)) {
+            self.events.send(EdgeEvent::PeerConnected(*peer));
+        }
+        inner.peers = peers;
+    }
+
+    /// Fails the next call to `method` with `reason`. Queued failures are used up in order
+    /// before any [`Self::fail_always`] failure applies.
+    pub fn fail_next(&self, method: MockMethod, reason: impl Into<String>) {
+        self.lock()
+            .next_failures
+            .entry(method)
+            .or_default()
+            .push_back(reason.into());
+    }
+
+    /// Fails every call to `method` with `reason` until [`Self::clear_failures`].
+    pub fn fail_always(&self, method: MockMethod, reason: impl Into<String>) {
+        self.lock().failures.insert(method, reason.into());
+    }
+
+    /// Drops all scripted failures.
+    pub fn clear_failures(&self) {
+        let mut inner = self.lock();
+        inner.next_failures.clear();
+        inner.failures.clear();
+    }
+
+    /// The calls made so far, oldest first.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.lock().calls.clone()
+    }
+
+    /// The calls made so far to `method`.
+    pub fn calls_to(&self, method: MockMethod) -> Vec<MockCall> {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|c| c.method() == method)
+            .cloned()
+            .collect()
+    }
+
+    /// Forgets the recorded calls.
+    pub fn clear_calls(&self) {
+        self.lock().calls.clear();
+    }
+
+    /// The channel to `destination` that is not yet closed, as the node now sees it.
+    pub fn channel(&self, destination: Address) -> Option<ChannelEntry> {
+        self.lock().channel(destination).ok().cloned()
+    }
+
+    /// Reports the changed balances after an operation moved funds.
+    fn balances_changed(&self, inner: &MockState) {
+        self.events
+            .send(EdgeEvent::BalancesChanged(inner.balances.clone()));
+    }
+}
+
+#[async_trait::async_trait]
+impl EdgeNodeApi for MockEdgeNode {
+    fn me_onchain(&self) -> Address {
+        self.address
+    }
+
+    fn safe_address(&self) -> Address {
+        self.safe
+    }
+
+    fn status(&self) -> HoprState {
+        self.lock().state
+    }
+
+    async fn balances(&self) -> Result<NodeBalances, HoprLibError> {
+        let mut inner = self.lock();
+        inner.call(MockCall::Balances)?;
+        Ok(inner.balances.clone())
+    }
+
+    async fn my_outgoing_channels(&self) -> Result<Vec<ChannelEntry>, HoprLibError> {
+        let mut inner = self.lock();
+        inner.call(MockCall::MyOutgoingChannels)?;
+        Ok(inner.channels.clone())
+    }
+
+    async fn open_channel(&self, target: Address, amount: HoprBalance) -> Result<(), HoprLibError> {
+        let mut inner = self.lock();
+        inner.call(MockCall::OpenChannel { target, amount })?;
+        inner.ensure_running()?;
+        if inner.channel(target).is_ok() {
+            return Err(error(format!("a channel to {target} already exists")));
+        }
+        inner.take_from_safe(amount)?;
+        inner.channels.push(self.open_channel_entry(target, amount));
+        self.events.send(EdgeEvent::ChannelOpened {
+            destination: target,
+            balance: amount,
+        });
+        self.balances_changed(&inner);
+        Ok(())
+    }
+
+    /// Recorded, and made to fail, as [`MockCall::OpenChannel`].
+    async fn submit_open_channel(
+        &self,
+        target: Address,
+        amount: HoprBalance,
+    ) -> Result<TransactionHandle, HoprLibError> {
+        self.open_channel(target, amount).await?;
+        Ok(confirmed())
+    }
+
+    async fn fund_channel(
+        &self,
+        destination: Address,
+        amount: HoprBalance,
+    ) -> Result<TransactionHandle, HoprLibError> {
+        let mut inner = self.lock();
+        inner.call(MockCall::FundChannel {
+            destination,
+            amount,
+        })?;
+        inner.ensure_running()?;
+        if inner.channel(destination)?.status != ChannelStatus::Open {
+            return Err(error(format!(
+                "the channel to {destination} is closing and cannot be funded"
+            )));
+        }
+        inner.take_from_safe(amount)?;
+        let channel = inner.channel(destination)?;
+        channel.balance = channel.balance + amount;
+        let balance = channel.balance;
+        self.events.send(EdgeEvent::ChannelFunded {
+            destination,
+            balance,
+        });
+        self.balances_changed(&inner);
+        Ok(confirmed())
+    }
+
+    async fn close_channel(&self, destination: Address) -> Result<TransactionHandle, HoprLibError> {
+        let mut inner = self.lock();
+        inner.call(MockCall::CloseChannel { destination })?;
+        inner.ensure_running()?;
+        let channel = inner.channel(destination)?;
+        if channel.status != ChannelStatus::Open {
+            return Err(error(format!(
+                "the channel to {destination} is already closing; finalize its closure instead"
+            )));
+        }
+        channel.status = ChannelStatus::PendingToClose(std::time::SystemTime::now());
+        self.events
+            .send(EdgeEvent::ChannelPendingToClose { destination });
+        Ok(confirmed())
+    }
+
+    async fn finalize_closure(
+        &self,
+        destination: Address,
+    ) -> Result<TransactionHandle, HoprLibError> {
+        let mut inner = self.lock();
+        inner.call(MockCall::FinalizeClosure { destination })?;
+        inner.ensure_running()?;
+        let channel = inner.channel(destination)?;
+        if channel.status == ChannelStatus::Open {
+            return Err(error(format!(
+                "the channel to {destination} is open; close it first"
+            )));
+        }
+        channel.status = ChannelStatus::Closed;
+        let stake = std::mem::replace(&mut channel.balance, HoprBalance::zero());
+        inner.balances.safe_wxhopr = inner.balances.safe_wxhopr + stake;
+        self.events.send(EdgeEvent::ChannelClosed { destination });
+        self.balances_changed(&inner);
+        Ok(confirmed())
+    }
+
+    async fn withdraw(
+        &self,
+        recipient: Address,
+        amount: HoprBalance,
+    ) -> Result<TransactionHandle, HoprLibError> {
+        let mut inner = self.lock();
+        inner.call(MockCall::Withdraw { recipient, amount })?;
+        inner.ensure_running()?;
+        if amount > inner.balances.node_wxhopr {
+            return Err(error(format!(
+                "the node holds {}, less than {amount}",
+                inner.balances.node_wxhopr
+            )));
+        }
+        inner.balances.node_wxhopr = inner.balances.node_wxhopr - amount;
+        self.balances_changed(&inner);
+        Ok(confirmed())
+    }
+
+    async fn connected_peer_addresses(&self) -> Result<Vec<Address>, HoprLibError> {
+        let mut inner = self.lock();
+        inner.call(MockCall::ConnectedPeerAddresses)?;
+        Ok(inner.peers.clone())
+    }
+
+    fn events(&self) -> BoxStream<'static, EdgeEvent> {
+        self.events.subscribe()
+    }
+
+    /// Opens an in-memory session that reads nothing and keeps what is written to it.
+    async fn open_session(
+        &self,
+        destination: Address,
+        _target: SessionTarget,
+        _cfg: HoprSessionClientConfig,
+    ) -> Result<Box<dyn EdgeSession>, HoprLibError> {
+        {
+            let mut inner = self.lock();
+            inner.call(MockCall::OpenSession { destination })?;
+            inner.ensure_running()?;
+        }
+        let session = futures::io::Cursor::new(Vec::new());
+        Ok(Box::new(self.sessions.register(destination, session, None)))
+    }
+
+    fn sessions(&self) -> Vec<SessionInfo> {
+        self.sessions.list()
+    }
+
+    fn close_session(&self, id: EdgeSessionId) -> Result<(), HoprLibError> {
+        self.lock().call(MockCall::CloseSession { id })?;
+        self.sessions
+            .close(id)
+            .then_some(())
+            .ok_or_else(|| error(format!("no open {id}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt as _;
+
+    use super::*;
+
+    const PEER: [u8; 20] = [0x77; 20];
+
+    fn funded() -> MockEdgeNode {
+        MockEdgeNode::default().with_balances(NodeBalances {
+            node_wxhopr: HoprBalance::new_base(5),
+            safe_wxhopr: HoprBalance::new_base(10),
+            node_xdai: Balance::zero(),
+        })
+    }
+
+    #[tokio::test]
+    async fn channels_take_their_stake_from_the_safe_and_return_it_on_closure() {
+        let node = funded();
+        let peer = PEER.into();
+
+        node.open_channel(peer, HoprBalance::new_base(4))
+            .await
+            .unwrap();
+        node.fund_channel(peer, HoprBalance::new_base(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            node.balances().await.unwrap().safe_wxhopr,
+            HoprBalance::new_base(5)
+        );
+        assert_eq!(
+            node.channel(peer).unwrap().balance,
+            HoprBalance::new_base(5)
+        );
+
+        assert!(node.finalize_closure(peer).await.is_err());
+        node.close_channel(peer).await.unwrap();
+        node.finalize_closure(peer).await.unwrap();
+        assert_eq!(
+            node.balances().await.unwrap().safe_wxhopr,
+            HoprBalance::new_base(10)
+        );
+        assert!(node.channel(peer).is_none());
+    }
+
+    #[tokio::test]
+    async fn operations_beyond_the_balance_fail() {
+        let node = funded();
+        assert!(
+            node.open_channel(PEER.into(), HoprBalance::new_base(11))
+                .await
+                .is_err()
+        );
+        assert!(
+            node.withdraw(PEER.into(), HoprBalance::new_base(6))
+                .await
+                .is_err()
+        );
+        node.withdraw(PEER.into(), HoprBalance::new_base(5))
+            .await
+            .unwrap();
+        assert_eq!(
+            node.balances().await.unwrap().node_wxhopr,
+            HoprBalance::zero()
+        );
+    }
+
+    #[tokio::test]
+    async fn scripted_failures_apply_in_order() {
+        let node = MockEdgeNode::default();
+        node.fail_next(MockMethod::Balances, "first");
+        node.fail_always(MockMethod::Balances, "always");
+
+        let reasons: Vec<_> = [
+            node.balances().await.unwrap_err(),
+            node.balances().await.unwrap_err(),
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        assert!(reasons[0].contains("first"));
+        assert!(reasons[1].contains("always"));
+
+        node.clear_failures();
+        assert!(node.balances().await.is_ok());
+        assert_eq!(node.calls_to(MockMethod::Balances).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn calls_are_recorded_with_their_arguments() {
+        let node = funded();
+        let peer = PEER.into();
+        let _ = node.open_channel(peer, HoprBalance::new_base(1)).await;
+        let _ = node.close_channel(peer).await;
+        assert_eq!(
+            node.calls(),
+            [
+                MockCall::OpenChannel {
+                    target: peer,
+                    amount: HoprBalance::new_base(1)
+                },
+                MockCall::CloseChannel { destination: peer },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn state_transitions_are_reported_and_gate_operations() {
+        let node = funded();
+        let mut events = node.events();
+
+        node.set_state(HoprState::Uninitialized);
+        assert_eq!(
+            events.next().await,
+            Some(EdgeEvent::StateChanged(HoprState::Uninitialized))
+        );
+        assert!(
+            node.open_channel(PEER.into(), HoprBalance::new_base(1))
+                .await
+                .is_err()
+        );
+
+        node.set_state(HoprState::Running);
+        node.set_peers(vec![PEER.into()]);
+        assert_eq!(
+            events.next().await,
+            Some(EdgeEvent::StateChanged(HoprState::Running))
+        );
+        assert_eq!(
+            events.next().await,
+            Some(EdgeEvent::PeerConnected(PEER.into()))
+        );
+    }
+}
@@ -0,0 +1,436 @@
+//! An in-memory chain behind [`IncentiveOperations`], for on-boarding tests.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use hopr_lib::api::types::{
+    internal::prelude::WinningProbability,
+    primitive::prelude::{Address, HoprBalance, XDaiBalance},
+};
+
+use crate::blokli::{IncentiveOperations, SafeModuleDeploymentResult, TicketStats};
+use crate::strategy::StartupCosts;
+
+/// Gas a transaction uses unless [`MockIncentiveOperations::with_gas_per_tx`] says otherwise.
+pub const DEFAULT_MOCK_GAS_PER_TX: u64 = 100_000;
+
+/// An [`IncentiveOperations`] method a fault can be injected into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum IncentiveMethod {
+    RetrieveSafe,
+    DeploySafe,
+    TicketStats,
+    MaxFeePerGas,
+    ComputeCostsToStart,
+    Balances,
+    WithdrawWxhopr,
+}
+
+/// How an injected fault fails a call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IncentiveFault {
+    /// The node cannot pay for the transaction; nothing is spent.
+    InsufficientFunds,
+    /// The transaction is mined but reverts: its gas is spent and nothing else changes.
+    Reverted,
+    /// The request times out before reaching the chain; [`crate::classify`] reports it as
+    /// [`crate::ErrorClass::Timeout`].
+    Timeout,
+}
+
+impl IncentiveFault {
+    fn into_error(self) -> anyhow::Error {
+        match self {
+            Self::InsufficientFunds => {
+                anyhow::anyhow!("insufficient funds for gas * price + value")
+            }
+            Self::Reverted => anyhow::anyhow!("transaction reverted"),
+            Self::Timeout => {
+                std::io::Error::new(std::io::ErrorKind::TimedOut, "the Blokli request timed out")
+                    .into()
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ChainState {
+    node_wxhopr: HoprBalance,
+    node_xdai: XDaiBalance,
+    safe: Option<SafeModuleDeploymentResult>,
+    safe_wxhopr: HoprBalance,
+    deployment: SafeModuleDeploymentResult,
+    key_bound: bool,
+    key_binding_fee: HoprBalance,
+    ticket_stats: TicketStats,
+    max_fee_per_gas: u128,
+    gas_per_tx: u64,
+    transactions: u64,
+    next_faults: HashMap<IncentiveMethod, VecDeque<IncentiveFault>>,
+    faults: HashMap<IncentiveMethod, IncentiveFault>,
+}
+
+impl ChainState {
+    /// The fault injected into the next call to `method`, if any.
+    fn fault(&mut self, method: IncentiveMethod) -> Option<IncentiveFault> {
+        self.next_faults
+            .get_mut(&method)
+            .and_then(VecDeque::pop_front)
+            .or_else(|| self.faults.get(&method).copied())
+    }
+
+    /// Fails a query with its injected fault.
+    fn query(&mut self, method: IncentiveMethod) -> anyhow::Result<()> {
+        self.fault(method).map_or(Ok(()), |f| Err(f.into_error()))
+    }
+
+    /// Pays for a transaction and mines it unless it reverts; the caller applies its effect
+    /// after this succeeds.
+    fn transact(&mut self, method: IncentiveMethod) -> anyhow::Result<()> {
+        let fault = self.fault(method);
+        if fault == Some(IncentiveFault::Timeout) {
+            return Err(IncentiveFault::Timeout.into_error());
+        }
+        let fee = XDaiBalance::from(
+            self.max_fee_per_gas
+                .saturating_mul(u128::from(self.gas_per_tx)),
+        );
+        if fault == Some(IncentiveFault::InsufficientFunds) || fee > self.node_xdai {
+            return Err(IncentiveFault::InsufficientFunds.into_error());
+        }
+        self.node_xdai = self.node_xdai - fee;
+        self.transactions += 1;
+        match fault {
+            Some(f) => Err(f.into_error()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// An in-memory chain for testing on-boarding against [`IncentiveOperations`].
+///
+/// Models the node's wxHOPR and xDAI, one Safe deployment, the key binding and its fee,
+/// ticket pricing and gas: every transaction costs [`Self::with_gas_per_tx`] gas at the
+/// current `max_fee_per_gas` in xDAI. Deploying the Safe moves its funding from the node
+/// wallet into it, and [`Self::bind_key`] stands in for the announcement a starting node
+/// makes. Faults are injected per method with [`Self::fail_next`] and [`Self::fail_always`].
+///
+/// Clones share the same chain, so a test can keep one to script and inspect it while the
+/// code under test owns another.
+#[derive(Clone, Debug)]
+pub struct MockIncentiveOperations {
+    inner: Arc<Mutex<ChainState>>,
+}
+
+impl Default for MockIncentiveOperations {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(ChainState {
+                node_wxhopr: HoprBalance::zero(),
+                node_xdai: XDaiBalance::zero(),
+                safe: None,
+                safe_wxhopr: HoprBalance::zero(),
+                deployment: SafeModuleDeploymentResult {
+                    safe_address: [0x5a; 20].into(),
+                    module_address: [0x3d; 20].into(),
+                },
+                key_bound: false,
+                key_binding_fee: HoprBalance::new_base(1),
+                ticket_stats: TicketStats {
+                    ticket_price: "0.000001 wxHOPR".parse().expect("valid balance"),
+                    winning_probability: WinningProbability::default(),
+                },
+                max_fee_per_gas: 1_000_000_000,
+                gas_per_tx: DEFAULT_MOCK_GAS_PER_TX,
+                transactions: 0,
+                next_faults: HashMap::new(),
+                faults: HashMap::new(),
+            })),
+        }
+    }
+}
+
+impl MockIncentiveOperations {
+    /// Sets the node wallet's wxHOPR and xDAI.
+    pub fn with_balances(self, wxhopr: HoprBalance, xdai: XDaiBalance) -> Self {
+        self.set_balances(wxhopr, xdai);
+        self
+    }
+
+    /// Starts with a Safe deployed at the mock's addresses and holding `safe_wxhopr`.
+    pub fn with_safe(self, safe_wxhopr: HoprBalance) -> Self {
+        {
+            let mut chain = self.lock();
+            chain.safe = Some(chain.deployment.clone());
+            chain.safe_wxhopr = safe_wxhopr;
+        }
+        self
+    }
+
+    /// Sets the addresses the Safe and its module are deployed at.
+    pub fn with_deployment(self, deployment: SafeModuleDeploymentResult) -> Self {
+        self.lock().deployment = deployment;
+        self
+    }
+
+    /// Starts with the node's key bound, so nothing is owed to start.
+    pub fn with_key_bound(self) -> Self {
+        self.lock().key_bound = true;
+        self
+    }
+
+    /// Sets the fee binding the key burns from the Safe. Default: 1 wxHOPR.
+    pub fn with_key_binding_fee(self, fee: HoprBalance) -> Self {
+        self.lock().key_binding_fee = fee;
+        self
+    }
+
+    /// Sets the reported ticket pricing. Default: 0.000001 wxHOPR per ticket.
+    pub fn with_ticket_stats(self, stats: TicketStats) -> Self {
+        self.lock().ticket_stats = stats;
+        self
+    }
+
+    /// Sets the gas each transaction uses. Default: [`DEFAULT_MOCK_GAS_PER_TX`].
+    pub fn with_gas_per_tx(self, gas: u64) -> Self {
+        self.lock().gas_per_tx = gas;
+        self
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ChainState> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Replaces the node wallet's wxHOPR and xDAI, as a faucet or a user's transfer would.
+    pub fn set_balances(&self, wxhopr: HoprBalance, xdai: XDaiBalance) {
+        let mut chain = self.lock();
+        chain.node_wxhopr = wxhopr;
+        chain.node_xdai = xdai;
+    }
+
+    /// Sets the chain's `max_fee_per_gas`, in wei per gas. Default: 1 gwei.
+    pub fn set_max_fee_per_gas(&self, max_fee_per_gas: u128) {
+        self.lock().max_fee_per_gas = max_fee_per_gas;
+    }
+
+    /// The wxHOPR the Safe holds.
+    pub fn safe_wxhopr(&self) -> HoprBalance {
+        self.lock().safe_wxhopr
+    }
+
+    /// How many transactions were mined, reverted ones included.
+    pub fn transactions(&self) -> u64 {
+        self.lock().transactions
+    }
+
+    /// Binds the node's key as a starting node's announcement does, burning the key-binding
+    /// fee from the Safe. Needs a deployed Safe holding the fee.
+    pub fn bind_key(&self) -> anyhow::Result<()> {
+        let mut chain = self.lock();
+        anyhow::ensure!(chain.safe.is_some(), "no Safe is deployed");
+        anyhow::ensure!(!chain.key_bound, "the key is bound already");
+        let fee = chain.key_binding_fee;
+        anyhow::ensure!(
+            fee <= chain.safe_wxhopr,
+            "the Safe holds {}, less than the key-binding fee of {fee}",
+            chain.safe_wxhopr
+        );
+        chain.safe_wxhopr = chain.safe_wxhopr - fee;
+        chain.key_bound = true;
+        Ok(())
+    }
+
+    /// Fails the next call to `method` with `fault`. Queued faults are used up in order
+    /// before any [`Self::fail_always`] fault applies.
+    pub fn fail_next(&self, method: IncentiveMethod, fault: IncentiveFault) {
+        self.lock()
+            .next_faults
+            .entry(method)
+            .or_default()
+            .push_back(fault);
+    }
+
+    /// Fails every call to `method` with `fault` until [`Self::clear_faults`].
+    pub fn fail_always(&self, method: IncentiveMethod, fault: IncentiveFault) {
+        self.lock().faults.insert(method, fault);
+    }
+
+    /// Drops all injected faults.
+    pub fn clear_faults(&self) {
+        let mut chain = self.lock();
+        chain.next_faults.clear();
+        chain.faults.clear();
+    }
+}
+
+#[async_trait::async_trait]
+impl IncentiveOperations for MockIncentiveOperations {
+    async fn retrieve_safe(&self) -> anyhow::Result<Option<SafeModuleDeploymentResult>> {
+        let mut chain = self.lock();
+        chain.query(IncentiveMethod::RetrieveSafe)?;
+        Ok(chain.safe.clone())
+    }
+
+    /// Returns the existing Safe instead of deploying a second one, as the Blokli-backed
+    /// implementation does.
+    async fn deploy_safe(
+        &self,
+        token_amount: HoprBalance,
+    ) -> anyhow::Result<SafeModuleDeploymentResult> {
+        let mut chain = self.lock();
+        if let Some(safe) = &chain.safe {
+            return Ok(safe.clone());
+        }
+        if token_amount > chain.node_wxhopr {
+            // Nothing to fund the Safe with: the transaction reverts.
+            chain.transact(IncentiveMethod::DeploySafe)?;
+            anyhow::bail!(
+                "transaction reverted: the node holds {}, less than {token_amount}",
+                chain.node_wxhopr
+            );
+        }
+        chain.transact(IncentiveMethod::DeploySafe)?;
+        chain.node_wxhopr = chain.node_wxhopr - token_amount;
+        chain.safe_wxhopr = token_amount;
+        chain.safe = Some(chain.deployment.clone());
+        Ok(chain.deployment.clone())
+    }
+
+    async fn ticket_stats(&self) -> anyhow::Result<TicketStats> {
+        let mut chain = self.lock();
+        chain.query(IncentiveMethod::TicketStats)?;
+        Ok(chain.ticket_stats)
+    }
+
+    async fn max_fee_per_gas(&self) -> anyhow::Result<u128> {
+        let mut chain = self.lock();
+        chain.query(IncentiveMethod::MaxFeePerGas)?;
+        Ok(chain.max_fee_per_gas)
+    }
+
+    async fn compute_costs_to_start(&self) -> anyhow::Result<StartupCosts> {
+        let mut chain = self.lock();
+        chain.query(IncentiveMethod::ComputeCostsToStart)?;
+        Ok(StartupCosts {
+            fee_to_start: if chain.key_bound {
+                HoprBalance::zero()
+            } else {
+                chain.key_binding_fee
+            },
+            txs_to_start: u64::from(chain.safe.is_none()) + if chain.key_bound { 0 } else { 2 },
+        })
+    }
+
+    async fn balances(&self) -> anyhow::Result<(HoprBalance, XDaiBalance)> {
+        let mut chain = self.lock();
+        chain.query(IncentiveMethod::Balances)?;
+        Ok((chain.node_wxhopr, chain.node_xdai))
+    }
+
+    async fn withdraw_wxhopr(
+        &self,
+        safe_address: Address,
+        amount: HoprBalance,
+    ) -> anyhow::Result<()> {
+        let mut chain = self.lock();
+        if amount > chain.node_wxhopr {
+            chain.transact(IncentiveMethod::WithdrawWxhopr)?;
+            anyhow::bail!(
+                "transaction reverted: the node holds {}, less than {amount}",
+                chain.node_wxhopr
+            );
+        }
+        chain.transact(IncentiveMethod::WithdrawWxhopr)?;
+        chain.node_wxhopr = chain.node_wxhopr - amount;
+        if chain
+            .safe
+            .as_ref()
+            .is_some_and(|s| s.safe_address == safe_address)
+        {
+            chain.safe_wxhopr = chain.safe_wxhopr + amount;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retry::{ErrorClass, classify};
+
+    fn xdai() -> XDaiBalance {
+        XDaiBalance::from(1_000_000_000_000_000_u64)
+    }
+
+    #[tokio::test]
+    async fn onboarding_deploys_the_safe_and_binds_the_key() {
+        let chain =
+            MockIncentiveOperations::default().with_balances(HoprBalance::new_base(10), xdai());
+
+        let costs = chain.compute_costs_to_start().await.unwrap();
+        assert_eq!(costs.txs_to_start, 3);
+        assert_eq!(costs.fee_to_start, HoprBalance::new_base(1));
+
+        let deployed = chain.deploy_safe(HoprBalance::new_base(10)).await.unwrap();
+        assert_eq!(
+            chain.retrieve_safe().await.unwrap().unwrap().safe_address,
+            deployed.safe_address
+        );
+        assert_eq!(chain.safe_wxhopr(), HoprBalance::new_base(10));
+        let (wxhopr, xdai_left) = chain.balances().await.unwrap();
+        assert_eq!(wxhopr, HoprBalance::zero());
+        assert!(xdai_left < xdai());
+
+        chain.bind_key().unwrap();
+        let costs = chain.compute_costs_to_start().await.unwrap();
+        assert_eq!(costs.txs_to_start, 0);
+        assert_eq!(costs.fee_to_start, HoprBalance::zero());
+        assert_eq!(chain.safe_wxhopr(), HoprBalance::new_base(9));
+    }
+
+    #[tokio::test]
+    async fn transactions_need_xdai_for_gas() {
+        let chain = MockIncentiveOperations::default()
+            .with_balances(HoprBalance::new_base(1), XDaiBalance::zero());
+        assert!(chain.deploy_safe(HoprBalance::new_base(1)).await.is_err());
+        assert!(chain.retrieve_safe().await.unwrap().is_none());
+        assert_eq!(chain.transactions(), 0);
+    }
+
+    #[tokio::test]
+    async fn reverted_transactions_spend_gas_only() {
+        let chain =
+            MockIncentiveOperations::default().with_balances(HoprBalance::new_base(5), xdai());
+        chain.fail_next(IncentiveMethod::WithdrawWxhopr, IncentiveFault::Reverted);
+
+        let safe = [0x5a; 20].into();
+        assert!(
+            chain
+                .withdraw_wxhopr(safe, HoprBalance::new_base(5))
+                .await
+                .is_err()
+        );
+        let (wxhopr, xdai_left) = chain.balances().await.unwrap();
+        assert_eq!(wxhopr, HoprBalance::new_base(5));
+        assert!(xdai_left < xdai());
+        assert_eq!(chain.transactions(), 1);
+
+        chain
+            .withdraw_wxhopr(safe, HoprBalance::new_base(5))
+            .await
+            .unwrap();
+        assert_eq!(chain.balances().await.unwrap().0, HoprBalance::zero());
+    }
+
+    #[tokio::test]
+    async fn timeouts_are_classified_as_such() {
+        let chain = MockIncentiveOperations::default();
+        chain.fail_always(IncentiveMethod::Balances, IncentiveFault::Timeout);
+        for _ in 0..2 {
+            let error = chain.balances().await.unwrap_err();
+            assert_eq!(classify(&error), ErrorClass::Timeout);
+        }
+        chain.clear_faults();
+        assert!(chain.balances().await.is_ok());
+    }
+}
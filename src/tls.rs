@@ -0,0 +1,422 @@
+//! Certificate pinning and custom trust roots for Blokli connections.
+//!
+//! The Blokli client validates TLS against the system roots and offers no hook to narrow
+//! that down. A [`BlokliEndpoint`](crate::BlokliEndpoint) with [`SpkiPin`]s or a
+//! [`CaBundle`] therefore has TLS terminated by edgli instead: the client talks plain HTTP
+//! to a loopback relay (see [`crate::proxy`]), which opens a TLS connection to Blokli per
+//! client connection and checks it with [`connector`]. Every request and subscription thus
+//! runs over a connection whose certificate chain was validated against the configured
+//! roots and matched a pin.
+
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use sha2::{Digest, Sha256};
+use tokio_rustls::TlsConnector;
+use tokio_rustls::rustls::client::WebPkiServerVerifier;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{
+    self, ClientConfig, DigitallySignedStruct, OtherError, RootCertStore, SignatureScheme,
+};
+
+use crate::errors::EdgliError;
+
+/// Error returned when a [`SpkiPin`] cannot be parsed.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum ParseSpkiPinError {
+    /// The input is not `sha256/<BASE64>` with a 32-byte digest.
+    #[error(
+        "invalid SPKI pin '{input}', expected sha256/<BASE64> of the SHA-256 digest of a SubjectPublicKeyInfo"
+    )]
+    InvalidFormat { input: String },
+}
+
+/// SHA-256 digest of a certificate's DER-encoded SubjectPublicKeyInfo, the pin format of
+/// HPKP (RFC 7469).
+///
+/// Pins are matched against the server's own certificate only. Intermediate and root keys
+/// cannot be pinned: a server may send certificates its chain does not go through, so their
+/// presence proves nothing.
+///
+/// Parsed from and displayed as `sha256/<BASE64>`; the `sha256/` prefix is optional when
+/// parsing. Compute one for a PEM certificate with
+/// `openssl x509 -in cert.pem -pubkey -noout | openssl pkey -pubin -outform der | openssl dgst -sha256 -binary | base64`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SpkiPin([u8; 32]);
+
+impl SpkiPin {
+    /// The pin of the public key in a DER-encoded certificate.
+    pub fn of_certificate(certificate: &[u8]) -> Result<Self, EdgliError> {
+        let (_, parsed) = x509_parser::parse_x509_certificate(certificate)
+            .map_err(|e| EdgliError::TlsError(format!("unparsable certificate: {e}")))?;
+        Ok(Self(
+            Sha256::digest(parsed.tbs_certificate.subject_pki.raw).into(),
+        ))
+    }
+}
+
+impl FromStr for SpkiPin {
+    type Err = ParseSpkiPinError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let encoded = value.trim();
+        let encoded = encoded.strip_prefix("sha256/").unwrap_or(encoded);
+        BASE64
+            .decode(encoded)
+            .ok()
+            .and_then(|digest| <[u8; 32]>::try_from(digest).ok())
+            .map(Self)
+            .ok_or_else(|| ParseSpkiPinError::InvalidFormat {
+                input: value.to_string(),
+            })
+    }
+}
+
+impl std::fmt::Display for SpkiPin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sha256/{}", BASE64.encode(self.0))
+    }
+}
+
+impl std::fmt::Debug for SpkiPin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SpkiPin({self})")
+    }
+}
+
+/// Root certificates to validate Blokli against instead of the system roots.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CaBundle {
+    certificates: Vec<CertificateDer<'static>>,
+}
+
+impl CaBundle {
+    /// Reads every certificate from PEM data; fails when there is none.
+    pub fn from_pem(pem: &[u8]) -> Result<Self, EdgliError> {
+        let certificates = CertificateDer::pem_slice_iter(pem)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| EdgliError::ConfigError(format!("invalid CA bundle: {e}")))?;
+        if certificates.is_empty() {
+            return Err(EdgliError::ConfigError(
+                "invalid CA bundle: no certificate found".into(),
+            ));
+        }
+        Ok(Self { certificates })
+    }
+
+    /// Reads every certificate from a PEM file.
+    pub fn from_pem_file(path: impl AsRef<Path>) -> Result<Self, EdgliError> {
+        let path = path.as_ref();
+        let pem = std::fs::read(path)
+            .map_err(|e| EdgliError::FileError(format!("{}: {e}", path.display())))?;
+        Self::from_pem(&pem)
+    }
+
+    /// The certificates, in file order.
+    pub fn certificates(&self) -> &[CertificateDer<'static>] {
+        &self.certificates
+    }
+}
+
+/// Raised by the handshake when the server's certificate matches no pin.
+#[derive(Debug, thiserror::Error)]
+#[error("server certificate presents {presented}, which is not pinned")]
+pub(crate) struct PinMismatch {
+    presented: SpkiPin,
+}
+
+/// Validates the chain against the roots, then requires a pin on the end-entity
+/// certificate.
+///
+/// The certificates after the end-entity one are only hints for path building, and the
+/// verifier does not tell which of them the validated path used, so they are not matched.
+#[derive(Debug)]
+struct PinningVerifier {
+    roots: Arc<WebPkiServerVerifier>,
+    pins: Vec<SpkiPin>,
+}
+
+impl ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.roots.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+        if self.pins.is_empty() {
+            return Ok(verified);
+        }
+
+        let presented = SpkiPin::of_certificate(end_entity)
+            .map_err(|e| rustls::Error::General(e.to_string()))?;
+        if self.pins.contains(&presented) {
+            Ok(verified)
+        } else {
+            Err(rustls::Error::Other(OtherError(Arc::new(PinMismatch {
+                presented,
+            }))))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer<'_>,
+        signature: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.roots
+            .verify_tls12_signature(message, certificate, signature)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer<'_>,
+        signature: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.roots
+            .verify_tls13_signature(message, certificate, signature)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.roots.supported_verify_schemes()
+    }
+}
+
+/// A TLS connector trusting `ca_bundle`, or the system roots without one, and requiring one
+/// of `pins` unless empty. Negotiates HTTP/1.1, which is what the relayed client speaks.
+pub(crate) fn connector(
+    pins: &[SpkiPin],
+    ca_bundle: Option<&CaBundle>,
+) -> Result<TlsConnector, EdgliError> {
+    let mut roots = RootCertStore::empty();
+    match ca_bundle {
+        Some(bundle) => {
+            for certificate in bundle.certificates() {
+                roots
+                    .add(certificate.clone())
+                    .map_err(|e| EdgliError::ConfigError(format!("invalid CA certificate: {e}")))?;
+            }
+        }
+        None => {
+            let native = rustls_native_certs::load_native_certs();
+            for error in &native.errors {
+                tracing::warn!(%error, "failed to load a system root certificate");
+            }
+            roots.add_parsable_certificates(native.certs);
+        }
+    }
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let roots = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+        .build()
+        .map_err(|e| EdgliError::TlsError(e.to_string()))?;
+    let mut config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| EdgliError::TlsError(e.to_string()))?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinningVerifier {
+            roots,
+            pins: pins.to_vec(),
+        }))
+        .with_no_client_auth();
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(TlsConnector::from(Arc::new(config)))
+}
+
+/// Maps a failed handshake with `host` to [`EdgliError::SpkiPinMismatch`] or
+/// [`EdgliError::TlsError`]; `None` when it failed below TLS, for example on a refused
+/// connection.
+pub(crate) fn handshake_error(host: &str, error: &std::io::Error) -> Option<EdgliError> {
+    let tls_error = error.get_ref()?.downcast_ref::<rustls::Error>()?;
+    if let rustls::Error::Other(OtherError(other)) = tls_error
+        && let Some(mismatch) = other.downcast_ref::<PinMismatch>()
+    {
+        return Some(EdgliError::SpkiPinMismatch(format!("{host}: {mismatch}")));
+    }
+    Some(EdgliError::TlsError(format!("{host}: {tls_error}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio_rustls::TlsAcceptor;
+    use tokio_rustls::rustls::ServerConfig;
+    use tokio_rustls::rustls::pki_types::PrivateKeyDer;
+
+    use super::*;
+
+    const CA_PEM: &str = include_str!("../tests/data/blokli-test-ca.pem");
+    const LEAF_PEM: &str = include_str!("../tests/data/blokli-test-leaf.pem");
+    const LEAF_KEY_PEM: &str = include_str!("../tests/data/blokli-test-leaf.key");
+    /// Computed with openssl, as in the [`SpkiPin`] docs.
+    const LEAF_PIN: &str = "sha256/qQi+Nw8pPtn6FfW2YUWP3Y6XHcvaYD3Ratt/+C+N9B4=";
+    const CA_PIN: &str = "sha256/l6Eti2CGmzuAj17/g/POvYDLICxEK2OpJ1SmhN4vSXo=";
+    /// A second CA, trusted but not pinned, and a `blokli.test` certificate it issued.
+    const ROGUE_CA_PEM: &str = include_str!("../tests/data/blokli-test-rogue-ca.pem");
+    const ROGUE_LEAF_PEM: &str = include_str!("../tests/data/blokli-test-rogue-leaf.pem");
+    const ROGUE_LEAF_KEY_PEM: &str = include_str!("../tests/data/blokli-test-rogue-leaf.key");
+
+    #[test]
+    fn pin_display_from_str_roundtrip() {
+        let pin: SpkiPin = LEAF_PIN.parse().unwrap();
+        assert_eq!(pin.to_string(), LEAF_PIN);
+        assert_eq!(LEAF_PIN["sha256/".len()..].parse::<SpkiPin>().unwrap(), pin);
+    }
+
+    #[test]
+    fn pin_rejects_other_digest_lengths() {
+        for input in ["sha256/AAAA", "sha256/not base64", ""] {
+            assert!(input.parse::<SpkiPin>().is_err(), "{input:?} accepted");
+        }
+    }
+
+    #[test]
+    fn pin_of_certificate_matches_openssl() {
+        let leaf = CaBundle::from_pem(LEAF_PEM.as_bytes()).unwrap();
+        let ca = CaBundle::from_pem(CA_PEM.as_bytes()).unwrap();
+        assert_eq!(
+            SpkiPin::of_certificate(&leaf.certificates()[0]).unwrap(),
+            LEAF_PIN.parse().unwrap()
+        );
+        assert_eq!(
+            SpkiPin::of_certificate(&ca.certificates()[0]).unwrap(),
+            CA_PIN.parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn ca_bundle_requires_a_certificate() {
+        assert!(matches!(
+            CaBundle::from_pem(b"no certificates here"),
+            Err(EdgliError::ConfigError(_))
+        ));
+    }
+
+    /// Serves `chain` for one connection and echoes what follows.
+    async fn tls_server(chain: &str, key: &str) -> std::net::SocketAddr {
+        let certificates = CaBundle::from_pem(chain.as_bytes())
+            .unwrap()
+            .certificates()
+            .to_vec();
+        let key = PrivateKeyDer::from_pem_slice(key.as_bytes()).unwrap();
+        let config =
+            ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .unwrap()
+                .with_no_client_auth()
+                .with_single_cert(certificates, key)
+                .unwrap();
+        let acceptor = TlsAcceptor::from(Arc::new(config));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            if let Ok(stream) = acceptor.accept(stream).await {
+                let (mut reader, mut writer) = tokio::io::split(stream);
+                let _ = tokio::io::copy(&mut reader, &mut writer).await;
+            }
+        });
+        addr
+    }
+
+    async fn handshake(pins: &[SpkiPin]) -> std::io::Result<()> {
+        handshake_with(LEAF_PEM, LEAF_KEY_PEM, CA_PEM, pins).await
+    }
+
+    /// Connects to a server presenting `chain`, trusting `roots` and requiring `pins`.
+    async fn handshake_with(
+        chain: &str,
+        key: &str,
+        roots: &str,
+        pins: &[SpkiPin],
+    ) -> std::io::Result<()> {
+        let roots = CaBundle::from_pem(roots.as_bytes()).unwrap();
+        let connector = connector(pins, Some(&roots)).unwrap();
+        let tcp = tokio::net::TcpStream::connect(tls_server(chain, key).await).await?;
+        let mut stream = connector
+            .connect(ServerName::try_from("blokli.test").unwrap(), tcp)
+            .await?;
+        stream.write_all(b"ping").await?;
+        let mut echoed = [0u8; 4];
+        stream.read_exact(&mut echoed).await?;
+        assert_eq!(&echoed, b"ping");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn handshake_accepts_a_pinned_key() {
+        handshake(&[]).await.unwrap();
+        handshake(&[LEAF_PIN.parse().unwrap()]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn handshake_rejects_an_unpinned_key() {
+        let other = SpkiPin([7; 32]);
+        let error = handshake(&[other]).await.unwrap_err();
+        assert!(matches!(
+            handshake_error("blokli.test", &error),
+            Some(EdgliError::SpkiPinMismatch(message)) if message.contains(LEAF_PIN)
+        ));
+    }
+
+    /// A chain from an unpinned CA must not pass by appending a pinned certificate that its
+    /// path does not go through.
+    #[tokio::test]
+    async fn handshake_ignores_pinned_certificates_outside_the_path() {
+        let chain = format!("{ROGUE_LEAF_PEM}{CA_PEM}");
+        handshake_with(&chain, ROGUE_LEAF_KEY_PEM, ROGUE_CA_PEM, &[])
+            .await
+            .unwrap();
+
+        let error = handshake_with(
+            &chain,
+            ROGUE_LEAF_KEY_PEM,
+            ROGUE_CA_PEM,
+            &[CA_PIN.parse().unwrap()],
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(
+            handshake_error("blokli.test", &error),
+            Some(EdgliError::SpkiPinMismatch(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn handshake_rejects_an_untrusted_chain() {
+        let connector = connector(&[], None).unwrap();
+        let tcp = tokio::net::TcpStream::connect(tls_server(LEAF_PEM, LEAF_KEY_PEM).await)
+            .await
+            .unwrap();
+        let error = connector
+            .connect(ServerName::try_from("blokli.test").unwrap(), tcp)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            handshake_error("blokli.test", &error),
+            Some(EdgliError::TlsError(_))
+        ));
+        assert!(
+            handshake_error("blokli.test", &std::io::ErrorKind::ConnectionRefused.into()).is_none()
+        );
+    }
+}
@@ -5,9 +5,24 @@
 //! trait rather than the concrete [`crate::Edgli`] type, consumers can
 //! substitute a mock or stub implementation in unit tests without requiring a
 //! live HOPR node or blockchain connection.
+//!
+//! Operations that submit an on-chain transaction return once it is submitted, with a
+//! [`TransactionHandle`] to await its confirmation. [`EdgeNodeApi::open_channel`] keeps
+//! waiting for confirmation itself; [`EdgeNodeApi::submit_open_channel`] is its counterpart
+//! returning a handle.
+//!
+//! [`EdgeNodeApi::events`] reports changes as [`EdgeEvent`]s instead of leaving consumers
+//! to poll for them.
+//!
+//! Sessions opened with [`EdgeNodeApi::open_session`] are tracked by a
+//! [`crate::session::SessionRegistry`], which lists them with their traffic and closes them
+//! by id.
 
+use futures::channel::oneshot;
+use futures::stream::BoxStream;
 use hopr_lib::{
     api::{
+        chain::ChainReceipt,
         node::HoprState,
         types::{
             internal::channels::ChannelEntry,
@@ -15,10 +30,14 @@ use hopr_lib::{
         },
     },
     errors::HoprLibError,
+    exports::transport::{HoprSessionClientConfig, SessionTarget},
 };
 
+use crate::events::EdgeEvent;
+use crate::session::{EdgeSession, EdgeSessionId, SessionInfo};
+
 /// All balance information for the node wallet and its linked Safe.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct NodeBalances {
     /// WxHOPR token balance held directly by the node wallet.
     pub node_wxhopr: HoprBalance,
@@ -28,11 +47,49 @@ pub struct NodeBalances {
     pub node_xdai: Balance<XDai>,
 }
 
+/// A submitted on-chain transaction.
+///
+/// Dropping the handle does not cancel the transaction; it only stops waiting for it.
+#[derive(Debug)]
+pub struct TransactionHandle {
+    confirmation: oneshot::Receiver<std::result::Result<ChainReceipt, HoprLibError>>,
+}
+
+impl TransactionHandle {
+    pub(crate) fn pending(
+        confirmation: oneshot::Receiver<std::result::Result<ChainReceipt, HoprLibError>>,
+    ) -> Self {
+        Self { confirmation }
+    }
+
+    /// A handle for a transaction that is already confirmed, for stub implementations.
+    pub fn confirmed(receipt: ChainReceipt) -> Self {
+        let (sender, confirmation) = oneshot::channel();
+        let _ = sender.send(Ok(receipt));
+        Self { confirmation }
+    }
+
+    /// Waits until the transaction is confirmed on chain and returns its receipt.
+    pub async fn confirmation(self) -> std::result::Result<ChainReceipt, HoprLibError> {
+        self.confirmation.await.unwrap_or_else(|_| {
+            Err(HoprLibError::GeneralError(
+                "the transaction was abandoned before it was confirmed".into(),
+            ))
+        })
+    }
+}
+
 /// High-level edge node API for consumers.
 ///
 /// All methods delegate to the underlying [`hopr_lib`] trait implementations
 /// on the concrete [`crate::Edgli`] type.  Test code may implement this trait
 /// with stubs or mocks to avoid requiring network connectivity.
+///
+/// Two operations one might expect are deliberately missing:
+///
+/// - Withdrawing from the Safe. The node's module may move Safe funds into channels, but only
+///   the Safe's owners can withdraw them; [`Self::withdraw`] transfers from the node wallet.
+/// - Redeeming tickets. An edge node only sends packets, so it never holds tickets to redeem.
 #[async_trait::async_trait]
 pub trait EdgeNodeApi: Send + Sync {
     // --- Identity ---
@@ -70,6 +127,51 @@ pub trait EdgeNodeApi: Send + Sync {
         amount: HoprBalance,
     ) -> std::result::Result<(), HoprLibError>;
 
+    /// Open a payment channel to `target` funded with `amount` WxHOPR from the Safe, returning
+    /// once the transaction is submitted, like the other channel operations.
+    ///
+    /// Fails if a channel to `target` is already open or closing.
+    async fn submit_open_channel(
+        &self,
+        target: Address,
+        amount: HoprBalance,
+    ) -> std::result::Result<TransactionHandle, HoprLibError>;
+
+    /// Add `amount` WxHOPR from the Safe to the open channel to `destination`.
+    async fn fund_channel(
+        &self,
+        destination: Address,
+        amount: HoprBalance,
+    ) -> std::result::Result<TransactionHandle, HoprLibError>;
+
+    /// Start closing the open channel to `destination`.
+    ///
+    /// The channel stays `PendingToClose` for the closure grace period; then
+    /// [`Self::finalize_closure`] returns its stake to the Safe.
+    async fn close_channel(
+        &self,
+        destination: Address,
+    ) -> std::result::Result<TransactionHandle, HoprLibError>;
+
+    /// Finish closing the `PendingToClose` channel to `destination` once its grace period
+    /// has passed, returning its stake to the Safe.
+    async fn finalize_closure(
+        &self,
+        destination: Address,
+    ) -> std::result::Result<TransactionHandle, HoprLibError>;
+
+    // --- Funds ---
+
+    /// Transfer `amount` WxHOPR from the node wallet to `recipient`.
+    ///
+    /// Only the node wallet can be withdrawn from: the node's module may move Safe funds
+    /// into channels, but only the Safe's owners can withdraw them.
+    async fn withdraw(
+        &self,
+        recipient: Address,
+        amount: HoprBalance,
+    ) -> std::result::Result<TransactionHandle, HoprLibError>;
+
     // --- Peer discovery ---
 
     /// On-chain addresses of all currently connected peers.
@@ -77,6 +179,34 @@ pub trait EdgeNodeApi: Send + Sync {
     /// Combines the transport-layer peer list with the chain-key lookup so
     /// callers receive Ethereum addresses directly.
     async fn connected_peer_addresses(&self) -> std::result::Result<Vec<Address>, HoprLibError>;
+
+    // --- Events ---
+
+    /// Changes to the node's channels, balances, peers and state from now on.
+    ///
+    /// The stream only reports changes; read the current values with the methods above.
+    fn events(&self) -> BoxStream<'static, EdgeEvent>;
+
+    // --- Sessions ---
+
+    /// Open a session to `target` through the exit node `destination`.
+    ///
+    /// `cfg` carries the forward and return routing options. The session stays listed in
+    /// [`Self::sessions`] until the returned handle is dropped or [`Self::close_session`]
+    /// closes it.
+    async fn open_session(
+        &self,
+        destination: Address,
+        target: SessionTarget,
+        cfg: HoprSessionClientConfig,
+    ) -> std::result::Result<Box<dyn EdgeSession>, HoprLibError>;
+
+    /// The sessions opened through [`Self::open_session`] that are still open, oldest first.
+    fn sessions(&self) -> Vec<SessionInfo>;
+
+    /// Close the session `id` right away and release its hold on shutdown: its handle reads
+    /// end-of-stream and refuses further writes.
+    fn close_session(&self, id: EdgeSessionId) -> std::result::Result<(), HoprLibError>;
 }
 
 #[cfg(all(feature = "runtime-tokio", feature = "blokli"))]
@@ -84,11 +214,100 @@ mod impl_edgli {
     use super::*;
     use crate::client::Edgli;
     use hopr_lib::api::{
-        chain::ChainKeyOperations,
+        chain::{ChainKeyOperations, ChainWriteAccountOperations, ChainWriteChannelOperations},
         node::{HasChainApi, HasTransportApi, HoprNodeOperations, IncentiveChannelOperations},
-        types::primitive::prelude::WxHOPR,
+        types::{internal::channels::ChannelStatus, primitive::prelude::WxHOPR},
     };
 
+    fn general_error(e: impl std::fmt::Display) -> HoprLibError {
+        HoprLibError::GeneralError(e.to_string())
+    }
+
+    /// The outgoing channel to `destination` that is not yet closed.
+    async fn outgoing_channel(
+        node: &Edgli,
+        destination: Address,
+    ) -> std::result::Result<ChannelEntry, HoprLibError> {
+        node.my_outgoing_channels()
+            .await?
+            .into_iter()
+            .find(|c| c.destination == destination && c.status != ChannelStatus::Closed)
+            .ok_or_else(|| general_error(format!("no outgoing channel to {destination}")))
+    }
+
+    /// Submits `transaction` from a task of its own, so it is confirmed whether or not the
+    /// returned handle is awaited.
+    ///
+    /// `transaction` signals through its sender once the transaction is submitted; failing
+    /// before that fails the call. Refuses to submit while gas is above the node's cap. Once
+    /// the transaction confirms, the node's [`EdgeNodeApi::events`] are brought up to date.
+    async fn submit<F, Fut>(
+        node: &Edgli,
+        operation: &'static str,
+        transaction: F,
+    ) -> std::result::Result<TransactionHandle, HoprLibError>
+    where
+        F: FnOnce(oneshot::Sender<()>) -> Fut,
+        Fut: Future<Output = std::result::Result<ChainReceipt, HoprLibError>> + Send + 'static,
+    {
+        node.check_gas_price().await.map_err(general_error)?;
+
+        let (submitted, on_submitted) = oneshot::channel();
+        let (confirmed, confirmation) = oneshot::channel();
+        let transaction = crate::metrics::blokli_request(
+            operation,
+            node.blokli_endpoint(),
+            transaction(submitted),
+        );
+        let resync = node.event_resync();
+        tokio::spawn(async move {
+            let confirmation = transaction.await;
+            if confirmation.is_ok() {
+                resync.notify_one();
+            }
+            let _ = confirmed.send(confirmation);
+        });
+
+        match on_submitted.await {
+            Ok(()) => Ok(TransactionHandle::pending(confirmation)),
+            // Dropping the sender unsent means the submission itself failed.
+            Err(_) => Err(TransactionHandle::pending(confirmation)
+                .confirmation()
+                .await
+                .err()
+                .unwrap_or_else(|| general_error(format!("{operation} was not submitted")))),
+        }
+    }
+
+    /// [`submit`] for a channel transaction, recording the gas it used into the node's
+    /// [`crate::gas::ObservedGas`] once it confirms.
+    async fn submit_channel_transaction<F, Fut>(
+        node: &Edgli,
+        operation: &'static str,
+        transaction: F,
+    ) -> std::result::Result<TransactionHandle, HoprLibError>
+    where
+        F: FnOnce(oneshot::Sender<()>) -> Fut,
+        Fut: Future<Output = std::result::Result<ChainReceipt, HoprLibError>> + Send + 'static,
+    {
+        let measurement = node.start_gas_measurement().await;
+        let hopr = node.as_hopr();
+        submit(node, operation, move |submitted| {
+            let transaction = transaction(submitted);
+            async move {
+                let receipt = transaction.await?;
+                if let Some(measurement) = measurement
+                    && let Ok(xdai) =
+                        IncentiveChannelOperations::get_balance::<XDai>(hopr.as_ref()).await
+                {
+                    measurement.record(xdai);
+                }
+                Ok(receipt)
+            }
+        })
+        .await
+    }
+
     #[async_trait::async_trait]
     impl EdgeNodeApi for Edgli {
         fn me_onchain(&self) -> Address {
@@ -115,6 +334,7 @@ mod impl_edgli {
             let node_xdai = IncentiveChannelOperations::get_balance::<XDai>(hopr.as_ref())
                 .await
                 .map_err(|e| HoprLibError::GeneralError(e.to_string()))?;
+            crate::metrics::record_balances(node_wxhopr, safe_wxhopr, node_xdai);
             Ok(NodeBalances {
                 node_wxhopr,
                 safe_wxhopr,
@@ -144,6 +364,123 @@ mod impl_edgli {
                 .map_err(|e| HoprLibError::GeneralError(e.to_string()))
         }
 
+        async fn submit_open_channel(
+            &self,
+            target: Address,
+            amount: HoprBalance,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            let exists = self
+                .my_outgoing_channels()
+                .await?
+                .iter()
+                .any(|c| c.destination == target && c.status != ChannelStatus::Closed);
+            if exists {
+                return Err(general_error(format!(
+                    "a channel to {target} already exists"
+                )));
+            }
+            let hopr = self.as_hopr();
+            submit_channel_transaction(self, "open_channel", move |submitted| async move {
+                let pending = hopr
+                    .chain_api()
+                    .open_channel(&target, amount)
+                    .await
+                    .map_err(general_error)?;
+                let _ = submitted.send(());
+                pending.await.map_err(general_error)
+            })
+            .await
+        }
+
+        async fn fund_channel(
+            &self,
+            destination: Address,
+            amount: HoprBalance,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            let channel = outgoing_channel(self, destination).await?;
+            if channel.status != ChannelStatus::Open {
+                return Err(general_error(format!(
+                    "the channel to {destination} is closing and cannot be funded"
+                )));
+            }
+            let hopr = self.as_hopr();
+            submit_channel_transaction(self, "fund_channel", move |submitted| async move {
+                let pending = hopr
+                    .chain_api()
+                    .fund_channel(&channel.get_id(), amount)
+                    .await
+                    .map_err(general_error)?;
+                let _ = submitted.send(());
+                pending.await.map_err(general_error)
+            })
+            .await
+        }
+
+        async fn close_channel(
+            &self,
+            destination: Address,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            let channel = outgoing_channel(self, destination).await?;
+            if channel.status != ChannelStatus::Open {
+                return Err(general_error(format!(
+                    "the channel to {destination} is already closing; finalize its closure instead"
+                )));
+            }
+            let hopr = self.as_hopr();
+            submit_channel_transaction(self, "close_channel", move |submitted| async move {
+                let pending = hopr
+                    .chain_api()
+                    .close_channel(&channel.get_id())
+                    .await
+                    .map_err(general_error)?;
+                let _ = submitted.send(());
+                pending.await.map_err(general_error)
+            })
+            .await
+        }
+
+        async fn finalize_closure(
+            &self,
+            destination: Address,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            let channel = outgoing_channel(self, destination).await?;
+            if channel.status == ChannelStatus::Open {
+                return Err(general_error(format!(
+                    "the channel to {destination} is open; close it first"
+                )));
+            }
+            // Closing a channel that is already pending to close finalizes the closure.
+            let hopr = self.as_hopr();
+            submit_channel_transaction(self, "finalize_closure", move |submitted| async move {
+                let pending = hopr
+                    .chain_api()
+                    .close_channel(&channel.get_id())
+                    .await
+                    .map_err(general_error)?;
+                let _ = submitted.send(());
+                pending.await.map_err(general_error)
+            })
+            .await
+        }
+
+        async fn withdraw(
+            &self,
+            recipient: Address,
+            amount: HoprBalance,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            let hopr = self.as_hopr();
+            submit(self, "withdraw", move |submitted| async move {
+                let pending = hopr
+                    .chain_api()
+                    .withdraw(amount, &recipient)
+                    .await
+                    .map_err(general_error)?;
+                let _ = submitted.send(());
+                pending.await.map_err(general_error)
+            })
+            .await
+        }
+
         async fn connected_peer_addresses(
             &self,
         ) -> std::result::Result<Vec<Address>, HoprLibError> {
@@ -165,12 +502,43 @@ mod impl_edgli {
             }
             Ok(addresses)
         }
+
+        fn events(&self) -> BoxStream<'static, EdgeEvent> {
+            self.event_channel().subscribe()
+        }
+
+        async fn open_session(
+            &self,
+            destination: Address,
+            target: SessionTarget,
+            cfg: HoprSessionClientConfig,
+        ) -> std::result::Result<Box<dyn EdgeSession>, HoprLibError> {
+            let lease = self.session_lease().map_err(general_error)?;
+            let session = self.as_hopr().connect_to(destination, target, cfg).await?;
+            Ok(Box::new(self.session_registry().register(
+                destination,
+                session,
+                Some(Box::new(lease)),
+            )))
+        }
+
+        fn sessions(&self) -> Vec<SessionInfo> {
+            self.session_registry().list()
+        }
+
+        fn close_session(&self, id: EdgeSessionId) -> std::result::Result<(), HoprLibError> {
+            self.session_registry()
+                .close(id)
+                .then_some(())
+                .ok_or_else(|| general_error(format!("no open {id}")))
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use hopr_lib::api::types::internal::channels::ChannelStatus;
     use std::sync::Arc;
 
     /// A minimal stub that satisfies [`EdgeNodeApi`] for unit testing.
@@ -183,6 +551,22 @@ mod tests {
         node_xdai: Balance<XDai>,
         channels: Vec<ChannelEntry>,
         peers: Vec<Address>,
+        events: crate::events::EdgeEventChannel,
+        sessions: crate::session::SessionRegistry,
+    }
+
+    impl StubEdgeNode {
+        fn channel_with(
+            &self,
+            destination: Address,
+            status: ChannelStatus,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            self.channels
+                .iter()
+                .find(|c| c.destination == destination && c.status == status)
+                .map(|_| TransactionHandle::confirmed(ChainReceipt::default()))
+                .ok_or_else(|| HoprLibError::GeneralError(format!("no channel to {destination}")))
+        }
     }
 
     impl Default for StubEdgeNode {
@@ -196,6 +580,8 @@ mod tests {
                 node_xdai: Balance::zero(),
                 channels: vec![],
                 peers: vec![],
+                events: Default::default(),
+                sessions: Default::default(),
             }
         }
     }
@@ -236,11 +622,82 @@ mod tests {
             Ok(())
         }
 
+        async fn submit_open_channel(
+            &self,
+            _target: Address,
+            _amount: HoprBalance,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            Ok(TransactionHandle::confirmed(ChainReceipt::default()))
+        }
+
+        async fn fund_channel(
+            &self,
+            destination: Address,
+            _amount: HoprBalance,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            self.channel_with(destination, ChannelStatus::Open)
+        }
+
+        async fn close_channel(
+            &self,
+            destination: Address,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            self.channel_with(destination, ChannelStatus::Open)
+        }
+
+        async fn finalize_closure(
+            &self,
+            destination: Address,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            match self.channels.iter().find(|c| c.destination == destination) {
+                Some(c) if matches!(c.status, ChannelStatus::PendingToClose(_)) => {
+                    Ok(TransactionHandle::confirmed(ChainReceipt::default()))
+                }
+                _ => Err(HoprLibError::GeneralError("not closing".into())),
+            }
+        }
+
+        async fn withdraw(
+            &self,
+            _recipient: Address,
+            amount: HoprBalance,
+        ) -> std::result::Result<TransactionHandle, HoprLibError> {
+            if amount > self.node_wxhopr {
+                return Err(HoprLibError::GeneralError("insufficient funds".into()));
+            }
+            Ok(TransactionHandle::confirmed(ChainReceipt::default()))
+        }
+
         async fn connected_peer_addresses(
             &self,
         ) -> std::result::Result<Vec<Address>, HoprLibError> {
             Ok(self.peers.clone())
         }
+
+        fn events(&self) -> BoxStream<'static, EdgeEvent> {
+            self.events.subscribe()
+        }
+
+        async fn open_session(
+            &self,
+            destination: Address,
+            _target: SessionTarget,
+            _cfg: HoprSessionClientConfig,
+        ) -> std::result::Result<Box<dyn EdgeSession>, HoprLibError> {
+            let session = futures::io::Cursor::new(Vec::new());
+            Ok(Box::new(self.sessions.register(destination, session, None)))
+        }
+
+        fn sessions(&self) -> Vec<SessionInfo> {
+            self.sessions.list()
+        }
+
+        fn close_session(&self, id: EdgeSessionId) -> std::result::Result<(), HoprLibError> {
+            self.sessions
+                .close(id)
+                .then_some(())
+                .ok_or_else(|| HoprLibError::GeneralError(format!("no open {id}")))
+        }
     }
 
     #[test]
@@ -284,6 +741,57 @@ mod tests {
                 .await
                 .is_ok()
         );
+        let handle = node
+            .submit_open_channel(Address::default(), HoprBalance::zero())
+            .await
+            .unwrap();
+        assert!(handle.confirmation().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn stub_channel_operations_need_a_channel() {
+        let peer: Address = [0x44u8; 20].into();
+        let node = StubEdgeNode::default();
+        assert!(
+            node.fund_channel(peer, HoprBalance::new_base(1))
+                .await
+                .is_err()
+        );
+        assert!(node.close_channel(peer).await.is_err());
+        assert!(node.finalize_closure(peer).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn stub_withdraw_is_limited_to_the_node_balance() {
+        let node = StubEdgeNode {
+            node_wxhopr: HoprBalance::new_base(2),
+            ..Default::default()
+        };
+        let handle = node
+            .withdraw(Address::default(), HoprBalance::new_base(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            handle.confirmation().await.unwrap(),
+            ChainReceipt::default()
+        );
+        assert!(
+            node.withdraw(Address::default(), HoprBalance::new_base(3))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn abandoned_transaction_reports_an_error() {
+        let (sender, confirmation) = oneshot::channel();
+        drop(sender);
+        assert!(
+            TransactionHandle::pending(confirmation)
+                .confirmation()
+                .await
+                .is_err()
+        );
     }
 
     #[tokio::test]
@@ -292,6 +800,34 @@ mod tests {
         assert!(node.connected_peer_addresses().await.unwrap().is_empty());
     }
 
+    #[tokio::test]
+    async fn stub_events_stream_what_the_stub_sends() {
+        use futures::StreamExt as _;
+
+        let node = StubEdgeNode::default();
+        let mut events = node.events();
+        let event = EdgeEvent::PeerConnected([0x44u8; 20].into());
+        node.events.send(event.clone());
+        assert_eq!(events.next().await, Some(event));
+    }
+
+    #[test]
+    fn stub_sessions_are_listed_until_closed() {
+        let node = StubEdgeNode::default();
+        let exit: Address = [0x55u8; 20].into();
+        let session = node
+            .sessions
+            .register(exit, futures::io::Cursor::new(vec![]), None);
+
+        let listed = node.sessions();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].destination, exit);
+
+        assert!(node.close_session(session.id()).is_ok());
+        assert!(node.sessions().is_empty());
+        assert!(node.close_session(session.id()).is_err());
+    }
+
     #[test]
     fn arc_stub_direct_dispatch_works() {
         // Call trait methods on the concrete type via Arc (no dyn overhead needed).
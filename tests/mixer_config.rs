@@ -23,8 +23,8 @@ fn missing_mixer_section_deserialises_as_default() {
     assert_eq!(parsed.protocol.mixer, MixerConfig::default());
 }
 
-/// Verifies the edge-client specific mixer override: 0 ms min and 1 ms range,
-/// matching the hardcoded values applied in main.rs after YAML deserialisation.
+/// Verifies the edge-client specific mixer defaults: 0 ms min and 1 ms range, which
+/// `edgli::config::parse_config` applies unless `edge.custom_protocol` is set.
 #[test]
 fn edge_client_mixer_defaults() {
     let edge_mixer = MixerConfig {
@@ -40,3 +40,55 @@ fn edge_client_mixer_defaults() {
     assert_eq!(edge_mixer.min_delay, Duration::ZERO);
     assert_eq!(edge_mixer.delay_range, Duration::from_millis(1));
 }
+
+/// A configuration written before `edge.custom_protocol` existed keeps running with the edge
+/// mixer delays, whatever its `protocol.mixer` says.
+#[test]
+fn existing_configs_get_the_edge_mixer_defaults() {
+    let mut cfg = HoprLibConfig::default();
+    cfg.protocol.mixer.min_delay = Duration::from_millis(5);
+    cfg.protocol.mixer.delay_range = Duration::from_millis(20);
+    let parsed = edgli::config::parse_config(&serde_yaml::to_string(&cfg).unwrap()).unwrap();
+    assert_eq!(
+        parsed.hopr.protocol.mixer.min_delay,
+        edgli::config::EDGE_MIXER_MIN_DELAY
+    );
+    assert_eq!(
+        parsed.hopr.protocol.mixer.delay_range,
+        edgli::config::EDGE_MIXER_DELAY_RANGE
+    );
+    assert_eq!(
+        parsed.hopr.protocol.path_planner,
+        edgli::latency_path_planner_config(edgli::config::DEFAULT_MIN_ACK_RATE)
+    );
+}
+
+#[test]
+fn configured_mixer_delays_are_honoured_with_custom_protocol() {
+    let mut cfg = HoprLibConfig::default();
+    cfg.protocol.mixer.min_delay = Duration::from_millis(5);
+    cfg.protocol.mixer.delay_range = Duration::from_millis(20);
+    let yaml = format!(
+        "{}edge:\n  custom_protocol: true\n",
+        serde_yaml::to_string(&cfg).unwrap()
+    );
+    let parsed = edgli::config::parse_config(&yaml).unwrap();
+    assert_eq!(
+        parsed.hopr.protocol.mixer.min_delay,
+        Duration::from_millis(5)
+    );
+    assert_eq!(
+        parsed.hopr.protocol.mixer.delay_range,
+        Duration::from_millis(20)
+    );
+
+    let defaulted = edgli::config::parse_config("{}").unwrap();
+    assert_eq!(
+        defaulted.hopr.protocol.mixer.min_delay,
+        edgli::config::EDGE_MIXER_MIN_DELAY
+    );
+    assert_eq!(
+        defaulted.hopr.protocol.mixer.delay_range,
+        edgli::config::EDGE_MIXER_DELAY_RANGE
+    );
+}